@@ -0,0 +1,220 @@
+// Field, curve and ECDSA timings.
+//
+//     cargo bench --bench ecc
+//     cargo bench --bench ecc -- field/      (only benchmarks whose name contains field/)
+//
+// The batch/ group is the one to compare with and without --features parallel to see how the
+// rayon paths scale with the number of cores
+//
+// Inputs come from a ChaCha20 rng with a fixed seed, so runs are comparable with each other
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use galactic_credit::ecc::curve::{
+    EcPoint, WnafTable, batch_double, mul_interleaved, sum_points, validate_each,
+};
+use galactic_credit::ecc::curves::SECP256K1;
+use galactic_credit::ecc::field::{FieldElement, Pow};
+use galactic_credit::ecc::secp256k1::{
+    PrivateKey, S256Field, S256Point, Scalar, Signature, g_mul, schnorr, verify_batch,
+};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+use rug::Integer;
+use rug::integer::Order;
+
+const SEED: u64 = 0x6761_6c61_6374_6963;
+
+// a uniform-ish value below modulus, good enough for benchmark inputs
+fn random_below(rng: &mut ChaCha20Rng, modulus: &Integer) -> Integer {
+    let mut bytes = [0u8; 32];
+    rng.fill_bytes(&mut bytes);
+    Integer::from_digits(&bytes, Order::Msf) % modulus
+}
+
+fn field(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(SEED);
+    let p = SECP256K1.prime();
+    let a = FieldElement::new(random_below(&mut rng, &p), p.clone());
+    let b = FieldElement::new(random_below(&mut rng, &p), p.clone());
+    let s256_a = S256Field::try_from(a.clone()).unwrap();
+    let sqrt_exp = (p.clone() + 1u8) >> 2u32;
+
+    let mut group = c.benchmark_group("field");
+    group.bench_function("add", |bench| bench.iter(|| black_box(&a) + black_box(&b)));
+    group.bench_function("mul", |bench| bench.iter(|| black_box(&a) * black_box(&b)));
+    group.bench_function("div", |bench| bench.iter(|| black_box(&a) / black_box(&b)));
+    // the addition chains against plain pow_mod for the same exponents
+    group.bench_function("inverse_chain", |bench| {
+        bench.iter(|| black_box(&s256_a).invert())
+    });
+    group.bench_function("inverse_pow", |bench| {
+        bench.iter(|| black_box(&a).pow(p.clone() - 2u8))
+    });
+    group.bench_function("sqrt_chain", |bench| {
+        bench.iter(|| black_box(&s256_a).sqrt())
+    });
+    group.bench_function("sqrt_pow", |bench| {
+        bench.iter(|| black_box(&a).pow(sqrt_exp.clone()))
+    });
+    group.finish();
+}
+
+fn curve(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(SEED);
+    let g = SECP256K1.generator();
+    let p = &g * random_below(&mut rng, &SECP256K1.order());
+    let k = random_below(&mut rng, &SECP256K1.order());
+    let s256_g = S256Point::get_generator();
+    let s256_p = s256_g.mul_vartime(&random_below(&mut rng, &SECP256K1.order()));
+
+    let mut group = c.benchmark_group("curve");
+    group.bench_function("add", |bench| bench.iter(|| black_box(&g) + black_box(&p)));
+    group.bench_function("double", |bench| {
+        bench.iter(|| black_box(&p) + black_box(&p))
+    });
+    group.bench_function("mul_g_comb", |bench| bench.iter(|| g_mul(black_box(&k))));
+    group.bench_function("mul_wnaf_fresh", |bench| {
+        bench.iter(|| WnafTable::new(black_box(&p), 5).mul(black_box(&k)))
+    });
+    let table = WnafTable::new(&p, 5);
+    group.bench_function("mul_wnaf_precomputed", |bench| {
+        bench.iter(|| table.mul(black_box(&k)))
+    });
+    group.bench_function("mul_g_vartime", |bench| {
+        bench.iter(|| s256_g.mul_vartime(black_box(&k)))
+    });
+    // double-and-add against the GLV split mul_vartime uses for points other than G
+    group.bench_function("mul_plain", |bench| {
+        bench.iter(|| &s256_p * black_box(&k).clone())
+    });
+    group.bench_function("mul_glv", |bench| {
+        bench.iter(|| s256_p.mul_vartime(black_box(&k)))
+    });
+    group.finish();
+}
+
+fn secp256k1(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(SEED);
+    let key = PrivateKey::generate(&mut rng);
+    let point = S256Point::from(key.public_key());
+    let compressed = point.sec(true);
+    let uncompressed = point.sec(false);
+    let z = random_below(&mut rng, &SECP256K1.order());
+    let sig = key.sign(&z);
+
+    let mut group = c.benchmark_group("secp256k1");
+    group.bench_function("sec_parse_compressed", |bench| {
+        bench.iter(|| S256Point::from_sec(black_box(&compressed)))
+    });
+    group.bench_function("sec_parse_uncompressed", |bench| {
+        bench.iter(|| S256Point::from_sec(black_box(&uncompressed)))
+    });
+    group.bench_function("ecdsa_sign", |bench| bench.iter(|| key.sign(black_box(&z))));
+    group.bench_function("ecdsa_verify", |bench| {
+        bench.iter(|| point.verify(black_box(&z), black_box(&sig)))
+    });
+    // the verify equation as two separate multiplications, against verify's Shamir's trick
+    group.bench_function("ecdsa_verify_separate", |bench| {
+        bench.iter(|| {
+            let s_inv = black_box(&sig).s.invert().unwrap();
+            let u = &Scalar::new(black_box(&z).clone()) * &s_inv;
+            let v = &sig.r * &s_inv;
+            &g_mul(u.as_integer()) + &point.mul_vartime(v.as_integer())
+        })
+    });
+    let table = point.precompute();
+    group.bench_function("ecdsa_verify_precomputed", |bench| {
+        bench.iter(|| point.verify_with_table(black_box(&z), black_box(&sig), &table))
+    });
+    group.finish();
+}
+
+// consecutive multiples of a random point, far cheaper to make than independent points
+fn secp256k1_points(rng: &mut ChaCha20Rng, count: usize) -> Vec<EcPoint> {
+    let g = SECP256K1.generator();
+    let step = &g * random_below(rng, &SECP256K1.order());
+    let mut points = vec![&g * random_below(rng, &SECP256K1.order())];
+    for i in 1..count {
+        let next = &points[i - 1] + &step;
+        points.push(next);
+    }
+    points
+}
+
+fn batch(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(SEED);
+    let points = secp256k1_points(&mut rng, 1024);
+    let terms: Vec<(&EcPoint, Integer)> = points[..256]
+        .iter()
+        .map(|point| (point, random_below(&mut rng, &SECP256K1.order())))
+        .collect();
+
+    let mut group = c.benchmark_group("batch");
+    group.bench_function("msm_256", |bench| {
+        bench.iter(|| mul_interleaved(black_box(&terms), 5))
+    });
+    group.bench_function("sum_points_1024", |bench| {
+        bench.iter(|| sum_points(black_box(&points)))
+    });
+    group.bench_function("validate_each_1024", |bench| {
+        bench.iter(|| validate_each(black_box(&points)))
+    });
+    group.bench_function("double_each_1024", |bench| {
+        bench.iter(|| {
+            black_box(&points)
+                .iter()
+                .map(|point| point + point)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("batch_double_1024", |bench| {
+        bench.iter(|| {
+            let mut doubled = points.clone();
+            batch_double(black_box(&mut doubled)).unwrap();
+            doubled
+        })
+    });
+
+    // verify_batch is serial without the parallel feature, so the two should then match
+    let ecdsa_items: Vec<(Integer, Signature, S256Point)> = (0..1000)
+        .map(|_| {
+            let key = PrivateKey::generate(&mut rng);
+            let z = random_below(&mut rng, &SECP256K1.order());
+            (z.clone(), key.sign(&z), key.public_key().into())
+        })
+        .collect();
+    group.bench_function("ecdsa_verify_each_1000", |bench| {
+        bench.iter(|| {
+            black_box(&ecdsa_items)
+                .iter()
+                .map(|(z, sig, point)| point.verify(z, sig))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("ecdsa_verify_batch_1000", |bench| {
+        bench.iter(|| verify_batch(black_box(&ecdsa_items)))
+    });
+
+    let schnorr_items: Vec<([u8; 32], [u8; 32], [u8; 64])> = (0..100)
+        .map(|_| {
+            let key = PrivateKey::generate(&mut rng);
+            let mut msg = [0u8; 32];
+            rng.fill_bytes(&mut msg);
+            let sig = schnorr::sign(&key, &msg, &[0u8; 32]);
+            (key.public_key().point().xonly(), msg, sig)
+        })
+        .collect();
+    group.bench_function("schnorr_verify_each_100", |bench| {
+        bench.iter(|| {
+            black_box(&schnorr_items)
+                .iter()
+                .all(|(p, msg, sig)| schnorr::verify(p, msg, sig))
+        })
+    });
+    group.bench_function("schnorr_verify_batch_100", |bench| {
+        bench.iter(|| schnorr::verify_batch(black_box(&schnorr_items), &mut rng))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, field, curve, secp256k1, batch);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+// cargo +nightly fuzz run base58_decode_check
+#![no_main]
+use galactic_credit::encoding::base58;
+use libfuzzer_sys::fuzz_target;
+
+// base58 has one encoding per byte string, so a successful decode encodes back to the input
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(payload) = base58::decode_check(s) {
+        assert_eq!(base58::encode_check(&payload), s);
+    }
+});
@@ -0,0 +1,20 @@
+// cargo +nightly fuzz run field_from_hex
+#![no_main]
+use galactic_credit::ecc::field::FieldElement;
+use libfuzzer_sys::fuzz_target;
+
+// input is "value,order" in hex. An accepted element prints back to hex that parses the same
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Some((value, order)) = s.split_once(',') else {
+        return;
+    };
+    if let Ok(element) = FieldElement::try_from_str(value, order) {
+        assert!(element.value < element.order);
+        let value = format!("{:x}", element.value);
+        let order = format!("{:x}", element.order);
+        assert_eq!(FieldElement::try_from_str(&value, &order), Ok(element));
+    }
+});
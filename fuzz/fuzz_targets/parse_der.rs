@@ -0,0 +1,11 @@
+// cargo +nightly fuzz run parse_der
+#![no_main]
+use galactic_credit::ecc::secp256k1::Signature;
+use libfuzzer_sys::fuzz_target;
+
+// parse_der only accepts strict DER, so anything it accepts must re-encode to the same bytes
+fuzz_target!(|data: &[u8]| {
+    if let Ok(sig) = Signature::parse_der(data) {
+        assert_eq!(sig.der(), data);
+    }
+});
@@ -0,0 +1,15 @@
+// cargo +nightly fuzz run parse_sec
+#![no_main]
+use galactic_credit::ecc::secp256k1::{ParsePolicy, S256Point};
+use libfuzzer_sys::fuzz_target;
+
+// every accepted key is one canonical encoding, hybrid keys differing only in the prefix byte
+fuzz_target!(|data: &[u8]| {
+    for policy in [ParsePolicy::Strict, ParsePolicy::AllowHybrid] {
+        if let Ok(point) = S256Point::parse(data, policy) {
+            let sec = point.sec(data.len() == 33);
+            assert_eq!(sec[1..], data[1..]);
+            assert!(sec[0] == data[0] || data[0] & 0xfe == 0x06);
+        }
+    }
+});
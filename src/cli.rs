@@ -0,0 +1,184 @@
+use crate::Error;
+use crate::ecc::hash::hash256;
+use crate::ecc::secp256k1::{Network, PrivateKey, S256Point, Secp256k1Error, sign_hex, verify_hex};
+use crate::encoding::hex;
+use rand_core::{CryptoRng, RngCore};
+use std::fmt::Display;
+
+pub const USAGE: &str = "usage:
+    galactic_credit keygen [--testnet]
+    galactic_credit pubkey <privhex> [--compressed]
+    galactic_credit sign <privhex> <msg>
+    galactic_credit verify <pubhex> <msg> <sighex>
+    galactic_credit address <pubhex> [--testnet]";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Keygen {
+        network: Network,
+    },
+    Pubkey {
+        key: String,
+        compressed: bool,
+    },
+    Sign {
+        key: String,
+        msg: String,
+    },
+    Verify {
+        key: String,
+        msg: String,
+        sig: String,
+    },
+    Address {
+        key: String,
+        network: Network,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    Usage(String),
+    Invalid(Error),
+    SignatureMismatch,
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{msg}\n{USAGE}"),
+            CliError::Invalid(err) => write!(f, "{err}"),
+            CliError::SignatureMismatch => write!(f, "signature does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<Secp256k1Error> for CliError {
+    fn from(err: Secp256k1Error) -> Self {
+        CliError::Invalid(err.into())
+    }
+}
+
+impl CliError {
+    // 2 for a malformed command line, 1 for anything wrong with the inputs themselves
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+// args without the program name. Flags may appear anywhere after the subcommand
+pub fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let (name, rest) = args
+        .split_first()
+        .ok_or_else(|| CliError::Usage("missing subcommand".to_string()))?;
+    let mut flags = Vec::new();
+    let mut positional = Vec::new();
+    for arg in rest {
+        if arg.starts_with("--") {
+            flags.push(arg.as_str());
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    let (allowed, arity): (&[&str], usize) = match name.as_str() {
+        "keygen" => (&["--testnet"], 0),
+        "pubkey" => (&["--compressed"], 1),
+        "sign" => (&[], 2),
+        "verify" => (&[], 3),
+        "address" => (&["--testnet"], 1),
+        _ => return Err(CliError::Usage(format!("unknown subcommand {name:?}"))),
+    };
+    if let Some(flag) = flags.iter().find(|flag| !allowed.contains(flag)) {
+        return Err(CliError::Usage(format!("unknown flag {flag} for {name}")));
+    }
+    if positional.len() != arity {
+        return Err(CliError::Usage(format!(
+            "{name} takes {arity} argument(s), got {}",
+            positional.len()
+        )));
+    }
+
+    let has = |flag: &str| flags.contains(&flag);
+    let network = if has("--testnet") {
+        Network::Testnet
+    } else {
+        Network::Mainnet
+    };
+    let mut positional = positional.into_iter();
+    let mut next = || positional.next().expect("arity checked above");
+    Ok(match name.as_str() {
+        "keygen" => Command::Keygen { network },
+        "pubkey" => Command::Pubkey {
+            key: next(),
+            compressed: has("--compressed"),
+        },
+        "sign" => Command::Sign {
+            key: next(),
+            msg: next(),
+        },
+        "verify" => Command::Verify {
+            key: next(),
+            msg: next(),
+            sig: next(),
+        },
+        _ => Command::Address {
+            key: next(),
+            network,
+        },
+    })
+}
+
+pub fn run(command: &Command) -> Result<String, CliError> {
+    match command {
+        Command::Keygen { network } => Ok(keygen(&mut rand_core::OsRng, *network)),
+        Command::Pubkey { key, compressed } => pubkey(key, *compressed),
+        Command::Sign { key, msg } => sign(key, msg),
+        Command::Verify { key, msg, sig } => verify(key, msg, sig),
+        Command::Address { key, network } => address(key, *network),
+    }
+}
+
+fn parse_private_key(privhex: &str) -> Result<PrivateKey, CliError> {
+    let bytes = hex::decode(privhex).map_err(Secp256k1Error::from)?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| Secp256k1Error::BadHexLength)?;
+    Ok(PrivateKey::from_bytes(&bytes)?)
+}
+
+// secret as hex, then the compressed WIF on the next line. Takes the rng so tests can seed it
+pub fn keygen<R: CryptoRng + RngCore>(rng: &mut R, network: Network) -> String {
+    let key = PrivateKey::generate(rng);
+    format!("{}\n{}", key.reveal_hex(), key.wif(true, network))
+}
+
+pub fn pubkey(privhex: &str, compressed: bool) -> Result<String, CliError> {
+    Ok(parse_private_key(privhex)?
+        .public_key()
+        .point()
+        .to_sec_hex(compressed))
+}
+
+// ECDSA over hash256(msg), DER encoded
+pub fn sign(privhex: &str, msg: &str) -> Result<String, CliError> {
+    Ok(sign_hex(privhex, &hex::encode(&hash256(msg.as_bytes())))?)
+}
+
+pub fn verify(pubhex: &str, msg: &str, sighex: &str) -> Result<String, CliError> {
+    if !verify_hex(pubhex, &hex::encode(&hash256(msg.as_bytes())), sighex)? {
+        return Err(CliError::SignatureMismatch);
+    }
+    Ok("ok".to_string())
+}
+
+// P2PKH, compressed or not according to the SEC encoding given
+pub fn address(pubhex: &str, network: Network) -> Result<String, CliError> {
+    let point: S256Point = pubhex.parse()?;
+    // a parsed key is 33 or 65 bytes, so the length alone tells them apart even with a 0x prefix
+    let compressed = pubhex.len() <= 68;
+    Ok(point.address(compressed, network))
+}
@@ -2,7 +2,8 @@
 
 use rug::{Complete, Integer};
 
-use super::field::{FieldElement, Pow};
+use super::field::{FieldElement, FieldError, Pow, batch_invert};
+use super::hash::sha256;
 
 use core::f64;
 use std::{
@@ -15,7 +16,8 @@ use std::{
 * Enums *
 *********
 * */
-#[derive(Debug, Clone, PartialEq)]
+// Finite sorts before Infinity here, EcPoint's Ord puts Infinity first itself
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Bounded<T> {
     Finite(T),
     Infinity,
@@ -31,6 +33,62 @@ impl<T: Display> Display for Bounded<T> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurveError {
+    NotOnCurve,
+    HalfInfinity,
+    CurveMismatch,
+    ZeroIsomorphism,
+    NotInSubgroup,
+    InvalidWindow,
+    SquareTwist,
+    NotOnTwist,
+    HashToCurveFailed,
+    Field(FieldError),
+}
+
+impl Display for CurveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurveError::NotOnCurve => write!(f, "point is not on the curve"),
+            CurveError::HalfInfinity => {
+                write!(
+                    f,
+                    "only one coordinate is infinity, a point needs both or neither"
+                )
+            }
+            CurveError::CurveMismatch => write!(f, "points are not on the same curve"),
+            CurveError::ZeroIsomorphism => write!(f, "isomorphism needs a nonzero u"),
+            CurveError::NotInSubgroup => write!(f, "point is not in the subgroup of order n"),
+            CurveError::InvalidWindow => write!(f, "wNAF window must be in 2..=16"),
+            CurveError::SquareTwist => write!(f, "a quadratic twist needs a non-square d"),
+            CurveError::NotOnTwist => write!(f, "x is on the curve itself, not on its twist"),
+            CurveError::HashToCurveFailed => {
+                write!(
+                    f,
+                    "no curve point after {MAX_HASH_TO_CURVE_ATTEMPTS} counters"
+                )
+            }
+            CurveError::Field(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CurveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CurveError::Field(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<FieldError> for CurveError {
+    fn from(err: FieldError) -> Self {
+        CurveError::Field(err)
+    }
+}
+
 /*
 *********
 * Point *
@@ -39,7 +97,7 @@ impl<T: Display> Display for Bounded<T> {
 
 // T must be able to be represented as a point on an EC
 // x and y could be infinite, so we use Bounded<T>
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct EcPoint {
     pub x: Bounded<FieldElement>,
     pub y: Bounded<FieldElement>,
@@ -48,33 +106,64 @@ pub struct EcPoint {
 }
 
 impl EcPoint {
+    // panicking wrapper around try_new, for points known to be valid
     pub fn new(
         x: Bounded<FieldElement>,
         y: Bounded<FieldElement>,
         a: FieldElement,
         b: FieldElement,
     ) -> Self {
+        Self::try_new(x, y, a, b).unwrap_or_else(|err| panic!("Elliptic Curve Error: {err}."))
+    }
+
+    // x, y, a and b must all be in the same field and y^2 = x^3 + ax + b must hold.
+    // (Infinity, Infinity) is the identity of the curve given by a and b
+    pub fn try_new(
+        x: Bounded<FieldElement>,
+        y: Bounded<FieldElement>,
+        a: FieldElement,
+        b: FieldElement,
+    ) -> Result<Self, CurveError> {
         use Bounded::{Finite, Infinity};
 
-        if let (Finite(x), Finite(y)) = (x, y) {
-            if y.pow(2) != &(&(x.pow(3)) + &(&a * &x)) + &b {
-                panic!("Elliptic Curve Error: {x}, {y} is not on curve.");
-            }
-            Self {
-                x: Finite(x),
-                y: Finite(y),
-                a,
-                b,
+        if a.order != b.order {
+            return Err(FieldError::OrderMismatch.into());
+        }
+        match (x, y) {
+            (Finite(x), Finite(y)) => {
+                if x.order != a.order || y.order != a.order {
+                    return Err(FieldError::OrderMismatch.into());
+                }
+                if y.pow(2) != &(&(x.pow(3)) + &(&a * &x)) + &b {
+                    return Err(CurveError::NotOnCurve);
+                }
+                Ok(Self {
+                    x: Finite(x),
+                    y: Finite(y),
+                    a,
+                    b,
+                })
             }
-        } else {
-            Self {
+            (Infinity, Infinity) => Ok(Self {
                 x: Infinity,
                 y: Infinity,
                 a,
                 b,
-            }
+            }),
+            _ => Err(CurveError::HalfInfinity),
         }
     }
+
+    // re-runs the try_new checks, for points whose public fields may have been changed
+    pub fn validate(&self) -> Result<(), CurveError> {
+        Self::try_new(
+            self.x.clone(),
+            self.y.clone(),
+            self.a.clone(),
+            self.b.clone(),
+        )
+        .map(|_| ())
+    }
 }
 
 impl Display for EcPoint {
@@ -83,20 +172,190 @@ impl Display for EcPoint {
     }
 }
 
-impl Add for &EcPoint {
+impl EcPoint {
+    // [0x00] for Infinity, otherwise 0x01, then x big endian padded to the byte width of p,
+    // then the parity of y as 0 or 1. Ord compares these bytes first
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        use Bounded::Finite;
+
+        let (Finite(x), Finite(y)) = (&self.x, &self.y) else {
+            return vec![0x00];
+        };
+        let width = self.a.order.significant_bits().div_ceil(8) as usize;
+        let digits = x.value.to_digits::<u8>(rug::integer::Order::Msf);
+        let mut result = vec![0u8; 1 + width.max(digits.len()) - digits.len()];
+        result[0] = 0x01;
+        result.extend_from_slice(&digits);
+        result.push(y.value.is_odd() as u8);
+        result
+    }
+}
+
+// An arbitrary but stable total order for sorting sets of points: Infinity first, then by x,
+// then even y before odd, by comparing canonical_bytes. It has nothing to do with the group,
+// P < Q says nothing about P + R and Q + R. Ties can only come from points on different curves
+// or assembled by hand, and fall back to comparing every field so the order agrees with ==
+impl Ord for EcPoint {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.canonical_bytes()
+            .cmp(&other.canonical_bytes())
+            .then_with(|| {
+                (&self.x, &self.y, &self.a, &self.b).cmp(&(&other.x, &other.y, &other.a, &other.b))
+            })
+    }
+}
+
+impl PartialOrd for EcPoint {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/*
+**************
+* Point view *
+**************
+* */
+
+// An EcPoint by reference, with the coordinates and the curve borrowed separately so points
+// that share a curve (the entries of a PointTable) don't each carry their own a and b.
+// Arithmetic on views gives owned EcPoints
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EcPointRef<'a> {
+    pub x: &'a Bounded<FieldElement>,
+    pub y: &'a Bounded<FieldElement>,
+    pub a: &'a FieldElement,
+    pub b: &'a FieldElement,
+}
+
+impl EcPoint {
+    pub fn view(&self) -> EcPointRef<'_> {
+        EcPointRef {
+            x: &self.x,
+            y: &self.y,
+            a: &self.a,
+            b: &self.b,
+        }
+    }
+}
+
+impl From<EcPointRef<'_>> for EcPoint {
+    fn from(point: EcPointRef<'_>) -> Self {
+        EcPoint {
+            x: point.x.clone(),
+            y: point.y.clone(),
+            a: point.a.clone(),
+            b: point.b.clone(),
+        }
+    }
+}
+
+impl Neg for EcPointRef<'_> {
     type Output = EcPoint;
 
-    fn add(self, rhs: Self) -> Self::Output {
+    fn neg(self) -> Self::Output {
+        use Bounded::{Finite, Infinity};
+
+        match self.y {
+            Finite(y) => EcPoint {
+                x: self.x.clone(),
+                y: Finite(-y),
+                a: self.a.clone(),
+                b: self.b.clone(),
+            },
+            Infinity => self.into(),
+        }
+    }
+}
+
+// Points on a single curve stored as bare coordinates, with a and b kept once for the whole
+// table. Each entry costs two coordinates instead of the four field elements of an EcPoint,
+// which adds up in the precomputed multiple tables. Entries are read back as EcPointRef
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointTable {
+    a: FieldElement,
+    b: FieldElement,
+    coordinates: Vec<(Bounded<FieldElement>, Bounded<FieldElement>)>,
+}
+
+impl PointTable {
+    // panics on no points or points from different curves
+    pub fn new(points: Vec<EcPoint>) -> Self {
+        let first = points
+            .first()
+            .expect("Error: a point table needs at least one point");
+        let (a, b) = (first.a.clone(), first.b.clone());
+        assert!(
+            points.iter().all(|p| p.a == a && p.b == b),
+            "Error: table points are not all on the same curve"
+        );
+        let coordinates = points.into_iter().map(|p| (p.x, p.y)).collect();
+        Self { a, b, coordinates }
+    }
+
+    pub fn len(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    // always false, tables are never empty
+    pub fn is_empty(&self) -> bool {
+        self.coordinates.is_empty()
+    }
+
+    // (a, b) shared by every entry
+    pub fn curve(&self) -> (&FieldElement, &FieldElement) {
+        (&self.a, &self.b)
+    }
+
+    // panics when index is out of bounds, like indexing a Vec
+    pub fn get(&self, index: usize) -> EcPointRef<'_> {
+        let (x, y) = &self.coordinates[index];
+        EcPointRef {
+            x,
+            y,
+            a: &self.a,
+            b: &self.b,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = EcPointRef<'_>> {
+        (0..self.len()).map(|i| self.get(i))
+    }
+}
+
+impl FromIterator<EcPoint> for PointTable {
+    fn from_iter<I: IntoIterator<Item = EcPoint>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl Neg for &EcPoint {
+    type Output = EcPoint;
+
+    fn neg(self) -> Self::Output {
+        -self.view()
+    }
+}
+
+impl EcPoint {
+    // Points from try_new are always on a single curve, so this only fails for points from
+    // different curves or ones assembled by hand with an invalid shape
+    pub fn try_add(&self, rhs: &EcPoint) -> Result<EcPoint, CurveError> {
+        self.view().try_add(rhs.view())
+    }
+}
+
+impl EcPointRef<'_> {
+    // EcPoint::try_add, for any mix of owned points and table entries
+    pub fn try_add(self, rhs: EcPointRef<'_>) -> Result<EcPoint, CurveError> {
         use Bounded::{Finite, Infinity};
 
         // Not on the same curve
         if self.a != rhs.a || self.b != rhs.b {
-            panic!(
-                "Error: Elliptic curve addition failed because points are not on the same curve"
-            );
+            return Err(CurveError::CurveMismatch);
         }
 
-        match (&self.x, &self.y, &rhs.x, &rhs.y) {
+        Ok(match (self.x, self.y, rhs.x, rhs.y) {
             // Infinity + Infinity = Infinity
             (Infinity, Infinity, Infinity, Infinity) => EcPoint {
                 x: Infinity,
@@ -138,9 +397,20 @@ impl Add for &EcPoint {
                     b: self.b.clone(),
                 }
             }
+            // Apex point of curve, the tangent is vertical so P + P = Infinity
+            (Finite(x1), Finite(y1), Finite(x2), Finite(y2))
+                if x1 == x2 && y1 == y2 && y1.is_zero() =>
+            {
+                EcPoint {
+                    x: Infinity,
+                    y: Infinity,
+                    a: self.a.clone(),
+                    b: self.b.clone(),
+                }
+            }
             // Point + itself = find tangent line intersection
             (Finite(x1), Finite(y1), Finite(x2), Finite(y2)) if x1 == x2 && y1 == y2 => {
-                let s = &((&(x1.pow(2)).scale(3)) + &self.a) / &(y1.scale(2));
+                let s = &((&(x1.pow(2)).scale(3)) + self.a) / &(y1.scale(2));
                 let x3 = &(s.pow(2)) - &(x1.scale(2));
                 let y3 = &(&s * &(x1 - &x3)) - y1;
 
@@ -151,20 +421,300 @@ impl Add for &EcPoint {
                     b: self.b.clone(),
                 }
             }
-            // Apex point of curve
-            (Finite(x1), Finite(y1), Finite(x2), Finite(y2))
-                if x1 == x2 && y1 == y2 && y1.is_zero() =>
+            // Addition not defined for any other conditions
+            (_, _, _, _) => return Err(CurveError::HalfInfinity),
+        })
+    }
+}
+
+/*
+****************
+* Small orders *
+****************
+* */
+impl EcPoint {
+    // order exactly 2: the tangent is vertical, so P = -P
+    pub fn is_two_torsion(&self) -> bool {
+        matches!(&self.y, Bounded::Finite(y) if y.is_zero())
+    }
+
+    // order exactly 3: 2P = -P, with Infinity (order 1) excluded
+    pub fn is_three_torsion(&self) -> bool {
+        matches!(self.x, Bounded::Finite(_)) && (self + self) == -self
+    }
+
+    // The order of the point if it is at most bound, by adding P until Infinity comes up.
+    // Infinity has order 1
+    pub fn small_order(&self, bound: u32) -> Option<u32> {
+        let mut multiple = self.clone();
+        for order in 1..=bound {
+            if multiple.x == Bounded::Infinity {
+                // a point's order divides the group order, so it can't pass the Hasse bound
+                debug_assert!(hasse_interval(&self.a.order).1 >= order);
+                return Some(order);
+            }
+            multiple = &multiple + self;
+        }
+        None
+    }
+}
+
+// Hasse's theorem, |#E(F_p) - (p + 1)| <= 2 sqrt(p), as the integers it allows. 2 sqrt(p) is
+// irrational for prime p, so the bounds are p + 1 -/+ floor(sqrt(4p)), found without floats
+pub fn hasse_interval(p: &Integer) -> (Integer, Integer) {
+    let width = Integer::from(p * 4u8).sqrt();
+    let middle = Integer::from(p + 1u8);
+    ((&middle - &width).complete(), middle + width)
+}
+
+// whether a computed group order for a curve over F_p is inside hasse_interval
+pub fn is_plausible_order(order: &Integer, p: &Integer) -> bool {
+    let (low, high) = hasse_interval(p);
+    low <= *order && *order <= high
+}
+
+// The distinct prime factors of n > 0, by trial division
+fn prime_factors(n: &Integer) -> Vec<Integer> {
+    let mut n = n.clone();
+    let mut factors = Vec::new();
+    let mut q = Integer::from(2);
+    while (&q * &q).complete() <= n {
+        if n.is_divisible(&q) {
+            while n.is_divisible(&q) {
+                n /= &q;
+            }
+            factors.push(q.clone());
+        }
+        q += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+// A point generating the whole group of y^2 = x^3 + ax + b, given its order N, trying x from 0
+// up with one y per x. P generates it when N*P is Infinity but (N/q)*P is not for each prime
+// q dividing N. None when the group is not cyclic, as for y^2 = x^3 + 7 over F_223 which has
+// three points of order 2, or when N is above bound: the search and factoring take O(N)
+pub fn find_generator(
+    a: &FieldElement,
+    b: &FieldElement,
+    group_order: &Integer,
+    bound: &Integer,
+) -> Option<EcPoint> {
+    use Bounded::{Finite, Infinity};
+
+    if group_order > bound || *group_order < 1 {
+        return None;
+    }
+    let cofactors: Vec<Integer> = prime_factors(group_order)
+        .into_iter()
+        .map(|q| group_order.clone() / q)
+        .collect();
+    let p = a.order.clone();
+    let mut x = Integer::ZERO;
+    while x < p {
+        let fe = FieldElement::new(x.clone(), p.clone());
+        let y2 = &(&fe.pow(3) + &(a * &fe)) + b;
+        if let Some(y) = y2.sqrt() {
+            let point = EcPoint::try_new(Finite(fe), Finite(y), a.clone(), b.clone()).ok()?;
+            if (group_order.clone() * &point).x == Infinity
+                && cofactors.iter().all(|m| (m.clone() * &point).x != Infinity)
             {
-                EcPoint {
-                    x: Infinity,
-                    y: Infinity,
-                    a: self.a.clone(),
-                    b: self.b.clone(),
-                }
+                return Some(point);
             }
-            // Addition not defined for any other conditions
-            (_, _, _, _) => panic!("Error: elliptic curve addition not defined for this condition"),
         }
+        x += 1;
+    }
+    None
+}
+
+/*
+*****************
+* Hash to curve *
+*****************
+* */
+
+// Each counter finds a point about half the time, so 256 failures in a row will not happen
+// for a real curve
+pub const MAX_HASH_TO_CURVE_ATTEMPTS: u32 = 256;
+
+// A point with no known discrete log, for Pedersen style second generators. Try and increment:
+// x = SHA256(seed || counter) mod p for counter = 0, 1, ... (4 bytes big endian) until
+// x^3 + ax + b is a square, with the even y. The point is then multiplied by the cofactor to
+// land in the subgroup of the given order, and counters that give Infinity are skipped.
+// Not constant time, the seed is public. Fails after MAX_HASH_TO_CURVE_ATTEMPTS counters, or
+// with NotInSubgroup when the order and cofactor don't match the curve
+pub fn hash_to_curve(
+    seed: &[u8],
+    a: &FieldElement,
+    b: &FieldElement,
+    order: &Integer,
+    cofactor: u32,
+) -> Result<EcPoint, CurveError> {
+    use Bounded::{Finite, Infinity};
+
+    let p = &a.order;
+    for counter in 0..MAX_HASH_TO_CURVE_ATTEMPTS {
+        let digest = sha256(&[seed, &counter.to_be_bytes()].concat());
+        let x = FieldElement::new(
+            Integer::from_digits(&digest, rug::integer::Order::Msf) % p,
+            p.clone(),
+        );
+        let Some(y) = (&(&x.pow(3) + &(a * &x)) + b).sqrt() else {
+            continue;
+        };
+        let y = if y.value.is_even() { y } else { -&y };
+        let point = Integer::from(cofactor)
+            * &EcPoint::try_new(Finite(x), Finite(y), a.clone(), b.clone())?;
+        if point.x == Infinity {
+            continue;
+        }
+        if (order.clone() * &point).x != Infinity {
+            return Err(CurveError::NotInSubgroup);
+        }
+        return Ok(point);
+    }
+    Err(CurveError::HashToCurveFailed)
+}
+
+/*
+***************
+* Isomorphism *
+***************
+* */
+
+// The change of variables (x, y) -> (u^2 x, u^3 y) from y^2 = x^3 + ax + b onto
+// y^2 = x^3 + u^4 a x + u^6 b. It is a group isomorphism, so it commutes with addition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isomorphism {
+    u: FieldElement,
+}
+
+impl Isomorphism {
+    // panicking wrapper around try_new
+    pub fn new(u: FieldElement) -> Self {
+        Self::try_new(u).unwrap_or_else(|err| panic!("Elliptic Curve Error: {err}."))
+    }
+
+    pub fn try_new(u: FieldElement) -> Result<Self, CurveError> {
+        if u.is_zero() {
+            return Err(CurveError::ZeroIsomorphism);
+        }
+        Ok(Self { u })
+    }
+
+    pub fn u(&self) -> &FieldElement {
+        &self.u
+    }
+
+    // the map back, with u^-1
+    pub fn inverse(&self) -> Self {
+        let one = FieldElement::new(1, self.u.order.clone());
+        Self { u: &one / &self.u }
+    }
+
+    // (u^4 a, u^6 b)
+    pub fn map_curve(&self, a: &FieldElement, b: &FieldElement) -> (FieldElement, FieldElement) {
+        (a * &self.u.pow(4), b * &self.u.pow(6))
+    }
+
+    // Infinity goes to the Infinity of the mapped curve. Fails when u and the point are over
+    // different fields, or the result is not on the mapped curve
+    pub fn map_point(&self, p: &EcPoint) -> Result<EcPoint, CurveError> {
+        use Bounded::{Finite, Infinity};
+
+        if self.u.order != p.a.order {
+            return Err(FieldError::OrderMismatch.into());
+        }
+        let (a, b) = self.map_curve(&p.a, &p.b);
+        match (&p.x, &p.y) {
+            (Finite(x), Finite(y)) => {
+                EcPoint::try_new(Finite(x * &self.u.pow(2)), Finite(y * &self.u.pow(3)), a, b)
+            }
+            (Infinity, Infinity) => Ok(EcPoint::new(Infinity, Infinity, a, b)),
+            _ => Err(CurveError::HalfInfinity),
+        }
+    }
+}
+
+/*
+******************
+* Quadratic twist *
+******************
+* */
+
+// The twist of y^2 = x^3 + ax + b by a non-square d, y^2 = x^3 + d^2 a x + d^3 b. Over F_p
+// every x lands on exactly one of the two curves (or both with y = 0), so together they have
+// 2p + 2 points. A point on the twist is a point of the curve over F_p^2
+pub fn twist(
+    a: &FieldElement,
+    b: &FieldElement,
+    d: &FieldElement,
+) -> Result<(FieldElement, FieldElement), CurveError> {
+    if a.order != b.order || a.order != d.order {
+        return Err(FieldError::OrderMismatch.into());
+    }
+    if d.is_square() {
+        return Err(CurveError::SquareTwist);
+    }
+    Ok((a * &d.pow(2), b * &d.pow(3)))
+}
+
+// The twist point for an x that is not on y^2 = x^3 + ax + b, meaning x^3 + ax + b is not a
+// square. With f(x) = x^3 + ax + b the twist has y^2 = d^3 f(x) at dx, and d f(x) is a square
+// because both factors are not
+pub fn twist_point(
+    a: &FieldElement,
+    b: &FieldElement,
+    d: &FieldElement,
+    x: &FieldElement,
+) -> Result<EcPoint, CurveError> {
+    let (twist_a, twist_b) = twist(a, b, d)?;
+    if x.order != a.order {
+        return Err(FieldError::OrderMismatch.into());
+    }
+    let fx = &(&x.pow(3) + &(a * x)) + b;
+    if fx.is_square() {
+        return Err(CurveError::NotOnTwist);
+    }
+    let root = (&fx * d).sqrt().expect("d f(x) is a square");
+    EcPoint::try_new(
+        Bounded::Finite(d * x),
+        Bounded::Finite(d * &root),
+        twist_a,
+        twist_b,
+    )
+}
+
+// Thin wrapper over try_add that panics, see try_add for when
+impl Add for &EcPoint {
+    type Output = EcPoint;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self + rhs.view()
+    }
+}
+
+impl Add<EcPointRef<'_>> for &EcPoint {
+    type Output = EcPoint;
+
+    fn add(self, rhs: EcPointRef<'_>) -> Self::Output {
+        self.view() + rhs
+    }
+}
+
+impl Add for EcPointRef<'_> {
+    type Output = EcPoint;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.try_add(rhs).unwrap_or_else(|err| match err {
+            CurveError::CurveMismatch => panic!(
+                "Error: Elliptic curve addition failed because points are not on the same curve"
+            ),
+            _ => panic!("Error: elliptic curve addition not defined for this condition"),
+        })
     }
 }
 
@@ -252,6 +802,272 @@ impl Mul<&EcPoint> for Integer {
     }
 }
 
+/*
+*******************
+* Batch operations *
+*******************
+* */
+
+// Inputs shorter than this stay on the calling thread even with the parallel feature. A point
+// addition takes microseconds, so below a few dozen items handing work to other threads costs
+// about as much as it saves
+pub const PARALLEL_THRESHOLD: usize = 32;
+
+// f over items, in order. Runs on rayon's pool with the parallel feature and at least
+// PARALLEL_THRESHOLD items, otherwise a plain map
+pub(crate) fn par_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "parallel")]
+    if items.len() >= PARALLEL_THRESHOLD {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).collect();
+    }
+    items.iter().map(f).collect()
+}
+
+// EcPoint::validate for each point, in order
+pub fn validate_each(points: &[EcPoint]) -> Vec<Result<(), CurveError>> {
+    par_map(points, EcPoint::validate)
+}
+
+// Checks every point and reports all the bad ones with their index, in order, rather than
+// stopping at the first. A point fails if it is not on the curve of points[0], fails validate,
+// or, given the subgroup order n, if n * P is not Infinity
+pub fn validate_points(
+    points: &[EcPoint],
+    order: Option<&Integer>,
+) -> Result<(), Vec<(usize, CurveError)>> {
+    let Some(first) = points.first() else {
+        return Ok(());
+    };
+    let indexed: Vec<(usize, &EcPoint)> = points.iter().enumerate().collect();
+    let check = |point: &EcPoint| {
+        if point.a != first.a || point.b != first.b {
+            return Err(CurveError::CurveMismatch);
+        }
+        point.validate()?;
+        match order {
+            Some(n) if (n.clone() * point).x != Bounded::Infinity => Err(CurveError::NotInSubgroup),
+            _ => Ok(()),
+        }
+    };
+    let failures: Vec<(usize, CurveError)> = par_map(&indexed, |(i, point)| (*i, check(point)))
+        .into_iter()
+        .filter_map(|(i, result)| result.err().map(|err| (i, err)))
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+// Sum of the points, in chunks of PARALLEL_THRESHOLD whose partial sums are added up the same
+// way. Addition is associative, so the result doesn't depend on how the work was split
+pub fn sum_points(points: &[EcPoint]) -> EcPoint {
+    assert!(!points.is_empty(), "Error: no points to add");
+    if points.len() <= PARALLEL_THRESHOLD {
+        return points[1..]
+            .iter()
+            .fold(points[0].clone(), |sum, point| &sum + point);
+    }
+    let chunks: Vec<&[EcPoint]> = points.chunks(PARALLEL_THRESHOLD).collect();
+    sum_points(&par_map(&chunks, |chunk| sum_points(chunk)))
+}
+
+// Doubles every point in place with a single field inversion: the 2y denominators of all the
+// tangent slopes go through batch_invert together. Points with y = 0 double to Infinity and
+// Infinity stays Infinity. All points must be on the curve of points[0]; on error none of them
+// are changed
+pub fn batch_double(points: &mut [EcPoint]) -> Result<(), CurveError> {
+    use Bounded::{Finite, Infinity};
+
+    let Some(first) = points.first() else {
+        return Ok(());
+    };
+    let (a, b) = (first.a.clone(), first.b.clone());
+    let mut denominators = Vec::with_capacity(points.len());
+    for point in points.iter() {
+        if point.a != a || point.b != b {
+            return Err(CurveError::CurveMismatch);
+        }
+        match (&point.x, &point.y) {
+            (Finite(_), Finite(y)) if !y.is_zero() => denominators.push(y.scale(2)),
+            (Finite(_), Finite(_)) | (Infinity, Infinity) => {}
+            _ => return Err(CurveError::HalfInfinity),
+        }
+    }
+
+    let mut inverses = batch_invert(&denominators)?.into_iter();
+    for point in points.iter_mut() {
+        let (Finite(x1), Finite(y1)) = (&point.x, &point.y) else {
+            continue;
+        };
+        if y1.is_zero() {
+            point.x = Infinity;
+            point.y = Infinity;
+            continue;
+        }
+        let inverse = inverses.next().expect("one inverse per nonzero y");
+        let s = &(&(x1.pow(2)).scale(3) + &a) * &inverse;
+        let x3 = &(s.pow(2)) - &(x1.scale(2));
+        let y3 = &(&s * &(x1 - &x3)) - y1;
+        point.x = Finite(x3);
+        point.y = Finite(y3);
+    }
+    Ok(())
+}
+
+/*
+*******************************
+* Multi-scalar multiplication *
+*******************************
+* */
+
+// width-w NAF of k >= 0, least significant digit first. Nonzero digits are odd with
+// |d| < 2^(w-1), and any w consecutive digits contain at most one nonzero
+pub fn wnaf(k: &Integer, width: u32) -> Vec<i32> {
+    let modulus = 1i32 << width;
+    let mut k = k.clone();
+    let mut digits = Vec::new();
+    while k.is_positive() {
+        let mut digit = 0;
+        if k.is_odd() {
+            digit = k.mod_u(modulus as u32) as i32;
+            if digit >= modulus / 2 {
+                digit -= modulus;
+            }
+            k -= digit;
+        }
+        digits.push(digit);
+        k >>= 1;
+    }
+    digits
+}
+
+// P, 3P, 5P, ..., (2^(w-1) - 1)P, the table multi_mul reads width-w wNAF digits from
+pub fn odd_multiples(point: &EcPoint, width: u32) -> PointTable {
+    assert!(width >= 2, "Error: wNAF width must be at least 2");
+    let double = point + point;
+    let mut odd = vec![point.clone()];
+    for i in 1..1 << (width - 2) {
+        let next = &odd[i - 1] + &double;
+        odd.push(next);
+    }
+    PointTable::new(odd)
+}
+
+// sum of k_i * P_i sharing one doubling chain across all terms (interleaved wNAF, a.k.a.
+// Shamir's trick), with each P_i given as its odd_multiples table. A table of 2^(w-2) entries
+// is read with width w, so long-lived points can use wider precomputed tables than the rest.
+// Negative scalars use negated table entries. Variable time, only for public scalars.
+// With the parallel feature and at least PARALLEL_THRESHOLD terms, the terms are split into one
+// chunk per thread and the chunk results summed. That repeats the doublings per chunk but
+// spreads the additions, which dominate once there are many terms
+pub fn multi_mul(terms: &[(&PointTable, Integer)]) -> EcPoint {
+    assert!(!terms.is_empty(), "Error: no points to multiply");
+    #[cfg(feature = "parallel")]
+    if terms.len() >= PARALLEL_THRESHOLD {
+        use rayon::prelude::*;
+        let chunk = terms.len().div_ceil(rayon::current_num_threads());
+        let chunks: Vec<_> = terms.chunks(chunk).collect();
+        let partial: Vec<EcPoint> = chunks.par_iter().map(|c| multi_mul_serial(c)).collect();
+        return sum_points(&partial);
+    }
+    multi_mul_serial(terms)
+}
+
+fn multi_mul_serial(terms: &[(&PointTable, Integer)]) -> EcPoint {
+    use Bounded::Infinity;
+    let (a, b) = terms[0].0.curve();
+
+    let digits: Vec<Vec<i32>> = terms
+        .iter()
+        .map(|(table, k)| {
+            assert!(
+                table.len().is_power_of_two(),
+                "Error: odd multiple table has the wrong length"
+            );
+            let width = table.len().trailing_zeros() + 2;
+            let mut digits = wnaf(&k.clone().abs(), width);
+            if k.is_negative() {
+                digits.iter_mut().for_each(|d| *d = -*d);
+            }
+            digits
+        })
+        .collect();
+
+    let len = digits.iter().map(Vec::len).max().unwrap_or(0);
+    let mut result = EcPoint::new(Infinity, Infinity, a.clone(), b.clone());
+    for i in (0..len).rev() {
+        result = &result + &result;
+        for ((table, _), digits) in terms.iter().zip(&digits) {
+            match digits.get(i) {
+                Some(&d) if d > 0 => result = &result + table.get((d as usize - 1) / 2),
+                Some(&d) if d < 0 => result = &result + &-table.get(((-d) as usize - 1) / 2),
+                _ => {}
+            }
+        }
+    }
+    result
+}
+
+// multi_mul for points without precomputed tables, every table built at the same width
+pub fn mul_interleaved(terms: &[(&EcPoint, Integer)], width: u32) -> EcPoint {
+    let tables = par_map(terms, |(point, _)| odd_multiples(point, width));
+    let terms: Vec<(&PointTable, Integer)> = tables
+        .iter()
+        .zip(terms)
+        .map(|(table, (_, k))| (table, k.clone()))
+        .collect();
+    multi_mul(&terms)
+}
+
+// Widest window WnafTable accepts, 2^14 odd multiples
+pub const MAX_WNAF_WINDOW: u32 = 16;
+
+// The odd multiples of one point, built once and reused for every k*P with the same P, e.g.
+// verifying many signatures from one key. Variable time like multi_mul
+#[derive(Debug, Clone, PartialEq)]
+pub struct WnafTable {
+    table: PointTable,
+}
+
+impl WnafTable {
+    // panicking wrapper around try_new
+    pub fn new(point: &EcPoint, window: u32) -> Self {
+        Self::try_new(point, window).unwrap_or_else(|err| panic!("Elliptic Curve Error: {err}."))
+    }
+
+    // window in 2..=MAX_WNAF_WINDOW, the point must pass validate
+    pub fn try_new(point: &EcPoint, window: u32) -> Result<Self, CurveError> {
+        if !(2..=MAX_WNAF_WINDOW).contains(&window) {
+            return Err(CurveError::InvalidWindow);
+        }
+        point.validate()?;
+        Ok(Self {
+            table: odd_multiples(point, window),
+        })
+    }
+
+    // the P the table was built for
+    pub fn point(&self) -> EcPoint {
+        self.table.get(0).into()
+    }
+
+    pub fn window(&self) -> u32 {
+        self.table.len().trailing_zeros() + 2
+    }
+
+    pub fn table(&self) -> &PointTable {
+        &self.table
+    }
+
+    // k*P for any k, negative included
+    pub fn mul(&self, k: &Integer) -> EcPoint {
+        multi_mul(&[(&self.table, k.clone())])
+    }
+}
+
 /*
 ***************
 * Point Macro *
@@ -261,7 +1077,7 @@ impl Mul<&EcPoint> for Integer {
 #[macro_export]
 macro_rules! ec_point {
     ($x:expr, $y:expr, $a:expr, $b:expr) => {
-        EcPoint::new($x, $y, $a, $b)
+        $crate::ecc::curve::EcPoint::new($x, $y, $a, $b)
     };
 }
 
@@ -400,4 +1216,636 @@ mod tests {
         );
         assert_eq!(&p1 + &p1, result);
     }
+
+    #[test]
+    fn test_wnaf() {
+        for k in [0u64, 1, 2, 7, 255, 1000, 0xdead_beef, u64::MAX] {
+            let k = Integer::from(k);
+            for width in 2..7 {
+                let digits = wnaf(&k, width);
+                let mut total = Integer::new();
+                for (i, &d) in digits.iter().enumerate() {
+                    total += Integer::from(d) << i as u32;
+                    if d != 0 {
+                        assert!(d % 2 != 0 && d.abs() < 1 << (width - 1));
+                        let next = &digits[i + 1..digits.len().min(i + width as usize)];
+                        assert!(next.iter().all(|&n| n == 0));
+                    }
+                }
+                assert_eq!(total, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_interleaved() {
+        let a = FieldElement::new(0, 223);
+        let b = FieldElement::new(7, 223);
+        let p1 = EcPoint::new(
+            Finite(FieldElement::new(192, 223)),
+            Finite(FieldElement::new(105, 223)),
+            a.clone(),
+            b.clone(),
+        );
+        let p2 = EcPoint::new(
+            Finite(FieldElement::new(47, 223)),
+            Finite(FieldElement::new(71, 223)),
+            a.clone(),
+            b.clone(),
+        );
+        let scale = |k: i32, p: &EcPoint| match k < 0 {
+            true => Integer::from(-k) * &(-p),
+            false => Integer::from(k) * p,
+        };
+        for (k1, k2) in [(0, 0), (1, 0), (0, 5), (13, 29), (-7, 100), (250, -3)] {
+            let expected = &scale(k1, &p1) + &scale(k2, &p2);
+            let terms = [(&p1, Integer::from(k1)), (&p2, Integer::from(k2))];
+            for width in [2, 4, 5] {
+                assert_eq!(mul_interleaved(&terms, width), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_mul_mixed_widths() {
+        let a = FieldElement::new(0, 223);
+        let b = FieldElement::new(7, 223);
+        let p1 = EcPoint::new(
+            Finite(FieldElement::new(192, 223)),
+            Finite(FieldElement::new(105, 223)),
+            a.clone(),
+            b.clone(),
+        );
+        let p2 = EcPoint::new(
+            Finite(FieldElement::new(47, 223)),
+            Finite(FieldElement::new(71, 223)),
+            a,
+            b,
+        );
+        let wide = odd_multiples(&p1, 6);
+        let narrow = odd_multiples(&p2, 3);
+        assert_eq!(wide.len(), 16);
+        assert_eq!(narrow.len(), 2);
+        assert_eq!(EcPoint::from(wide.get(15)), Integer::from(31) * &p1);
+
+        for (k1, k2) in [(0, 1), (77, 12), (-45, 9), (1000, -1000)] {
+            let expected = &mul_interleaved(&[(&p1, Integer::from(k1))], 2)
+                + &mul_interleaved(&[(&p2, Integer::from(k2))], 2);
+            let terms = [(&wide, Integer::from(k1)), (&narrow, Integer::from(k2))];
+            assert_eq!(multi_mul(&terms), expected);
+        }
+    }
+
+    #[test]
+    fn test_try_new_and_try_add() {
+        let fe = |v| FieldElement::new(v, 223);
+        let p1 = EcPoint::try_new(Finite(fe(192)), Finite(fe(105)), fe(0), fe(7)).unwrap();
+        let p2 = EcPoint::try_new(Finite(fe(17)), Finite(fe(56)), fe(0), fe(7)).unwrap();
+        assert_eq!(p1.try_add(&p2), Ok(&p1 + &p2));
+
+        assert_eq!(
+            EcPoint::try_new(Finite(fe(200)), Finite(fe(119)), fe(0), fe(7)),
+            Err(CurveError::NotOnCurve)
+        );
+        assert_eq!(
+            EcPoint::try_new(Finite(fe(192)), Infinity, fe(0), fe(7)),
+            Err(CurveError::HalfInfinity)
+        );
+        let x = FieldElement::new(1, 7);
+        assert_eq!(
+            EcPoint::try_new(Finite(x), Finite(fe(105)), fe(0), fe(7)),
+            Err(CurveError::Field(FieldError::OrderMismatch))
+        );
+
+        let other_curve = EcPoint::new(Infinity, Infinity, fe(5), fe(7));
+        assert_eq!(p1.try_add(&other_curve), Err(CurveError::CurveMismatch));
+    }
+
+    // every finite point of y^2 = x^3 + ax + b over F_p, by brute force
+    fn all_points(a: i32, b: i32, p: i32) -> Vec<EcPoint> {
+        let fe = |v| FieldElement::new(v, p);
+        (0..p)
+            .flat_map(|x| (0..p).map(move |y| (x, y)))
+            .filter_map(|(x, y)| EcPoint::try_new(Finite(fe(x)), Finite(fe(y)), fe(a), fe(b)).ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_small_torsion() {
+        let fe = |v| FieldElement::new(v, 13);
+        let point = |x, y, b| EcPoint::new(Finite(fe(x)), Finite(fe(y)), fe(0), fe(b));
+
+        // y^2 = x^3 + 1: (-1, 0) has order 2, (0, ±1) order 3 and (2, ±3) order 6
+        let two = point(12, 0, 1);
+        assert!(two.is_two_torsion() && !two.is_three_torsion());
+        assert_eq!(two.small_order(10), Some(2));
+        for y in [1, 12] {
+            let three = point(0, y, 1);
+            assert!(three.is_three_torsion() && !three.is_two_torsion());
+            assert_eq!(three.small_order(10), Some(3));
+        }
+        let six = point(2, 3, 1);
+        assert!(!six.is_two_torsion() && !six.is_three_torsion());
+        assert_eq!(six.small_order(6), Some(6));
+        assert_eq!(six.small_order(5), None);
+        assert_eq!((&six + &six).small_order(10), Some(3));
+        assert_eq!((Integer::from(3) * &six).small_order(10), Some(2));
+
+        let infinity = EcPoint::new(Infinity, Infinity, fe(0), fe(1));
+        assert!(!infinity.is_two_torsion() && !infinity.is_three_torsion());
+        assert_eq!(infinity.small_order(1), Some(1));
+        assert_eq!(infinity.small_order(0), None);
+
+        // y^2 = x^3 - x: the three points with y = 0 are all of order 2
+        let two_torsion: Vec<EcPoint> = all_points(12, 0, 13)
+            .into_iter()
+            .filter(EcPoint::is_two_torsion)
+            .collect();
+        assert_eq!(two_torsion.len(), 3);
+        assert!(two_torsion.iter().all(|p| p.small_order(2) == Some(2)));
+    }
+
+    #[test]
+    fn test_torsion_agrees_with_small_order() {
+        for (a, b, p) in [
+            (0, 1, 13),
+            (12, 0, 13),
+            (0, 7, 223),
+            (2, 3, 97),
+            (0, 1, 101),
+        ] {
+            for point in all_points(a, b, p) {
+                let order = point.small_order(3);
+                assert_eq!(point.is_two_torsion(), order == Some(2), "{point}");
+                assert_eq!(point.is_three_torsion(), order == Some(3), "{point}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_isomorphism_preserves_addition() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(186);
+        let points = many_points();
+        let fe = |v| FieldElement::new(v, 223);
+        let infinity = EcPoint::new(Infinity, Infinity, fe(0), fe(7));
+
+        for _ in 0..8 {
+            let phi = Isomorphism::new(FieldElement::random_nonzero(&mut rng, 223));
+            let (a, b) = phi.map_curve(&fe(0), &fe(7));
+            assert_eq!(a, fe(0));
+            assert_eq!(b, &fe(7) * &phi.u().pow(6));
+
+            for pair in points.chunks(2).take(10) {
+                let (p, q) = (&pair[0], &pair[1]);
+                let sum = phi.map_point(&(p + q)).unwrap();
+                assert_eq!(sum, &phi.map_point(p).unwrap() + &phi.map_point(q).unwrap());
+                // and back again
+                let back = phi.inverse().map_point(&phi.map_point(p).unwrap());
+                assert_eq!(back.as_ref(), Ok(p));
+            }
+            let mapped = phi.map_point(&infinity).unwrap();
+            assert_eq!(mapped, EcPoint::new(Infinity, Infinity, a, b));
+        }
+    }
+
+    #[test]
+    fn test_isomorphism_errors() {
+        let fe = |v| FieldElement::new(v, 223);
+        assert_eq!(
+            Isomorphism::try_new(fe(0)),
+            Err(CurveError::ZeroIsomorphism)
+        );
+        let point = EcPoint::new(Finite(fe(192)), Finite(fe(105)), fe(0), fe(7));
+        let phi = Isomorphism::new(FieldElement::new(3, 97));
+        assert_eq!(
+            phi.map_point(&point),
+            Err(CurveError::Field(FieldError::OrderMismatch))
+        );
+        // a point edited off its curve stays off the mapped one
+        let mut off_curve = point;
+        off_curve.b = fe(8);
+        assert_eq!(
+            Isomorphism::new(fe(5)).map_point(&off_curve),
+            Err(CurveError::NotOnCurve)
+        );
+        // u = -1 is the identity on x and negation on y
+        let minus_one = Isomorphism::new(fe(222));
+        let p = EcPoint::new(Finite(fe(192)), Finite(fe(105)), fe(0), fe(7));
+        assert_eq!(minus_one.map_point(&p), Ok(-&p));
+    }
+
+    #[test]
+    fn test_point_views() {
+        let points = many_points();
+        let table: PointTable = points.iter().cloned().collect();
+        assert_eq!(table.len(), 100);
+        assert_eq!(table.curve(), (&points[0].a, &points[0].b));
+        for (i, view) in table.iter().enumerate() {
+            assert_eq!(view, points[i].view());
+            assert_eq!(EcPoint::from(view), points[i]);
+        }
+        let (p, q) = (table.get(3), table.get(60));
+        assert_eq!(p + q, &points[3] + &points[60]);
+        assert_eq!(p + p, &points[3] + &points[3]);
+        assert_eq!(&points[7] + q, &points[7] + &points[60]);
+        assert_eq!(-p, -&points[3]);
+        let minus_p = -p;
+        assert_eq!((p + minus_p.view()).x, Infinity);
+
+        let other = EcPoint::new(Infinity, Infinity, FieldElement::new(5, 223), p.b.clone());
+        assert_eq!(p.try_add(other.view()), Err(CurveError::CurveMismatch));
+    }
+
+    #[test]
+    #[should_panic(expected = "not all on the same curve")]
+    fn test_point_table_one_curve() {
+        let mut points = many_points();
+        points[1].b = FieldElement::new(8, 223);
+        PointTable::new(points);
+    }
+
+    // inline size plus allocated limbs
+    fn field_bytes(element: &FieldElement) -> usize {
+        let limbs = |n: &Integer| n.capacity().div_ceil(8);
+        std::mem::size_of::<FieldElement>() + limbs(&element.value) + limbs(&element.order)
+    }
+
+    #[test]
+    fn test_point_table_memory() {
+        use crate::ecc::curves::SECP256K1;
+        let g = SECP256K1.generator();
+        let table = odd_multiples(&g, 6);
+        let points: Vec<EcPoint> = table.iter().map(EcPoint::from).collect();
+
+        let coordinate = |c: &Bounded<FieldElement>| match c {
+            Finite(c) => field_bytes(c),
+            Infinity => std::mem::size_of::<FieldElement>(),
+        };
+        let point_bytes: usize = points
+            .iter()
+            .map(|p| coordinate(&p.x) + coordinate(&p.y) + field_bytes(&p.a) + field_bytes(&p.b))
+            .sum();
+        let entry_bytes: usize = table
+            .iter()
+            .map(|p| coordinate(p.x) + coordinate(p.y))
+            .sum();
+        // a and b each hold a 256-bit order as well, so dropping them leaves an entry at under
+        // two thirds of a point
+        assert!(
+            3 * entry_bytes < 2 * point_bytes,
+            "{entry_bytes} vs {point_bytes}"
+        );
+    }
+
+    #[test]
+    fn test_hasse_interval() {
+        // floor(sqrt(892)) = 29
+        let p = Integer::from(223);
+        assert_eq!(hasse_interval(&p), (Integer::from(195), Integer::from(253)));
+        let order = Integer::from(all_points(0, 7, 223).len() + 1);
+        assert!(is_plausible_order(&order, &p));
+        assert!(is_plausible_order(&Integer::from(253), &p));
+        assert!(!is_plausible_order(&Integer::from(254), &p));
+        assert!(!is_plausible_order(&Integer::from(194), &p));
+        assert!(!is_plausible_order(&Integer::from(446), &p));
+
+        // for prime powers 4p is a perfect square and the bounds are met exactly, as by the
+        // supersingular curves over F_4 and F_9
+        assert_eq!(
+            hasse_interval(&Integer::from(4)),
+            (Integer::from(1), Integer::from(9))
+        );
+        assert_eq!(
+            hasse_interval(&Integer::from(9)),
+            (Integer::from(4), Integer::from(16))
+        );
+        // just below a square, sqrt(4 * 24) = 9.8 rounds down
+        assert_eq!(
+            hasse_interval(&Integer::from(24)),
+            (Integer::from(16), Integer::from(34))
+        );
+    }
+
+    #[test]
+    fn test_find_generator() {
+        let fe = |v| FieldElement::new(v, 223);
+        let bound = Integer::from(1000);
+
+        // y^2 = x^3 + x + 1 has 244 = 4 * 61 points and a cyclic group
+        let order = Integer::from(244);
+        let g = find_generator(&fe(1), &fe(1), &order, &bound).unwrap();
+        assert_eq!(g.x, Finite(fe(4)));
+        assert_eq!(g.small_order(1000), Some(244));
+        for q in [2u32, 61] {
+            assert_ne!(((order.clone() / q) * &g).x, Infinity);
+        }
+
+        // 252 points, but 2 * Z/126 rather than Z/252
+        assert_eq!(
+            find_generator(&fe(0), &fe(7), &Integer::from(252), &bound),
+            None
+        );
+        // the search never starts when the group is too big
+        assert_eq!(
+            find_generator(&fe(1), &fe(1), &order, &Integer::from(243)),
+            None
+        );
+        assert_eq!(prime_factors(&Integer::from(252)), [2, 3, 7]);
+        assert_eq!(prime_factors(&Integer::from(229)), [229]);
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        use crate::ecc::curves::{SECP256K1, TOY223};
+
+        for curve in [TOY223, SECP256K1] {
+            let (a, b) = curve.coefficients();
+            let n = curve.order();
+            let hash = |seed: &[u8]| hash_to_curve(seed, &a, &b, &n, curve.h).unwrap();
+
+            let h = hash(b"galactic_credit pedersen H");
+            assert_eq!(hash(b"galactic_credit pedersen H"), h);
+            assert_eq!(h.validate(), Ok(()));
+            assert_ne!(h.x, Infinity);
+            assert_eq!((n.clone() * &h).x, Infinity, "{}", curve.name);
+            assert_ne!(h, curve.generator());
+            if curve.name == "secp256k1" {
+                assert_ne!(hash(b"galactic_credit pedersen J"), h);
+                assert_ne!(hash(b""), h);
+            }
+        }
+
+        // the 223 subgroup has only 6 points besides Infinity, so count distinct outputs
+        let (a, b) = TOY223.coefficients();
+        let n = TOY223.order();
+        let mut seen: Vec<EcPoint> = Vec::new();
+        for i in 0..50u8 {
+            let point = hash_to_curve(&[i], &a, &b, &n, TOY223.h).unwrap();
+            if !seen.contains(&point) {
+                seen.push(point);
+            }
+        }
+        assert_eq!(seen.len(), 6);
+    }
+
+    #[test]
+    fn test_hash_to_curve_errors() {
+        use crate::ecc::curves::TOY223;
+
+        let (a, b) = TOY223.coefficients();
+        // without clearing the cofactor the point is almost never in the order 7 subgroup
+        assert_eq!(
+            hash_to_curve(b"seed", &a, &b, &TOY223.order(), 1),
+            Err(CurveError::NotInSubgroup)
+        );
+        // y^2 = x^3 + 7 over F_223 has 252 points, so a cofactor of 252 clears all of them
+        assert_eq!(
+            hash_to_curve(b"seed", &a, &b, &TOY223.order(), 252),
+            Err(CurveError::HashToCurveFailed)
+        );
+    }
+
+    #[test]
+    fn test_twist() {
+        let fe = |v| FieldElement::new(v, 223);
+        let (a, b) = (fe(0), fe(7));
+        let d = (2..223).map(fe).find(|d| !d.is_square()).unwrap();
+        let (twist_a, twist_b) = twist(&a, &b, &d).unwrap();
+        let as_i32 = |v: &FieldElement| v.value.to_i32().unwrap();
+
+        // counting Infinity on each curve
+        let curve = all_points(0, 7, 223);
+        let twisted = all_points(as_i32(&twist_a), as_i32(&twist_b), 223);
+        assert_eq!(curve.len() + 1 + twisted.len() + 1, 2 * 223 + 2);
+
+        let mut found = 0;
+        for x in (0..223).map(fe) {
+            match twist_point(&a, &b, &d, &x) {
+                Ok(point) => {
+                    assert!(twisted.contains(&point));
+                    assert!(!curve.iter().any(|p| p.x == Finite(x.clone())));
+                    found += 1;
+                }
+                Err(err) => {
+                    assert_eq!(err, CurveError::NotOnTwist);
+                    assert!(curve.iter().any(|p| p.x == Finite(x.clone())));
+                }
+            }
+        }
+        // one x for each pair of twist points, apart from the three roots of x^3 + 7 (6 is
+        // one), which are on both curves with y = 0 and which twist_point refuses
+        let roots = curve.iter().filter(|p| p.is_two_torsion()).count();
+        assert_eq!(roots, 3);
+        assert_eq!(2 * found + roots, twisted.len());
+    }
+
+    #[test]
+    fn test_twist_errors() {
+        let fe = |v| FieldElement::new(v, 223);
+        assert_eq!(twist(&fe(0), &fe(7), &fe(4)), Err(CurveError::SquareTwist));
+        assert_eq!(
+            twist(&fe(0), &fe(7), &FieldElement::new(2, 5)),
+            Err(CurveError::Field(FieldError::OrderMismatch))
+        );
+    }
+
+    #[test]
+    fn test_batch_double() {
+        // every point of y^2 = x^3 + 7 over F_223, which includes points with y = 0
+        let mut points = all_points(0, 7, 223);
+        let inf = EcPoint::new(Infinity, Infinity, points[0].a.clone(), points[0].b.clone());
+        points.push(inf.clone());
+        let expected: Vec<EcPoint> = points.iter().map(|p| p + p).collect();
+        assert!(expected.contains(&inf));
+
+        let mut doubled = points.clone();
+        batch_double(&mut doubled).unwrap();
+        assert_eq!(doubled, expected);
+        assert_eq!(batch_double(&mut []), Ok(()));
+    }
+
+    #[test]
+    fn test_batch_double_errors() {
+        let fe = |v| FieldElement::new(v, 223);
+        let points = many_points();
+
+        let mut mixed = points.clone();
+        mixed[3] = EcPoint::new(Finite(fe(3)), Finite(fe(60)), fe(0), fe(5));
+        assert_eq!(batch_double(&mut mixed), Err(CurveError::CurveMismatch));
+
+        let mut half = points.clone();
+        half[7].y = Infinity;
+        assert_eq!(batch_double(&mut half), Err(CurveError::HalfInfinity));
+        // nothing is written when any point fails
+        assert_eq!(half[..7], points[..7]);
+    }
+
+    #[test]
+    fn test_validate_points() {
+        let fe = |v| FieldElement::new(v, 223);
+        let mut points = many_points();
+        assert_eq!(validate_points(&points, None), Ok(()));
+        assert_eq!(validate_points(&[], None), Ok(()));
+
+        // off the curve through its public fields
+        points[5].y = Finite(fe(1));
+        // a valid point, but on y^2 = x^3 + 5
+        points[17] = EcPoint::new(Finite(fe(3)), Finite(fe(60)), fe(0), fe(5));
+        points[70].b = fe(8);
+        // half infinity, assembled by hand
+        points[99].x = Infinity;
+        assert_eq!(
+            validate_points(&points, None),
+            Err(vec![
+                (5, CurveError::NotOnCurve),
+                (17, CurveError::CurveMismatch),
+                (70, CurveError::CurveMismatch),
+                (99, CurveError::HalfInfinity),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_points_subgroup() {
+        let fe = |v| FieldElement::new(v, 223);
+        // (15, 86) generates the subgroup of order 7, and the curve has 252 points
+        let g = EcPoint::new(Finite(fe(15)), Finite(fe(86)), fe(0), fe(7));
+        let mut points: Vec<EcPoint> = (0..7).map(|k| Integer::from(k) * &g).collect();
+        let seven = Integer::from(7);
+        assert_eq!(validate_points(&points, Some(&seven)), Ok(()));
+
+        points.push(EcPoint::new(Finite(fe(192)), Finite(fe(105)), fe(0), fe(7)));
+        points.push(Integer::from(3) * &g);
+        assert_eq!(
+            validate_points(&points, Some(&seven)),
+            Err(vec![(7, CurveError::NotInSubgroup)])
+        );
+        assert_eq!(validate_points(&points, None), Ok(()));
+    }
+
+    #[test]
+    fn test_wnaf_table() {
+        use rand_core::{RngCore, SeedableRng};
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(190);
+        let fe = |v| FieldElement::new(v, 223);
+        let p = EcPoint::new(Finite(fe(47)), Finite(fe(71)), fe(0), fe(7));
+        for window in [2, 4, 7] {
+            let table = WnafTable::new(&p, window);
+            assert_eq!(table.window(), window);
+            assert_eq!(table.point(), p);
+            for _ in 0..20 {
+                let k = Integer::from(rng.next_u32() as i64 - (1 << 31));
+                let expected = match k.is_negative() {
+                    true => (-k.clone()) * &(-&p),
+                    false => k.clone() * &p,
+                };
+                assert_eq!(table.mul(&k), expected, "{k}");
+            }
+            assert_eq!(table.mul(&Integer::new()).x, Infinity);
+        }
+        let infinity = EcPoint::new(Infinity, Infinity, fe(0), fe(7));
+        assert_eq!(
+            WnafTable::new(&infinity, 3).mul(&Integer::from(5)),
+            infinity
+        );
+    }
+
+    #[test]
+    fn test_wnaf_table_errors() {
+        let fe = |v| FieldElement::new(v, 223);
+        let p = EcPoint::new(Finite(fe(47)), Finite(fe(71)), fe(0), fe(7));
+        for window in [0, 1, MAX_WNAF_WINDOW + 1] {
+            assert_eq!(
+                WnafTable::try_new(&p, window),
+                Err(CurveError::InvalidWindow)
+            );
+        }
+        let mut off_curve = p;
+        off_curve.b = fe(8);
+        assert_eq!(
+            WnafTable::try_new(&off_curve, 4),
+            Err(CurveError::NotOnCurve)
+        );
+    }
+
+    // 100 points on the 223 curve, enough to cross PARALLEL_THRESHOLD
+    fn many_points() -> Vec<EcPoint> {
+        let fe = |v| FieldElement::new(v, 223);
+        let g = EcPoint::new(Finite(fe(47)), Finite(fe(71)), fe(0), fe(7));
+        (1..=100).map(|k| &g * Integer::from(k * 7 + 3)).collect()
+    }
+
+    #[test]
+    fn test_point_order() {
+        use rand_core::{RngCore, SeedableRng};
+
+        let fe = |v| FieldElement::new(v, 223);
+        let mut points = all_points(0, 7, 223);
+        points.push(EcPoint::new(Infinity, Infinity, fe(0), fe(7)));
+        let mut sorted = points.clone();
+        sorted.sort();
+
+        // Fisher-Yates, then sort again
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(197);
+        for _ in 0..5 {
+            for i in (1..points.len()).rev() {
+                points.swap(i, rng.next_u32() as usize % (i + 1));
+            }
+            let mut resorted = points.clone();
+            resorted.sort();
+            assert_eq!(resorted, sorted);
+        }
+
+        assert_eq!(sorted[0].x, Infinity);
+        assert_eq!(sorted[0].canonical_bytes(), [0x00]);
+        for pair in sorted.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].canonical_bytes() < pair[1].canonical_bytes());
+            assert_ne!(pair[0], pair[1]);
+        }
+        // same x: the even y comes first
+        let (even, odd) = (&sorted[1], &sorted[2]);
+        assert_eq!(even.x, odd.x);
+        assert_eq!(*even.canonical_bytes().last().unwrap(), 0);
+        assert_eq!(*odd.canonical_bytes().last().unwrap(), 1);
+        assert_eq!(even.canonical_bytes().len(), 3);
+
+        // agrees with == even across curves, where the bytes alone would tie
+        let p = EcPoint::new(Finite(fe(3)), Finite(fe(60)), fe(0), fe(5));
+        let mut q = p.clone();
+        q.b = fe(6);
+        assert_eq!(p.canonical_bytes(), q.canonical_bytes());
+        assert_ne!(p.cmp(&q), std::cmp::Ordering::Equal);
+        assert_eq!(p.cmp(&p.clone()), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_batch_ops_match_serial() {
+        let points = many_points();
+        let serial = points[1..]
+            .iter()
+            .fold(points[0].clone(), |sum, point| &sum + point);
+        assert_eq!(sum_points(&points), serial);
+        assert_eq!(sum_points(&points[..1]), points[0]);
+
+        let tables: Vec<PointTable> = points.iter().map(|p| odd_multiples(p, 4)).collect();
+        let terms: Vec<(&PointTable, Integer)> = tables
+            .iter()
+            .enumerate()
+            .map(|(i, table)| (table, Integer::from(i as i32 * 37 - 1000)))
+            .collect();
+        assert_eq!(multi_mul(&terms), multi_mul_serial(&terms));
+
+        let mut points = points;
+        points[41].b = FieldElement::new(8, 223);
+        let results = validate_each(&points);
+        assert_eq!(results[41], Err(CurveError::NotOnCurve));
+        assert!(
+            results
+                .iter()
+                .enumerate()
+                .all(|(i, r)| i == 41 || r.is_ok())
+        );
+    }
 }
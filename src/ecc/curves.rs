@@ -0,0 +1,206 @@
+use super::curve::{Bounded::Finite, EcPoint, is_plausible_order};
+use super::field::{FieldElement, Pow};
+use rug::Integer;
+use rug::integer::IsPrime;
+use std::fmt::Display;
+
+// Domain parameters of a short Weierstrass curve y^2 = x^3 + ax + b over F_p, stored as hex
+// so they can be consts. n is the order of G and h the cofactor, #E(F_p) = h * n
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveParams {
+    pub name: &'static str,
+    pub p: &'static str,
+    pub a: &'static str,
+    pub b: &'static str,
+    pub gx: &'static str,
+    pub gy: &'static str,
+    pub n: &'static str,
+    pub h: u32,
+}
+
+// SEC 2 section 2.4.1
+pub const SECP256K1: CurveParams = CurveParams {
+    name: "secp256k1",
+    p: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+    a: "0000000000000000000000000000000000000000000000000000000000000000",
+    b: "0000000000000000000000000000000000000000000000000000000000000007",
+    gx: "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    gy: "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+    n: "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+    h: 1,
+};
+
+// SEC 2 section 2.4.2, NIST P-256, a = -3 mod p
+pub const SECP256R1: CurveParams = CurveParams {
+    name: "secp256r1",
+    p: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    a: "ffffffff00000001000000000000000000000000fffffffffffffffffffffffc",
+    b: "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+    gx: "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+    gy: "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+    n: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    h: 1,
+};
+
+// The y^2 = x^3 + 7 over F_223 example from Programming Bitcoin. The curve has 252 points, and
+// (15, 86) generates the subgroup of prime order 7, so the cofactor is 36
+pub const TOY223: CurveParams = CurveParams {
+    name: "toy223",
+    p: "df",
+    a: "0",
+    b: "7",
+    gx: "f",
+    gy: "56",
+    n: "7",
+    h: 36,
+};
+
+pub const CURVES: &[CurveParams] = &[SECP256K1, SECP256R1, TOY223];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveParamsError {
+    CompositePrime,
+    CompositeOrder,
+    SingularCurve,
+    GeneratorNotOnCurve,
+    OutsideHasseBound,
+}
+
+impl Display for CurveParamsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurveParamsError::CompositePrime => write!(f, "field modulus p is not prime"),
+            CurveParamsError::CompositeOrder => write!(f, "generator order n is not prime"),
+            CurveParamsError::SingularCurve => write!(f, "curve is singular, 4a^3 + 27b^2 = 0"),
+            CurveParamsError::GeneratorNotOnCurve => write!(f, "generator is not on the curve"),
+            CurveParamsError::OutsideHasseBound => {
+                write!(f, "h * n is outside the Hasse bound for p")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CurveParamsError {}
+
+impl CurveParams {
+    pub fn by_name(name: &str) -> Option<&'static CurveParams> {
+        CURVES.iter().find(|curve| curve.name == name)
+    }
+
+    pub fn prime(&self) -> Integer {
+        Integer::from_str_radix(self.p, 16).unwrap()
+    }
+
+    pub fn order(&self) -> Integer {
+        Integer::from_str_radix(self.n, 16).unwrap()
+    }
+
+    pub fn field_element(&self, value: &str) -> FieldElement {
+        FieldElement::from_str(value, self.p)
+    }
+
+    // (a, b) in the order EcPoint::new takes them
+    pub fn coefficients(&self) -> (FieldElement, FieldElement) {
+        (self.field_element(self.a), self.field_element(self.b))
+    }
+
+    pub fn generator(&self) -> EcPoint {
+        let (a, b) = self.coefficients();
+        EcPoint::new(
+            Finite(self.field_element(self.gx)),
+            Finite(self.field_element(self.gy)),
+            a,
+            b,
+        )
+    }
+
+    // Cheap checks only. n*G = Infinity costs a full scalar multiplication, so the tests do that
+    pub fn validate(&self) -> Result<(), CurveParamsError> {
+        let p = self.prime();
+        let n = self.order();
+        if p.is_probably_prime(30) == IsPrime::No {
+            return Err(CurveParamsError::CompositePrime);
+        }
+        if n.is_probably_prime(30) == IsPrime::No {
+            return Err(CurveParamsError::CompositeOrder);
+        }
+
+        let (a, b) = self.coefficients();
+        let discriminant =
+            &(&a.pow(3) * &self.field_element("4")) + &(&b.pow(2) * &self.field_element("1b"));
+        if discriminant.is_zero() {
+            return Err(CurveParamsError::SingularCurve);
+        }
+
+        let x = self.field_element(self.gx);
+        let y = self.field_element(self.gy);
+        if y.pow(2) != &(&x.pow(3) + &(&a * &x)) + &b {
+            return Err(CurveParamsError::GeneratorNotOnCurve);
+        }
+
+        if !is_plausible_order(&(n * self.h), &p) {
+            return Err(CurveParamsError::OutsideHasseBound);
+        }
+        Ok(())
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::curve::Bounded::Infinity;
+
+    #[test]
+    fn test_registry() {
+        for curve in CURVES {
+            assert_eq!(CurveParams::by_name(curve.name), Some(curve));
+            assert_eq!(curve.validate(), Ok(()), "{}", curve.name);
+        }
+        assert_eq!(CurveParams::by_name("secp256k1"), Some(&SECP256K1));
+        assert_eq!(CurveParams::by_name("P-256"), None);
+    }
+
+    #[test]
+    fn test_generator_order() {
+        for curve in CURVES {
+            let g = curve.generator();
+            let (a, b) = curve.coefficients();
+            let infinity = EcPoint::new(Infinity, Infinity, a, b);
+            assert_eq!(&g * curve.order(), infinity, "{}", curve.name);
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_params() {
+        let cases = [
+            (
+                CurveParams { p: "dd", ..TOY223 },
+                CurveParamsError::CompositePrime,
+            ),
+            (
+                CurveParams { n: "15", ..TOY223 },
+                CurveParamsError::CompositeOrder,
+            ),
+            (
+                CurveParams { b: "0", ..TOY223 },
+                CurveParamsError::SingularCurve,
+            ),
+            (
+                CurveParams { gy: "48", ..TOY223 },
+                CurveParamsError::GeneratorNotOnCurve,
+            ),
+            (
+                CurveParams { h: 1, ..TOY223 },
+                CurveParamsError::OutsideHasseBound,
+            ),
+        ];
+        for (curve, err) in cases {
+            assert_eq!(curve.validate(), Err(err));
+        }
+    }
+}
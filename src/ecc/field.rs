@@ -1,47 +1,325 @@
-use rug::ops::{RemRounding, RemRoundingAssign};
+use rand_core::{CryptoRng, RngCore};
+use rug::integer::Order;
+use rug::ops::RemRoundingAssign;
 use rug::{Complete, Integer};
 use std::fmt::Display;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldError {
+    ValueOutOfRange,
+    InvalidOrder,
+    OrderMismatch,
+    DivisionByZero,
+    BadHex,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldError::ValueOutOfRange => write!(f, "value is not in [0, order)"),
+            FieldError::InvalidOrder => write!(f, "field order must be at least 2"),
+            FieldError::OrderMismatch => write!(f, "FieldElements are not the same order"),
+            FieldError::DivisionByZero => write!(f, "division by zero"),
+            FieldError::BadHex => write!(f, "expected a string of hex digits"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+// Masking to the bit length of the bound keeps every draw accepted with probability above 1/2,
+// so reaching this many rejections means the RNG is broken, not unlucky (odds below 2^-128)
+const MAX_SAMPLE_ATTEMPTS: u32 = 128;
+
+// Uniform in [0, bound) by rejection sampling: ceil(bits/8) random bytes with the excess top
+// bits cleared, retried until below bound. Unbiased, unlike reducing a random number mod bound.
+// Also returns how many draws it took, for the tests
+pub(crate) fn sample_below<R: CryptoRng + RngCore>(rng: &mut R, bound: &Integer) -> (Integer, u32) {
+    assert!(*bound > 0, "Error: cannot sample below {bound}");
+    let bits = bound.significant_bits();
+    let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+    for attempt in 1..=MAX_SAMPLE_ATTEMPTS {
+        rng.fill_bytes(&mut bytes);
+        bytes[0] &= 0xff >> (bytes.len() as u32 * 8 - bits);
+        let candidate = Integer::from_digits(&bytes, Order::Msf);
+        if candidate < *bound {
+            return (candidate, attempt);
+        }
+    }
+    panic!("Error: rng gave {MAX_SAMPLE_ATTEMPTS} values out of range in a row, it is broken")
+}
+
+// Inverts every element with one field inversion and about 3(n - 1) multiplications
+// (Montgomery's trick): invert the running product, then peel it apart from the end.
+// Fails if any element is zero or the orders differ
+pub fn batch_invert(elements: &[FieldElement]) -> Result<Vec<FieldElement>, FieldError> {
+    let Some(first) = elements.first() else {
+        return Ok(Vec::new());
+    };
+    // prefix[i] = elements[0] * ... * elements[i - 1]
+    let mut prefix = Vec::with_capacity(elements.len());
+    let mut running = first.with_value(Integer::from(1));
+    for element in elements {
+        if element.is_zero() {
+            return Err(FieldError::DivisionByZero);
+        }
+        prefix.push(running.clone());
+        running = running.try_mul(element)?;
+    }
+    let mut inverse = running.with_value(Integer::from(1)).try_div(&running)?;
+    let mut inverses = vec![inverse.clone(); elements.len()];
+    for i in (0..elements.len()).rev() {
+        inverses[i] = &inverse * &prefix[i];
+        inverse = &inverse * &elements[i];
+    }
+    Ok(inverses)
+}
+
+// One step of an addition chain. Chains run over an accumulator that starts at the base, plus a
+// list of saved values whose index 0 is the base itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainOp {
+    // square the accumulator this many times
+    Square(u32),
+    // multiply the accumulator by a saved value
+    Mul(usize),
+    // save the accumulator, as the next index
+    Save,
+}
+
+// A fixed exponent spelled out as squarings and multiplications. For exponents like p - 2 a
+// handmade chain needs far fewer multiplications than square-and-multiply, which spends one on
+// every set bit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdditionChain {
+    pub ops: Vec<ChainOp>,
+}
+
+impl AdditionChain {
+    pub fn new(ops: Vec<ChainOp>) -> Self {
+        Self { ops }
+    }
+
+    // base^exponent(). Panics on a Mul of a value that was never saved
+    pub fn exec(&self, base: &FieldElement) -> FieldElement {
+        self.run(base.clone(), |acc| acc * acc, |acc, saved| acc * saved)
+    }
+
+    // the exponent the chain computes, the same walk with doubling and adding
+    pub fn exponent(&self) -> Integer {
+        self.run(
+            Integer::from(1),
+            |acc| (acc << 1u32).complete(),
+            |acc, saved| (acc + saved).complete(),
+        )
+    }
+
+    fn run<T: Clone>(&self, base: T, square: impl Fn(&T) -> T, mul: impl Fn(&T, &T) -> T) -> T {
+        let mut acc = base.clone();
+        let mut saved = vec![base];
+        for op in &self.ops {
+            match *op {
+                ChainOp::Square(times) => {
+                    for _ in 0..times {
+                        acc = square(&acc);
+                    }
+                }
+                ChainOp::Mul(index) => {
+                    let value = saved
+                        .get(index)
+                        .unwrap_or_else(|| panic!("Error: no value saved at index {index}"));
+                    acc = mul(&acc, value);
+                }
+                ChainOp::Save => saved.push(acc.clone()),
+            }
+        }
+        acc
+    }
+}
+
 pub trait Pow {
     fn pow<T: Into<Integer>>(&self, exp: T) -> Self;
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct FieldElement {
     pub value: Integer,
     pub order: Integer,
 }
 
 impl FieldElement {
+    // panicking wrapper around try_new, for values known to be in range
     pub fn new<T, U>(value: T, order: U) -> Self
+    where
+        T: Into<Integer>,
+        U: Into<Integer>,
+    {
+        Self::try_new(value, order)
+            .unwrap_or_else(|err| panic!("Error: {err}, cannot create FieldElement"))
+    }
+
+    pub fn try_new<T, U>(value: T, order: U) -> Result<Self, FieldError>
     where
         T: Into<Integer>,
         U: Into<Integer>,
     {
         let value: Integer = value.into();
         let order: Integer = order.into();
-        assert!(
-            !value.is_negative() && value <= order,
-            "Error: value out of range, cannot create FieldElement"
-        );
-        Self { value, order }
+        if order < 2 {
+            return Err(FieldError::InvalidOrder);
+        }
+        if value.is_negative() || value >= order {
+            return Err(FieldError::ValueOutOfRange);
+        }
+        Ok(Self { value, order })
+    }
+
+    // uniform over all of F_order, see sample_below
+    pub fn random_in_range<R, T>(rng: &mut R, order: T) -> Self
+    where
+        R: CryptoRng + RngCore,
+        T: Into<Integer>,
+    {
+        let order: Integer = order.into();
+        let (value, _) = sample_below(rng, &order);
+        Self::new(value, order)
     }
 
+    // uniform over F_order without 0: a uniform draw from [0, order - 1), shifted up by one
+    pub fn random_nonzero<R, T>(rng: &mut R, order: T) -> Self
+    where
+        R: CryptoRng + RngCore,
+        T: Into<Integer>,
+    {
+        let order: Integer = order.into();
+        let (value, _) = sample_below(rng, &(&order - 1u8).complete());
+        Self::new(value + 1u8, order)
+    }
+
+    // panicking wrapper around try_from_str
     pub fn from_str(value: &str, order: &str) -> Self {
-        let value: Integer = Integer::from_str_radix(value, 16).unwrap();
-        let order: Integer = Integer::from_str_radix(order, 16).unwrap();
-        assert!(
-            !value.is_negative() && value <= order,
-            "Error: value out of range, cannot create FieldElement"
-        );
-        Self { value, order }
+        Self::try_from_str(value, order)
+            .unwrap_or_else(|err| panic!("Error: {err}, cannot create FieldElement"))
+    }
+
+    // value and order as big-endian hex without prefix or sign
+    pub fn try_from_str(value: &str, order: &str) -> Result<Self, FieldError> {
+        let parse = |hex: &str| {
+            if hex.is_empty() || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return Err(FieldError::BadHex);
+            }
+            Integer::from_str_radix(hex, 16).map_err(|_| FieldError::BadHex)
+        };
+        Self::try_new(parse(value)?, parse(order)?)
+    }
+
+    fn check_order(&self, rhs: &FieldElement) -> Result<(), FieldError> {
+        if self.order != rhs.order {
+            return Err(FieldError::OrderMismatch);
+        }
+        Ok(())
+    }
+
+    fn with_value(&self, value: Integer) -> FieldElement {
+        let mut value = value;
+        value.rem_euc_assign(&self.order);
+        FieldElement {
+            value,
+            order: self.order.clone(),
+        }
+    }
+
+    pub fn try_add(&self, rhs: &FieldElement) -> Result<FieldElement, FieldError> {
+        self.check_order(rhs)?;
+        Ok(self.with_value((&self.value + &rhs.value).complete()))
+    }
+
+    pub fn try_sub(&self, rhs: &FieldElement) -> Result<FieldElement, FieldError> {
+        self.check_order(rhs)?;
+        Ok(self.with_value((&self.value - &rhs.value).complete()))
+    }
+
+    pub fn try_mul(&self, rhs: &FieldElement) -> Result<FieldElement, FieldError> {
+        self.check_order(rhs)?;
+        Ok(self.with_value((&self.value * &rhs.value).complete()))
+    }
+
+    // a / b = a * b^(p - 2), which needs b != 0
+    pub fn try_div(&self, rhs: &FieldElement) -> Result<FieldElement, FieldError> {
+        self.check_order(rhs)?;
+        if rhs.is_zero() {
+            return Err(FieldError::DivisionByZero);
+        }
+        let exp = (&self.order - 2i32).complete();
+        let inverse = rhs
+            .value
+            .pow_mod_ref(&exp, &self.order)
+            .ok_or(FieldError::DivisionByZero)?
+            .complete();
+        Ok(self.with_value((&self.value * &inverse).complete()))
     }
 
     pub fn is_zero(&self) -> bool {
         self.value.is_zero()
     }
 
+    // Euler's criterion, v^((p - 1) / 2) is 1 for the nonzero squares. 0 counts as a square
+    pub fn is_square(&self) -> bool {
+        let exp = (&self.order - 1i32).complete() >> 1u32;
+        let result = self
+            .value
+            .pow_mod_ref(&exp, &self.order)
+            .expect("Error: FieldElement exponent failed")
+            .complete();
+        result <= 1
+    }
+
+    // A square root by Tonelli-Shanks, or None for a non-square. Either root may come back, the
+    // other is its negative
+    pub fn sqrt(&self) -> Option<FieldElement> {
+        if !self.is_square() {
+            return None;
+        }
+        if self.is_zero() || self.order == 2 {
+            return Some(self.clone());
+        }
+        let p = &self.order;
+        let pow = |base: &Integer, exp: &Integer| {
+            base.pow_mod_ref(exp, p)
+                .expect("Error: FieldElement exponent failed")
+                .complete()
+        };
+        // p - 1 = q * 2^s with q odd
+        let p_minus_1 = (p - 1i32).complete();
+        let s = p_minus_1.find_one(0).expect("p - 1 is nonzero");
+        let q = p_minus_1 >> s;
+        let mut z = Integer::from(2);
+        while self.with_value(z.clone()).is_square() {
+            z += 1;
+        }
+
+        let mut m = s;
+        let mut c = pow(&z, &q);
+        let mut t = pow(&self.value, &q);
+        let mut r = pow(&self.value, &((&q + 1i32).complete() >> 1u32));
+        // invariant: r^2 = t * value, with t of order 2^i for some i < m
+        while t != 1 {
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while t_pow != 1 {
+                t_pow = t_pow.square() % p;
+                i += 1;
+            }
+            let b = pow(&c, &(Integer::from(1) << (m - i - 1)));
+            r = r * &b % p;
+            c = b.square() % p;
+            t = t * &c % p;
+            m = i;
+        }
+        Some(self.with_value(r))
+    }
+
     pub fn scale(&self, scalar: i32) -> Self {
         let mut result = Integer::from(&self.value * scalar);
         result.rem_euc_assign(&self.order);
@@ -58,6 +336,25 @@ impl Display for FieldElement {
     }
 }
 
+// The order is public and compared as usual, the values are padded to the byte width of the
+// order and compared without stopping at the first difference
+#[cfg(feature = "ct")]
+impl subtle::ConstantTimeEq for FieldElement {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        if self.order != other.order {
+            return subtle::Choice::from(0);
+        }
+        let width = self.order.significant_bits().div_ceil(8) as usize;
+        let pad = |value: &Integer| {
+            let mut bytes = vec![0u8; width];
+            let digits = value.to_digits::<u8>(rug::integer::Order::Msf);
+            bytes[width - digits.len()..].copy_from_slice(&digits);
+            bytes
+        };
+        pad(&self.value).ct_eq(&pad(&other.value))
+    }
+}
+
 impl Neg for &FieldElement {
     type Output = FieldElement;
 
@@ -72,21 +369,14 @@ impl Neg for &FieldElement {
     }
 }
 
+// The operators are thin wrappers over the try_ methods that panic on mismatched orders (and
+// division by zero), the way the book's Python raises. Use try_ for anything data dependent
 impl Add<&FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn add(self, rhs: &FieldElement) -> Self::Output {
-        assert!(
-            self.order == rhs.order,
-            "Error: add operation failed because FieldElements are not the same order"
-        );
-        let mut result = (&self.value + &rhs.value).complete();
-        result.rem_euc_assign(&self.order);
-
-        FieldElement {
-            value: result,
-            order: self.order.clone(),
-        }
+        self.try_add(rhs)
+            .unwrap_or_else(|err| panic!("Error: add operation failed because {err}"))
     }
 }
 
@@ -94,18 +384,8 @@ impl Sub<&FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn sub(self, rhs: &FieldElement) -> Self::Output {
-        assert!(
-            self.order == rhs.order,
-            "Error: sub operation failed because FieldElements are not the same order"
-        );
-
-        let mut result = (&self.value - &rhs.value).complete();
-        result.rem_euc_assign(&self.order);
-
-        FieldElement {
-            value: result,
-            order: self.order.clone(),
-        }
+        self.try_sub(rhs)
+            .unwrap_or_else(|err| panic!("Error: sub operation failed because {err}"))
     }
 }
 
@@ -113,18 +393,8 @@ impl Mul<&FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn mul(self, rhs: &FieldElement) -> Self::Output {
-        assert!(
-            self.order == rhs.order,
-            "Error: mul operation failed because FieldElements are not the same order"
-        );
-
-        let mut result = (&self.value * &rhs.value).complete();
-        result.rem_euc_assign(&self.order);
-
-        FieldElement {
-            value: result,
-            order: self.order.clone(),
-        }
+        self.try_mul(rhs)
+            .unwrap_or_else(|err| panic!("Error: mul operation failed because {err}"))
     }
 }
 
@@ -132,24 +402,8 @@ impl Div<&FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn div(self, rhs: &FieldElement) -> Self::Output {
-        assert!(
-            self.order == rhs.order,
-            "Error: div operation failed because FieldElements are not the same order"
-        );
-
-        let exp = (&self.order - 2i32).complete();
-        let result = rhs
-            .value
-            .pow_mod_ref(&exp, &self.order)
-            .expect("Error: FieldElement div failed")
-            .complete();
-        let mut result = (&self.value * &result).complete();
-        result.rem_euc_assign(&self.order);
-
-        FieldElement {
-            value: result,
-            order: self.order.clone(),
-        }
+        self.try_div(rhs)
+            .unwrap_or_else(|err| panic!("Error: div operation failed because {err}"))
     }
 }
 
@@ -181,7 +435,7 @@ impl Pow for FieldElement {
 #[macro_export]
 macro_rules! field_element {
     ($num:expr, $prime:expr) => {
-        FieldElement::new($num, $prime)
+        $crate::ecc::field::FieldElement::new($num, $prime)
     };
 }
 
@@ -194,6 +448,159 @@ mod tests {
     // Import the outer scope
     use super::*;
 
+    // counts of each value over draws samples of F_19
+    fn histogram(draws: usize, nonzero: bool) -> ([usize; 19], u32) {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(19);
+        let mut counts = [0usize; 19];
+        let mut max_attempts = 0;
+        for _ in 0..draws {
+            let value = if nonzero {
+                FieldElement::random_nonzero(&mut rng, 19).value
+            } else {
+                let (value, attempts) = sample_below(&mut rng, &Integer::from(19));
+                max_attempts = max_attempts.max(attempts);
+                value
+            };
+            counts[value.to_usize().unwrap()] += 1;
+        }
+        (counts, max_attempts)
+    }
+
+    #[test]
+    fn test_addition_chain() {
+        // x^2, x^3 saved, then (x^3)^4 * x^3 = x^15, then x^31
+        let chain = AdditionChain::new(vec![
+            ChainOp::Square(1),
+            ChainOp::Mul(0),
+            ChainOp::Save,
+            ChainOp::Square(2),
+            ChainOp::Mul(1),
+            ChainOp::Square(1),
+            ChainOp::Mul(0),
+        ]);
+        assert_eq!(chain.exponent(), 31);
+        for v in 0..223 {
+            let x = FieldElement::new(v, 223);
+            assert_eq!(chain.exec(&x), x.pow(31));
+        }
+        assert_eq!(AdditionChain::new(Vec::new()).exponent(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no value saved at index 1")]
+    fn test_addition_chain_bad_index() {
+        AdditionChain::new(vec![ChainOp::Mul(1)]).exec(&FieldElement::new(3, 223));
+    }
+
+    #[test]
+    fn test_batch_invert() {
+        let elements: Vec<FieldElement> = (1..19).map(|v| FieldElement::new(v, 19)).collect();
+        let inverses = batch_invert(&elements).unwrap();
+        for (element, inverse) in elements.iter().zip(&inverses) {
+            assert_eq!(inverse, &(&FieldElement::new(1, 19) / element));
+        }
+        assert_eq!(batch_invert(&[]), Ok(Vec::new()));
+        assert_eq!(
+            batch_invert(&[FieldElement::new(3, 19), FieldElement::new(0, 19)]),
+            Err(FieldError::DivisionByZero)
+        );
+        assert_eq!(
+            batch_invert(&[FieldElement::new(3, 19), FieldElement::new(3, 23)]),
+            Err(FieldError::OrderMismatch)
+        );
+    }
+
+    #[test]
+    fn test_is_square_and_sqrt() {
+        // 223 = 3 mod 4 and 337 = 1 mod 16, so both the one step and the looping case are covered
+        for p in [2, 223, 337] {
+            let squares: Vec<Integer> = (0..p).map(|v| Integer::from(v * v % p)).collect();
+            for v in 0..p {
+                let x = FieldElement::new(v, p);
+                assert_eq!(x.is_square(), squares.contains(&x.value), "{v} mod {p}");
+                match x.sqrt() {
+                    Some(root) => assert_eq!(&root * &root, x),
+                    None => assert!(!x.is_square()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_is_uniform() {
+        // 1000 expected per value, the tolerance is about 5 standard deviations
+        let (counts, max_attempts) = histogram(19_000, false);
+        assert!(
+            counts.iter().all(|c| (850..=1150).contains(c)),
+            "{counts:?}"
+        );
+        // 19 of every 32 masked draws are accepted, so long runs of rejections are rare
+        assert!((2..=30).contains(&max_attempts), "{max_attempts}");
+
+        let (counts, _) = histogram(18_000, true);
+        assert_eq!(counts[0], 0);
+        assert!(
+            counts[1..].iter().all(|c| (850..=1150).contains(c)),
+            "{counts:?}"
+        );
+    }
+
+    #[test]
+    fn test_random_is_deterministic_with_a_seed() {
+        use rand_core::SeedableRng;
+        let p = Integer::from_str_radix(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            16,
+        )
+        .unwrap();
+        let draw = |seed| {
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+            (0..4)
+                .map(|_| FieldElement::random_in_range(&mut rng, p.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+        assert!(draw(7).iter().all(|e| e.value < p));
+    }
+
+    #[test]
+    #[should_panic(expected = "rng gave 128 values out of range")]
+    fn test_random_gives_up_on_a_broken_rng() {
+        struct Stuck;
+        impl rand_core::RngCore for Stuck {
+            fn next_u32(&mut self) -> u32 {
+                u32::MAX
+            }
+            fn next_u64(&mut self) -> u64 {
+                u64::MAX
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                dest.fill(0xff);
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+        impl rand_core::CryptoRng for Stuck {}
+        FieldElement::random_in_range(&mut Stuck, 19);
+    }
+
+    #[cfg(feature = "ct")]
+    #[test]
+    fn test_ct_eq_matches_eq() {
+        use subtle::ConstantTimeEq;
+        let a = FieldElement::new(7, 263);
+        let b = FieldElement::new(7, 263);
+        let c = FieldElement::new(8, 263);
+        let d = FieldElement::new(7, 19);
+        for (x, y) in [(&a, &b), (&a, &c), (&a, &d)] {
+            assert_eq!(bool::from(x.ct_eq(y)), x == y);
+        }
+    }
+
     #[test]
     fn test_equal() {
         let element1 = FieldElement::new(7, 19);
@@ -234,13 +641,13 @@ mod tests {
     #[test]
     fn test_pow() {
         let element = FieldElement::new(7, 19);
-        assert_eq!(element.pow(&Integer::from(3)), FieldElement::new(1, 19));
+        assert_eq!(element.pow(Integer::from(3)), FieldElement::new(1, 19));
 
         let element = FieldElement::new(5, 19);
-        assert_eq!(element.pow(&Integer::from(-3)), FieldElement::new(7, 19));
+        assert_eq!(element.pow(Integer::from(-3)), FieldElement::new(7, 19));
 
         let element = FieldElement::new(7, 19);
-        assert_eq!(element.pow(&Integer::from(-2)), FieldElement::new(7, 19));
+        assert_eq!(element.pow(Integer::from(-2)), FieldElement::new(7, 19));
     }
 
     #[test]
@@ -264,6 +671,48 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_macro_fail() {
-        let element = field_element!(20, 19);
+        field_element!(20, 19);
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(FieldElement::try_new(18, 19), Ok(FieldElement::new(18, 19)));
+        assert_eq!(
+            FieldElement::try_new(19, 19),
+            Err(FieldError::ValueOutOfRange)
+        );
+        assert_eq!(
+            FieldElement::try_new(-1, 19),
+            Err(FieldError::ValueOutOfRange)
+        );
+        assert_eq!(FieldElement::try_new(0, 1), Err(FieldError::InvalidOrder));
+        assert_eq!(
+            FieldElement::try_from_str("a", "13"),
+            Ok(FieldElement::new(10, 19))
+        );
+        assert_eq!(
+            FieldElement::try_from_str("-a", "13"),
+            Err(FieldError::BadHex)
+        );
+        assert_eq!(
+            FieldElement::try_from_str("", "13"),
+            Err(FieldError::BadHex)
+        );
+    }
+
+    #[test]
+    fn test_try_ops() {
+        let a = FieldElement::new(10, 19);
+        let b = FieldElement::new(3, 19);
+        assert_eq!(a.try_add(&b), Ok(&a + &b));
+        assert_eq!(a.try_sub(&b), Ok(&a - &b));
+        assert_eq!(a.try_mul(&b), Ok(&a * &b));
+        assert_eq!(a.try_div(&b), Ok(&a / &b));
+
+        let zero = FieldElement::new(0, 19);
+        assert_eq!(a.try_div(&zero), Err(FieldError::DivisionByZero));
+        let other = FieldElement::new(3, 23);
+        assert_eq!(a.try_add(&other), Err(FieldError::OrderMismatch));
+        assert_eq!(a.try_div(&other), Err(FieldError::OrderMismatch));
     }
 }
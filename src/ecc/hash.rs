@@ -0,0 +1,152 @@
+use core::fmt::Display;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&Sha256::digest(data));
+    result
+}
+
+// double SHA256, used for txids, block hashes and Base58Check checksums
+pub fn hash256(data: &[u8]) -> [u8; 32] {
+    sha256(&sha256(data))
+}
+
+// RIPEMD160(SHA256(data)), used for P2PKH and P2WPKH key hashes
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    let mut result = [0u8; 20];
+    result.copy_from_slice(&Ripemd160::digest(sha256(data)));
+    result
+}
+
+// Digests are stored in the order the hash function produced them. Bitcoin shows txids and
+// block hashes byte-reversed, so the reversed helpers convert to and from that order
+macro_rules! digest_newtype {
+    ($name:ident, $len:expr, $hasher:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name(pub [u8; $len]);
+
+        impl $name {
+            pub fn of(data: &[u8]) -> Self {
+                Self($hasher(data))
+            }
+
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            pub fn to_reversed(&self) -> [u8; $len] {
+                let mut bytes = self.0;
+                bytes.reverse();
+                bytes
+            }
+
+            pub fn from_reversed(bytes: [u8; $len]) -> Self {
+                let mut bytes = bytes;
+                bytes.reverse();
+                Self(bytes)
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                for byte in self.0 {
+                    write!(f, "{byte:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+digest_newtype!(Hash256, 32, hash256);
+digest_newtype!(Hash160, 20, hash160);
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_sha256_nist() {
+        let cases: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(hex(&sha256(data)), expected);
+        }
+        assert_eq!(
+            hex(&sha256(&vec![b'a'; 1_000_000])),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn test_hash256() {
+        assert_eq!(
+            hex(&hash256(b"hello")),
+            "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+        );
+        assert_eq!(Hash256::of(b"hello").0, hash256(b"hello"));
+    }
+
+    #[test]
+    fn test_hash160_generator() {
+        // compressed SEC of the secp256k1 generator
+        let sec: Vec<u8> = (0..66)
+            .step_by(2)
+            .map(|i| {
+                let s = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+                u8::from_str_radix(&s[i..i + 2], 16).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            Hash160::of(&sec).to_string(),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+    }
+
+    #[test]
+    fn test_byte_order() {
+        let h = Hash256::of(b"hello");
+        let reversed = h.to_reversed();
+        assert_eq!(reversed[0], h.0[31]);
+        assert_eq!(Hash256::from_reversed(reversed), h);
+        assert_eq!(
+            Hash256::from(h.to_reversed()).to_string(),
+            "503d8319a48348cdc610a582f7bf754b5833df65038606eb48510790dfc99595"
+        );
+    }
+}
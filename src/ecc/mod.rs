@@ -1,16 +1,29 @@
+// Everything but the hashes does big integer arithmetic through rug, which needs GMP
+#[cfg(feature = "gmp")]
 pub mod curve;
+#[cfg(feature = "gmp")]
+pub mod curves;
+#[cfg(feature = "gmp")]
 pub mod field;
+pub mod hash;
+#[cfg(feature = "gmp")]
+pub mod poly;
+#[cfg(feature = "gmp")]
+pub mod ring;
+#[cfg(feature = "gmp")]
 pub mod secp256k1;
+#[cfg(feature = "gmp")]
+pub mod secp256r1;
+#[cfg(feature = "gmp")]
+pub mod sss;
+#[cfg(feature = "proptest")]
+pub mod strategies;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "gmp"))]
 mod tests {
     use super::*;
     use crate::{ec_point, field_element};
-    use curve::{
-        Bounded::{Finite, Infinity},
-        EcPoint,
-    };
-    use field::FieldElement;
+    use curve::{Bounded::Finite, EcPoint};
 
     #[test]
     fn test_finite_field_ec() {
@@ -18,13 +31,13 @@ mod tests {
         let b = field_element!(7, 223);
         let fe1 = field_element!(192, 223);
         let fe2 = field_element!(105, 223);
-        let p1 = EcPoint::new(Finite(fe1), Finite(fe2), a.clone(), b.clone());
+        EcPoint::new(Finite(fe1), Finite(fe2), a.clone(), b.clone());
         let fe3 = field_element!(17, 223);
         let fe4 = field_element!(56, 223);
-        let p2 = EcPoint::new(Finite(fe3), Finite(fe4), a.clone(), b.clone());
+        EcPoint::new(Finite(fe3), Finite(fe4), a.clone(), b.clone());
         let fe5 = field_element!(1, 223);
         let fe6 = field_element!(193, 223);
-        let p3 = EcPoint::new(Finite(fe5), Finite(fe6), a, b);
+        EcPoint::new(Finite(fe5), Finite(fe6), a, b);
     }
 
     #[test]
@@ -34,7 +47,7 @@ mod tests {
         let b = field_element!(7, 223);
         let fe1 = field_element!(200, 223);
         let fe2 = field_element!(119, 223);
-        let p1 = EcPoint::new(Finite(fe1), Finite(fe2), a, b);
+        EcPoint::new(Finite(fe1), Finite(fe2), a, b);
     }
 
     #[test]
@@ -0,0 +1,260 @@
+use super::field::{FieldElement, batch_invert};
+use rug::Integer;
+use std::fmt::Display;
+
+// Polynomials over F_p and Lagrange interpolation: k points with distinct x pin down exactly one
+// polynomial of degree < k
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolyError {
+    NoPoints,
+    DuplicateX(Integer),
+    OrderMismatch,
+}
+
+impl Display for PolyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolyError::NoPoints => write!(f, "no points to interpolate"),
+            PolyError::DuplicateX(x) => write!(f, "more than one point at x = {x}"),
+            PolyError::OrderMismatch => write!(f, "points are not all over the same field"),
+        }
+    }
+}
+
+impl std::error::Error for PolyError {}
+
+// Coefficients lowest degree first, without trailing zeros. The zero polynomial keeps a single
+// zero coefficient so it still knows its field
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    coefficients: Vec<FieldElement>,
+}
+
+impl Polynomial {
+    // panics on no coefficients or coefficients from different fields
+    pub fn new(coefficients: Vec<FieldElement>) -> Self {
+        let first = coefficients
+            .first()
+            .expect("Error: a polynomial needs at least one coefficient");
+        assert!(
+            coefficients.iter().all(|c| c.order == first.order),
+            "Error: polynomial coefficients are not all over the same field"
+        );
+        let mut coefficients = coefficients;
+        while coefficients.len() > 1 && coefficients.last().is_some_and(FieldElement::is_zero) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn coefficients(&self) -> &[FieldElement] {
+        &self.coefficients
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        match self.coefficients.as_slice() {
+            [c] if c.is_zero() => None,
+            coefficients => Some(coefficients.len() - 1),
+        }
+    }
+
+    // Horner, from the highest coefficient down. Panics if x is over another field
+    pub fn eval(&self, x: &FieldElement) -> FieldElement {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(zero(x), |acc, c| &(&acc * x) + c)
+    }
+}
+
+fn zero(like: &FieldElement) -> FieldElement {
+    FieldElement::new(0, like.order.clone())
+}
+
+fn one(like: &FieldElement) -> FieldElement {
+    FieldElement::new(1, like.order.clone())
+}
+
+// every x and y over one field, and no x twice
+fn check_points(points: &[(FieldElement, FieldElement)]) -> Result<(), PolyError> {
+    let (first, _) = points.first().ok_or(PolyError::NoPoints)?;
+    for (i, (x, y)) in points.iter().enumerate() {
+        if x.order != first.order || y.order != first.order {
+            return Err(PolyError::OrderMismatch);
+        }
+        if points[..i].iter().any(|(seen, _)| seen == x) {
+            return Err(PolyError::DuplicateX(x.value.clone()));
+        }
+    }
+    Ok(())
+}
+
+// 1 / prod over j != i of (x_i - x_j), for every i. Distinct x keeps every product nonzero
+fn weights(points: &[(FieldElement, FieldElement)]) -> Vec<FieldElement> {
+    let denominators: Vec<FieldElement> = points
+        .iter()
+        .enumerate()
+        .map(|(i, (x_i, _))| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(one(x_i), |acc, (_, (x_j, _))| &acc * &(x_i - x_j))
+        })
+        .collect();
+    batch_invert(&denominators).expect("Error: distinct x values have nonzero differences")
+}
+
+// The coefficients of the unique polynomial of degree < k through the k points. Builds
+// M(x) = prod (x - x_j) once and divides out each (x - x_i) to get the basis numerators, so
+// this is O(k^2) field operations plus a single inversion
+pub fn lagrange_interpolate(
+    points: &[(FieldElement, FieldElement)],
+) -> Result<Polynomial, PolyError> {
+    check_points(points)?;
+    let one = one(&points[0].0);
+    let zero = zero(&one);
+
+    // M(x), lowest degree first
+    let mut full = vec![one.clone()];
+    for (x_j, _) in points {
+        let mut next = vec![zero.clone(); full.len() + 1];
+        for (k, c) in full.iter().enumerate() {
+            next[k + 1] = &next[k + 1] + c;
+            next[k] = &next[k] - &(c * x_j);
+        }
+        full = next;
+    }
+
+    let mut coefficients = vec![zero.clone(); points.len()];
+    for ((x_i, y_i), weight) in points.iter().zip(weights(points)) {
+        // synthetic division of M(x) by (x - x_i), from the top down
+        let scale = y_i * &weight;
+        let mut carry = zero.clone();
+        for k in (0..points.len()).rev() {
+            carry = &full[k + 1] + &(&carry * x_i);
+            coefficients[k] = &coefficients[k] + &(&carry * &scale);
+        }
+    }
+    Ok(Polynomial::new(coefficients))
+}
+
+// The interpolating polynomial at x, without building its coefficients. This is the barycentric
+// form M(x) * sum y_i * w_i / (x - x_i), O(k^2) for the weights plus one batched inversion
+pub fn lagrange_eval_at(
+    points: &[(FieldElement, FieldElement)],
+    x: &FieldElement,
+) -> Result<FieldElement, PolyError> {
+    check_points(points)?;
+    if x.order != points[0].0.order {
+        return Err(PolyError::OrderMismatch);
+    }
+    // the formula divides by x - x_i, so x on a known point just reads it off
+    if let Some((_, y)) = points.iter().find(|(x_i, _)| x_i == x) {
+        return Ok(y.clone());
+    }
+
+    let differences: Vec<FieldElement> = points.iter().map(|(x_i, _)| x - x_i).collect();
+    let inverses = batch_invert(&differences).expect("Error: x is not any of the x_i");
+    let full = differences
+        .iter()
+        .fold(one(x), |acc, difference| &acc * difference);
+    let zero = zero(x);
+    let sum = points
+        .iter()
+        .zip(weights(points))
+        .zip(&inverses)
+        .fold(zero, |acc, (((_, y_i), weight), inverse)| {
+            &acc + &(&(y_i * &weight) * inverse)
+        });
+    Ok(&full * &sum)
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(value: u32, order: u32) -> FieldElement {
+        FieldElement::new(value, order)
+    }
+
+    fn samples(poly: &Polynomial, xs: &[u32], order: u32) -> Vec<(FieldElement, FieldElement)> {
+        xs.iter()
+            .map(|&x| (f(x, order), poly.eval(&f(x, order))))
+            .collect()
+    }
+
+    #[test]
+    fn test_interpolation_recovers_coefficients() {
+        // 3 + 5x + 0x^2 + 200x^3 over F_223
+        let poly = Polynomial::new(vec![f(3, 223), f(5, 223), f(0, 223), f(200, 223)]);
+        assert_eq!(poly.degree(), Some(3));
+        let points = samples(&poly, &[1, 7, 100, 222], 223);
+        assert_eq!(lagrange_interpolate(&points), Ok(poly.clone()));
+
+        // more points than needed still give the same polynomial
+        let points = samples(&poly, &[2, 4, 6, 8, 10, 12], 223);
+        assert_eq!(lagrange_interpolate(&points), Ok(poly));
+
+        // a constant, and the zero polynomial
+        let constant = Polynomial::new(vec![f(9, 223)]);
+        assert_eq!(
+            lagrange_interpolate(&samples(&constant, &[5], 223)),
+            Ok(constant)
+        );
+        let zero = Polynomial::new(vec![f(0, 223), f(0, 223)]);
+        assert_eq!(zero.degree(), None);
+        assert_eq!(zero.coefficients().len(), 1);
+        assert_eq!(
+            lagrange_interpolate(&samples(&zero, &[1, 2], 223)),
+            Ok(zero)
+        );
+    }
+
+    #[test]
+    fn test_eval_at_agrees_with_polynomial() {
+        let points = [
+            (f(1, 19), f(4, 19)),
+            (f(3, 19), f(11, 19)),
+            (f(8, 19), f(0, 19)),
+        ];
+        let poly = lagrange_interpolate(&points).unwrap();
+        for (x, y) in &points {
+            assert_eq!(&poly.eval(x), y);
+        }
+        for x in 0..19 {
+            assert_eq!(
+                lagrange_eval_at(&points, &f(x, 19)),
+                Ok(poly.eval(&f(x, 19)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpolation_errors() {
+        assert_eq!(lagrange_interpolate(&[]), Err(PolyError::NoPoints));
+        assert_eq!(lagrange_eval_at(&[], &f(0, 19)), Err(PolyError::NoPoints));
+        let duplicate = [
+            (f(2, 19), f(1, 19)),
+            (f(5, 19), f(1, 19)),
+            (f(2, 19), f(3, 19)),
+        ];
+        assert_eq!(
+            lagrange_interpolate(&duplicate),
+            Err(PolyError::DuplicateX(Integer::from(2)))
+        );
+        let mixed = [(f(2, 19), f(1, 19)), (f(5, 23), f(1, 23))];
+        assert_eq!(lagrange_interpolate(&mixed), Err(PolyError::OrderMismatch));
+        assert_eq!(
+            lagrange_eval_at(&mixed[..1], &f(0, 23)),
+            Err(PolyError::OrderMismatch)
+        );
+    }
+}
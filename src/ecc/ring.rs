@@ -0,0 +1,443 @@
+use super::field::{FieldElement, Pow, sample_below};
+use rand_core::{CryptoRng, RngCore};
+use rug::integer::IsPrime;
+use rug::ops::RemRoundingAssign;
+use rug::{Complete, Integer};
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// Z/nZ for any modulus n >= 2. Unlike FieldElement nothing here assumes n is prime: only units,
+// the elements coprime to n, can be inverted or divided by
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RingError {
+    ValueOutOfRange,
+    InvalidModulus,
+    ModulusMismatch,
+    NotAUnit,
+    NotPrime,
+    InvalidJacobiModulus,
+}
+
+impl Display for RingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RingError::ValueOutOfRange => write!(f, "value is not in [0, modulus)"),
+            RingError::InvalidModulus => write!(f, "ring modulus must be at least 2"),
+            RingError::ModulusMismatch => write!(f, "RingElements do not share a modulus"),
+            RingError::NotAUnit => write!(f, "element is not invertible modulo its modulus"),
+            RingError::NotPrime => write!(f, "modulus is not prime, so this is not a field"),
+            RingError::InvalidJacobiModulus => {
+                write!(f, "Jacobi symbol needs an odd positive modulus")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RingError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingElement {
+    pub value: Integer,
+    pub modulus: Integer,
+}
+
+// (gcd(a, n), x) with a*x = gcd (mod n), by the extended Euclidean algorithm
+fn ext_gcd(a: &Integer, n: &Integer) -> (Integer, Integer) {
+    let (mut old_r, mut r) = (a.clone(), n.clone());
+    let (mut old_x, mut x) = (Integer::from(1), Integer::new());
+    while !r.is_zero() {
+        let q = (&old_r / &r).complete();
+        let next_r = (&old_r - &(&q * &r).complete()).complete();
+        old_r = std::mem::replace(&mut r, next_r);
+        let next_x = (&old_x - &(&q * &x).complete()).complete();
+        old_x = std::mem::replace(&mut x, next_x);
+    }
+    (old_r, old_x)
+}
+
+impl RingElement {
+    // panicking wrapper around try_new, for values known to be in range
+    pub fn new<T, U>(value: T, modulus: U) -> Self
+    where
+        T: Into<Integer>,
+        U: Into<Integer>,
+    {
+        Self::try_new(value, modulus)
+            .unwrap_or_else(|err| panic!("Error: {err}, cannot create RingElement"))
+    }
+
+    pub fn try_new<T, U>(value: T, modulus: U) -> Result<Self, RingError>
+    where
+        T: Into<Integer>,
+        U: Into<Integer>,
+    {
+        let value: Integer = value.into();
+        let modulus: Integer = modulus.into();
+        if modulus < 2 {
+            return Err(RingError::InvalidModulus);
+        }
+        if value.is_negative() || value >= modulus {
+            return Err(RingError::ValueOutOfRange);
+        }
+        Ok(Self { value, modulus })
+    }
+
+    fn check_modulus(&self, rhs: &RingElement) -> Result<(), RingError> {
+        if self.modulus != rhs.modulus {
+            return Err(RingError::ModulusMismatch);
+        }
+        Ok(())
+    }
+
+    fn with_value(&self, value: Integer) -> RingElement {
+        let mut value = value;
+        value.rem_euc_assign(&self.modulus);
+        RingElement {
+            value,
+            modulus: self.modulus.clone(),
+        }
+    }
+
+    pub fn try_add(&self, rhs: &RingElement) -> Result<RingElement, RingError> {
+        self.check_modulus(rhs)?;
+        Ok(self.with_value((&self.value + &rhs.value).complete()))
+    }
+
+    pub fn try_sub(&self, rhs: &RingElement) -> Result<RingElement, RingError> {
+        self.check_modulus(rhs)?;
+        Ok(self.with_value((&self.value - &rhs.value).complete()))
+    }
+
+    pub fn try_mul(&self, rhs: &RingElement) -> Result<RingElement, RingError> {
+        self.check_modulus(rhs)?;
+        Ok(self.with_value((&self.value * &rhs.value).complete()))
+    }
+
+    // a / b = a * b^-1, defined only when b is a unit
+    pub fn try_div(&self, rhs: &RingElement) -> Result<RingElement, RingError> {
+        self.check_modulus(rhs)?;
+        let inverse = rhs.invert().ok_or(RingError::NotAUnit)?;
+        self.try_mul(&inverse)
+    }
+
+    // gcd(value, modulus) = 1
+    pub fn is_unit(&self) -> bool {
+        self.invert().is_some()
+    }
+
+    pub fn invert(&self) -> Option<RingElement> {
+        let (gcd, x) = ext_gcd(&self.value, &self.modulus);
+        (gcd == 1).then(|| self.with_value(x))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    // the same element as a FieldElement, once the modulus passes a Miller-Rabin test
+    pub fn to_field(&self) -> Result<FieldElement, RingError> {
+        if self.modulus.is_probably_prime(30) == IsPrime::No {
+            return Err(RingError::NotPrime);
+        }
+        Ok(FieldElement::new(self.value.clone(), self.modulus.clone()))
+    }
+}
+
+impl From<FieldElement> for RingElement {
+    fn from(element: FieldElement) -> Self {
+        RingElement {
+            value: element.value,
+            modulus: element.order,
+        }
+    }
+}
+
+impl Display for RingElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RingElement_{}({})", self.modulus, self.value)
+    }
+}
+
+impl Neg for &RingElement {
+    type Output = RingElement;
+
+    fn neg(self) -> Self::Output {
+        self.with_value((-&self.value).complete())
+    }
+}
+
+// panicking wrappers over the try_ methods, as for FieldElement
+impl Add<&RingElement> for &RingElement {
+    type Output = RingElement;
+
+    fn add(self, rhs: &RingElement) -> Self::Output {
+        self.try_add(rhs)
+            .unwrap_or_else(|err| panic!("Error: add operation failed because {err}"))
+    }
+}
+
+impl Sub<&RingElement> for &RingElement {
+    type Output = RingElement;
+
+    fn sub(self, rhs: &RingElement) -> Self::Output {
+        self.try_sub(rhs)
+            .unwrap_or_else(|err| panic!("Error: sub operation failed because {err}"))
+    }
+}
+
+impl Mul<&RingElement> for &RingElement {
+    type Output = RingElement;
+
+    fn mul(self, rhs: &RingElement) -> Self::Output {
+        self.try_mul(rhs)
+            .unwrap_or_else(|err| panic!("Error: mul operation failed because {err}"))
+    }
+}
+
+impl Div<&RingElement> for &RingElement {
+    type Output = RingElement;
+
+    fn div(self, rhs: &RingElement) -> Self::Output {
+        self.try_div(rhs)
+            .unwrap_or_else(|err| panic!("Error: div operation failed because {err}"))
+    }
+}
+
+// The exponent is used as given. There is no reduction mod n - 1 as in FieldElement, since
+// Fermat's little theorem only holds for a prime modulus. A negative exponent needs a unit
+impl Pow for RingElement {
+    fn pow<T: Into<Integer>>(&self, exp: T) -> Self {
+        let exp: Integer = exp.into();
+        let result = self
+            .value
+            .pow_mod_ref(&exp, &self.modulus)
+            .unwrap_or_else(|| panic!("Error: {}, cannot raise to {exp}", RingError::NotAUnit))
+            .complete();
+        self.with_value(result)
+    }
+}
+
+// The Jacobi symbol (a/n) for odd n > 0, the product of the Legendre symbols of a over the
+// prime factors of n. Found by quadratic reciprocity, so n is never factored. It is 0 when
+// gcd(a, n) > 1. For composite n a -1 proves a is not a square mod n, but a 1 proves nothing
+pub fn jacobi(a: &Integer, n: &Integer) -> Result<i32, RingError> {
+    if *n <= 0 || n.is_even() {
+        return Err(RingError::InvalidJacobiModulus);
+    }
+    let mut a = a.clone();
+    a.rem_euc_assign(n);
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        // (2/n) = -1 exactly when n = 3 or 5 mod 8
+        let twos = a.find_one(0).expect("a is nonzero");
+        a >>= twos;
+        if twos % 2 == 1 && matches!(n.mod_u(8), 3 | 5) {
+            result = -result;
+        }
+        // (a/n) = (n/a) for odd a and n, unless both are 3 mod 4
+        std::mem::swap(&mut a, &mut n);
+        if a.mod_u(4) == 3 && n.mod_u(4) == 3 {
+            result = -result;
+        }
+        a.rem_euc_assign(&n);
+    }
+    // n is now gcd(a, n)
+    Ok(if n == 1 { result } else { 0 })
+}
+
+// The smallest prime above n, so next_prime(2) is 3 and anything below 2 gives 2. GMP's
+// nextprime, which uses the same Miller-Rabin test as to_field
+pub fn next_prime(n: &Integer) -> Integer {
+    n.next_prime_ref().complete()
+}
+
+// A prime with exactly `bits` bits, uniform over all such primes: uniform draws from
+// [2^(bits - 1), 2^bits) until one passes Miller-Rabin. Panics below 2 bits
+pub fn random_prime<R: CryptoRng + RngCore>(bits: u32, rng: &mut R) -> Integer {
+    assert!(bits >= 2, "Error: no prime has fewer than 2 bits");
+    let low = Integer::from(1) << (bits - 1);
+    loop {
+        let (offset, _) = sample_below(rng, &low);
+        let candidate = offset + &low;
+        if candidate.is_probably_prime(30) != IsPrime::No {
+            return candidate;
+        }
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn z15(value: u32) -> RingElement {
+        RingElement::new(value, 15)
+    }
+
+    #[test]
+    fn test_units_mod_15() {
+        assert!(!z15(3).is_unit());
+        assert_eq!(z15(3).invert(), None);
+        assert_eq!(z15(0).invert(), None);
+        assert_eq!(z15(7).invert(), Some(z15(13)));
+        assert_eq!(&z15(7) * &z15(13), z15(1));
+        assert_eq!(&z15(1) / &z15(7), z15(13));
+        assert_eq!(z15(9).try_div(&z15(3)), Err(RingError::NotAUnit));
+        // 3 * 5 = 0 with neither factor zero, which can't happen in a field
+        assert!((&z15(3) * &z15(5)).is_zero());
+
+        let units = (0..15).filter(|&v| z15(v).is_unit()).count();
+        assert_eq!(units, 8);
+    }
+
+    #[test]
+    fn test_pow_is_not_fermat() {
+        // a^(n-1) = 1 only holds for prime n
+        assert_eq!(z15(2).pow(14), z15(4));
+        // Euler: a^phi(15) = 1 for every unit
+        for v in (1..15).filter(|&v| z15(v).is_unit()) {
+            assert_eq!(z15(v).pow(8), z15(1));
+        }
+        assert_eq!(z15(7).pow(-1), z15(13));
+        assert_eq!(z15(3).pow(0), z15(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "not invertible")]
+    fn test_negative_pow_of_non_unit() {
+        z15(3).pow(-1);
+    }
+
+    #[test]
+    fn test_ring_ops_and_errors() {
+        assert_eq!(&z15(9) + &z15(8), z15(2));
+        assert_eq!(&z15(2) - &z15(9), z15(8));
+        assert_eq!(-&z15(4), z15(11));
+        assert_eq!(-&z15(0), z15(0));
+        assert_eq!(
+            z15(1).try_add(&RingElement::new(1, 16)),
+            Err(RingError::ModulusMismatch)
+        );
+        assert_eq!(
+            RingElement::try_new(15, 15),
+            Err(RingError::ValueOutOfRange)
+        );
+        assert_eq!(RingElement::try_new(0, 1), Err(RingError::InvalidModulus));
+    }
+
+    #[test]
+    fn test_jacobi_table() {
+        // rows are n = 1, 3, ..., 15 and columns a = 0..8, from the standard table
+        let table: [(u32, [i32; 9]); 8] = [
+            (1, [1, 1, 1, 1, 1, 1, 1, 1, 1]),
+            (3, [0, 1, -1, 0, 1, -1, 0, 1, -1]),
+            (5, [0, 1, -1, -1, 1, 0, 1, -1, -1]),
+            (7, [0, 1, 1, -1, 1, -1, -1, 0, 1]),
+            (9, [0, 1, 1, 0, 1, 1, 0, 1, 1]),
+            (11, [0, 1, -1, 1, 1, 1, -1, -1, -1]),
+            (13, [0, 1, -1, 1, 1, -1, -1, -1, -1]),
+            (15, [0, 1, 1, 0, 1, 0, 0, -1, 1]),
+        ];
+        for (n, row) in table {
+            for (a, expected) in row.into_iter().enumerate() {
+                let symbol = jacobi(&Integer::from(a), &Integer::from(n)).unwrap();
+                assert_eq!(symbol, expected, "({a}/{n})");
+            }
+        }
+        // (2/15) = 1 though 2 is not a square mod 15
+        assert!((0..15).all(|v| z15(v).pow(2) != z15(2)));
+        // a is reduced mod n first, negatives included
+        assert_eq!(jacobi(&Integer::from(-1), &Integer::from(7)), Ok(-1));
+        assert_eq!(jacobi(&Integer::from(1001), &Integer::from(9907)), Ok(-1));
+        let big = (Integer::from(15) << 200u32) + 7u8;
+        assert_eq!(jacobi(&big, &Integer::from(15)), Ok(-1));
+    }
+
+    #[test]
+    fn test_jacobi_matches_legendre_for_primes() {
+        for p in [3u32, 5, 7, 11, 13, 223, 7919] {
+            let n = Integer::from(p);
+            for a in 0..p.min(300) {
+                // Euler's criterion: a^((p - 1) / 2) is 0, 1 or p - 1
+                let euler = FieldElement::new(a, p).pow((p - 1) / 2).value;
+                let legendre = if euler == p - 1 {
+                    -1
+                } else {
+                    euler.to_i32().unwrap()
+                };
+                assert_eq!(jacobi(&Integer::from(a), &n), Ok(legendre), "({a}/{p})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_jacobi_rejects_bad_moduli() {
+        for n in [0, -3, 2, 16] {
+            assert_eq!(
+                jacobi(&Integer::from(5), &Integer::from(n)),
+                Err(RingError::InvalidJacobiModulus)
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_prime() {
+        let next = |n: i32| next_prime(&Integer::from(n));
+        assert_eq!(next(220), 223);
+        assert_eq!(next(223), 227);
+        assert_eq!(next(-5), 2);
+        assert_eq!(next(0), 2);
+        assert_eq!(next(1), 2);
+        assert_eq!(next(2), 3);
+        assert_eq!(next(3), 5);
+        assert_eq!(next(24), 29);
+        // 2^64 - 59 is the largest prime that fits a u64, the next one is 2^64 + 13
+        let largest_u64 = u64::MAX - 58;
+        assert_eq!(next_prime(&Integer::from(largest_u64 - 1)), largest_u64);
+        let two_64 = Integer::from(1) << 64u32;
+        assert_eq!(next_prime(&Integer::from(largest_u64)), two_64 + 13u8);
+    }
+
+    #[test]
+    fn test_random_prime() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(184);
+        for bits in [2, 3, 8, 31, 32, 63, 64, 65, 128] {
+            for _ in 0..8 {
+                let prime = random_prime(bits, &mut rng);
+                assert_eq!(prime.significant_bits(), bits);
+                assert_ne!(prime.is_probably_prime(30), IsPrime::No);
+            }
+        }
+        // both 2-bit primes come up
+        let small: Vec<Integer> = (0..32).map(|_| random_prime(2, &mut rng)).collect();
+        assert!(small.contains(&Integer::from(2)) && small.contains(&Integer::from(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "fewer than 2 bits")]
+    fn test_random_prime_one_bit() {
+        use rand_core::SeedableRng;
+        random_prime(1, &mut rand_chacha::ChaCha20Rng::seed_from_u64(184));
+    }
+
+    #[test]
+    fn test_field_conversion() {
+        assert_eq!(z15(7).to_field(), Err(RingError::NotPrime));
+        let element = RingElement::new(7, 13).to_field().unwrap();
+        assert_eq!(element, FieldElement::new(7, 13));
+        assert_eq!(RingElement::from(element), RingElement::new(7, 13));
+        // same inverse either way once the modulus is prime
+        let inverse = RingElement::new(7, 13).invert().unwrap();
+        assert_eq!(
+            inverse.to_field().unwrap(),
+            &FieldElement::new(1, 13) / &FieldElement::new(7, 13)
+        );
+    }
+}
@@ -0,0 +1,494 @@
+use super::{Infinity, PrivateKey, S256Point, g_mul, order};
+use crate::ecc::hash::hash160;
+use crate::encoding::base58::{self, Base58Error};
+use hmac::{Hmac, Mac};
+use rug::Integer;
+use rug::integer::Order;
+use sha2::Sha512;
+use std::fmt::Display;
+use std::str::FromStr;
+
+const HARDENED: u32 = 1 << 31;
+
+const XPRV: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+const XPUB: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const TPRV: [u8; 4] = [0x04, 0x35, 0x83, 0x94];
+const TPUB: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bip32Error {
+    InvalidSeedLength,
+    InvalidChild,
+    HardenedFromPublic,
+    MaxDepth,
+    InvalidPath,
+    InvalidVersion,
+    InvalidLength,
+    InvalidKey,
+    Base58(Base58Error),
+}
+
+impl Display for Bip32Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bip32Error::InvalidSeedLength => write!(f, "seed must be between 16 and 64 bytes"),
+            Bip32Error::InvalidChild => write!(f, "derived key is invalid, try the next index"),
+            Bip32Error::HardenedFromPublic => {
+                write!(f, "hardened children cannot be derived from a public key")
+            }
+            Bip32Error::MaxDepth => write!(f, "extended keys cannot be deeper than 255"),
+            Bip32Error::InvalidPath => write!(f, "invalid derivation path"),
+            Bip32Error::InvalidVersion => write!(f, "unknown extended key version bytes"),
+            Bip32Error::InvalidLength => write!(f, "extended key must be 78 bytes"),
+            Bip32Error::InvalidKey => write!(f, "extended key contains an invalid key"),
+            Bip32Error::Base58(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Bip32Error {}
+
+impl From<Base58Error> for Bip32Error {
+    fn from(err: Base58Error) -> Self {
+        Bip32Error::Base58(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPrivKey {
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    pub key: PrivateKey,
+    pub testnet: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPubKey {
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    pub key: S256Point,
+    pub testnet: bool,
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    let result = mac.finalize().into_bytes();
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&result[..32]);
+    right.copy_from_slice(&result[32..]);
+    (left, right)
+}
+
+fn fingerprint(key: &S256Point) -> [u8; 4] {
+    let mut result = [0u8; 4];
+    result.copy_from_slice(&hash160(&key.sec(true))[..4]);
+    result
+}
+
+// "m/44'/0'/0'/0/0" -> [44 | H, 0 | H, 0 | H, 0, 0], accepting ', h or H for hardened
+fn parse_path(path: &str, allow_public: bool) -> Result<Vec<u32>, Bip32Error> {
+    let mut parts = path.split('/');
+    match parts.next() {
+        Some("m") => {}
+        Some("M") if allow_public => {}
+        _ => return Err(Bip32Error::InvalidPath),
+    }
+    parts
+        .map(|part| {
+            let (digits, hardened) = match part.strip_suffix(['\'', 'h', 'H']) {
+                Some(digits) => (digits, true),
+                None => (part, false),
+            };
+            if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(Bip32Error::InvalidPath);
+            }
+            let index: u32 = digits.parse().map_err(|_| Bip32Error::InvalidPath)?;
+            if index >= HARDENED {
+                return Err(Bip32Error::InvalidPath);
+            }
+            Ok(if hardened { index | HARDENED } else { index })
+        })
+        .collect()
+}
+
+fn serialize(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: &[u8; 4],
+    child_number: u32,
+    chain_code: &[u8; 32],
+    key_data: &[u8],
+) -> String {
+    let mut bytes = Vec::with_capacity(78);
+    bytes.extend_from_slice(&version);
+    bytes.push(depth);
+    bytes.extend_from_slice(parent_fingerprint);
+    bytes.extend_from_slice(&child_number.to_be_bytes());
+    bytes.extend_from_slice(chain_code);
+    bytes.extend_from_slice(key_data);
+    base58::encode_check(&bytes)
+}
+
+// the 78 serialized bytes split into fields, before the version and key are interpreted
+struct RawExtendedKey {
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    key_data: Vec<u8>,
+}
+
+fn deserialize(s: &str) -> Result<RawExtendedKey, Bip32Error> {
+    let bytes = base58::decode_check(s)?;
+    if bytes.len() != 78 {
+        return Err(Bip32Error::InvalidLength);
+    }
+    let mut version = [0u8; 4];
+    version.copy_from_slice(&bytes[..4]);
+    let depth = bytes[4];
+    let mut parent_fingerprint = [0u8; 4];
+    parent_fingerprint.copy_from_slice(&bytes[5..9]);
+    let mut child = [0u8; 4];
+    child.copy_from_slice(&bytes[9..13]);
+    let mut chain_code = [0u8; 32];
+    chain_code.copy_from_slice(&bytes[13..45]);
+    let child_number = u32::from_be_bytes(child);
+
+    // a master key has no parent, so its fingerprint and index must be zero
+    if depth == 0 && (parent_fingerprint != [0; 4] || child_number != 0) {
+        return Err(Bip32Error::InvalidKey);
+    }
+    Ok(RawExtendedKey {
+        version,
+        depth,
+        parent_fingerprint,
+        child_number,
+        chain_code,
+        key_data: bytes[45..].to_vec(),
+    })
+}
+
+impl ExtendedPrivKey {
+    // master key from HMAC-SHA512("Bitcoin seed", seed)
+    pub fn new_master(seed: &[u8], testnet: bool) -> Result<Self, Bip32Error> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(Bip32Error::InvalidSeedLength);
+        }
+        let (il, chain_code) = hmac_sha512(b"Bitcoin seed", &[seed]);
+        let secret = Integer::from_digits(&il, Order::Msf);
+        if secret == 0 || secret >= order() {
+            return Err(Bip32Error::InvalidKey);
+        }
+        Ok(Self {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code,
+            key: PrivateKey::new(secret),
+            testnet,
+        })
+    }
+
+    // indices >= 2^31 are hardened and commit to the private key instead of the public key
+    pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
+        let depth = self.depth.checked_add(1).ok_or(Bip32Error::MaxDepth)?;
+        let (il, chain_code) = if index >= HARDENED {
+            let secret = self.key.secret.to_bytes32();
+            hmac_sha512(&self.chain_code, &[&[0x00], &secret, &index.to_be_bytes()])
+        } else {
+            let sec = self.key.point.sec(true);
+            hmac_sha512(&self.chain_code, &[&sec, &index.to_be_bytes()])
+        };
+
+        let n = order();
+        let tweak = Integer::from_digits(&il, Order::Msf);
+        if tweak >= n {
+            return Err(Bip32Error::InvalidChild);
+        }
+        let secret = (tweak + self.key.secret.as_integer()) % &n;
+        if secret == 0 {
+            return Err(Bip32Error::InvalidChild);
+        }
+        Ok(Self {
+            depth,
+            parent_fingerprint: fingerprint(&self.key.point),
+            child_number: index,
+            chain_code,
+            key: PrivateKey::new(secret),
+            testnet: self.testnet,
+        })
+    }
+
+    pub fn derive_path(&self, path: &str) -> Result<Self, Bip32Error> {
+        parse_path(path, false)?
+            .into_iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(index))
+    }
+
+    pub fn fingerprint(&self) -> [u8; 4] {
+        self.key.public_key().fingerprint()
+    }
+
+    // the matching xpub, which can derive the same non-hardened children
+    pub fn to_extended_pub(&self) -> ExtendedPubKey {
+        ExtendedPubKey {
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            chain_code: self.chain_code,
+            key: self.key.public_key().into(),
+            testnet: self.testnet,
+        }
+    }
+}
+
+impl ExtendedPubKey {
+    // child point is parent + IL*G, only possible for non-hardened indices
+    pub fn derive_child(&self, index: u32) -> Result<Self, Bip32Error> {
+        if index >= HARDENED {
+            return Err(Bip32Error::HardenedFromPublic);
+        }
+        let depth = self.depth.checked_add(1).ok_or(Bip32Error::MaxDepth)?;
+        let sec = self.key.sec(true);
+        let (il, chain_code) = hmac_sha512(&self.chain_code, &[&sec, &index.to_be_bytes()]);
+
+        let tweak = Integer::from_digits(&il, Order::Msf);
+        if tweak >= order() {
+            return Err(Bip32Error::InvalidChild);
+        }
+        let key = &g_mul(&tweak) + &self.key;
+        if key.point.x == Infinity {
+            return Err(Bip32Error::InvalidChild);
+        }
+        Ok(Self {
+            depth,
+            parent_fingerprint: fingerprint(&self.key),
+            child_number: index,
+            chain_code,
+            key,
+            testnet: self.testnet,
+        })
+    }
+
+    pub fn derive_path(&self, path: &str) -> Result<Self, Bip32Error> {
+        parse_path(path, true)?
+            .into_iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(index))
+    }
+
+    pub fn fingerprint(&self) -> [u8; 4] {
+        fingerprint(&self.key)
+    }
+}
+
+impl Display for ExtendedPrivKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = if self.testnet { TPRV } else { XPRV };
+        let mut key_data = vec![0x00];
+        key_data.extend_from_slice(&self.key.secret.to_bytes32());
+        let s = serialize(
+            version,
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &key_data,
+        );
+        write!(f, "{s}")
+    }
+}
+
+impl Display for ExtendedPubKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = if self.testnet { TPUB } else { XPUB };
+        let s = serialize(
+            version,
+            self.depth,
+            &self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &self.key.sec(true),
+        );
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for ExtendedPrivKey {
+    type Err = Bip32Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = deserialize(s)?;
+        let testnet = match raw.version {
+            XPRV => false,
+            TPRV => true,
+            _ => return Err(Bip32Error::InvalidVersion),
+        };
+        if raw.key_data[0] != 0x00 {
+            return Err(Bip32Error::InvalidKey);
+        }
+        let secret = Integer::from_digits(&raw.key_data[1..], Order::Msf);
+        if secret == 0 || secret >= order() {
+            return Err(Bip32Error::InvalidKey);
+        }
+        Ok(Self {
+            depth: raw.depth,
+            parent_fingerprint: raw.parent_fingerprint,
+            child_number: raw.child_number,
+            chain_code: raw.chain_code,
+            key: PrivateKey::new(secret),
+            testnet,
+        })
+    }
+}
+
+impl FromStr for ExtendedPubKey {
+    type Err = Bip32Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = deserialize(s)?;
+        let testnet = match raw.version {
+            XPUB => false,
+            TPUB => true,
+            _ => return Err(Bip32Error::InvalidVersion),
+        };
+        if raw.key_data[0] != 0x02 && raw.key_data[0] != 0x03 {
+            return Err(Bip32Error::InvalidKey);
+        }
+        let key = S256Point::from_sec(&raw.key_data).map_err(|_| Bip32Error::InvalidKey)?;
+        Ok(Self {
+            depth: raw.depth,
+            parent_fingerprint: raw.parent_fingerprint,
+            child_number: raw.child_number,
+            chain_code: raw.chain_code,
+            key,
+            testnet,
+        })
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/vectors/bip32_vectors.csv"
+    ));
+
+    fn decode_hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_bip32_vectors() {
+        for line in VECTORS.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            let (seed, path, xpub, xprv) = (fields[0], fields[1], fields[2], fields[3]);
+
+            let master = ExtendedPrivKey::new_master(&decode_hex(seed), false).unwrap();
+            let key = master.derive_path(path).unwrap();
+            assert_eq!(key.to_string(), xprv, "xprv for {path}");
+            assert_eq!(key.to_extended_pub().to_string(), xpub, "xpub for {path}");
+
+            assert_eq!(xprv.parse::<ExtendedPrivKey>().unwrap(), key);
+            assert_eq!(
+                xpub.parse::<ExtendedPubKey>().unwrap(),
+                key.to_extended_pub()
+            );
+        }
+    }
+
+    #[test]
+    fn test_public_derivation_matches_private() {
+        let seed = decode_hex("000102030405060708090a0b0c0d0e0f");
+        let master = ExtendedPrivKey::new_master(&seed, false).unwrap();
+        let account = master.derive_path("m/0H").unwrap();
+        let xpub = account.to_extended_pub();
+
+        for path in ["m/1", "m/1/2", "m/0/5/1000000000"] {
+            let from_private = account.derive_path(path).unwrap().to_extended_pub();
+            let from_public = xpub.derive_path(&path.replacen('m', "M", 1)).unwrap();
+            assert_eq!(from_public, from_private);
+        }
+        assert_eq!(
+            xpub.derive_child(HARDENED),
+            Err(Bip32Error::HardenedFromPublic)
+        );
+    }
+
+    #[test]
+    fn test_testnet_versions() {
+        let seed = decode_hex("000102030405060708090a0b0c0d0e0f");
+        let master = ExtendedPrivKey::new_master(&seed, true).unwrap();
+        let key = master.derive_path("m/44'/1'/0'/0/0").unwrap();
+        assert!(key.to_string().starts_with("tprv"));
+        assert!(key.to_extended_pub().to_string().starts_with("tpub"));
+        assert_eq!(key.to_string().parse::<ExtendedPrivKey>().unwrap(), key);
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("m/44'/0h/0H/0/7", false).unwrap(),
+            vec![44 | HARDENED, HARDENED, HARDENED, 0, 7]
+        );
+        assert!(parse_path("m", false).unwrap().is_empty());
+        for bad in [
+            "",
+            "M/0",
+            "m/",
+            "m/x",
+            "m/-1",
+            "m/2147483648",
+            "m//1",
+            "0/1",
+        ] {
+            assert_eq!(
+                parse_path(bad, false),
+                Err(Bip32Error::InvalidPath),
+                "{bad}"
+            );
+        }
+        assert!(parse_path("M/0", true).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(
+            ExtendedPrivKey::new_master(&[0u8; 15], false),
+            Err(Bip32Error::InvalidSeedLength)
+        );
+        // an xpub is not an xprv
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        assert_eq!(
+            xpub.parse::<ExtendedPrivKey>(),
+            Err(Bip32Error::InvalidVersion)
+        );
+        assert_eq!(
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet9"
+                .parse::<ExtendedPubKey>(),
+            Err(Bip32Error::Base58(Base58Error::BadChecksum))
+        );
+        assert_eq!(
+            base58::encode_check(&[0u8; 77]).parse::<ExtendedPubKey>(),
+            Err(Bip32Error::InvalidLength)
+        );
+    }
+}
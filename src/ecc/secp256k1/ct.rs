@@ -0,0 +1,378 @@
+// Hardened scalar operations for the signing path. What is claimed, relative to the secret:
+//  - g_mul_ct does the same 64 table scans and 65 point additions for every k, and every table
+//    entry is read on each scan, so neither the control flow nor the memory access pattern of
+//    this module depends on k
+//  - mul_ct, the variable-base k*P behind ECDH, does the same with a 16 entry table of P's
+//    multiples built per call: 256 doublings, 64 scans and 65 additions for every k
+//  - inv_mod_n is Fermat inversion with the fixed public exponent n - 2 through GMP's
+//    mpz_powm_sec, not the extended Euclid behind invert, whose iteration count depends on k
+//  - low_s picks between s and n - s, and the BIP340 signer between k and -k, with a subtle
+//    conditional_assign over the fixed 32-byte encodings instead of a branch
+//  - select_scalar / select_field / conditional_assign_bytes are branch-free selects on those
+//    encodings, and Scalar::ct_equals (plus ConstantTimeEq for Scalar and FieldElement under the ct
+//    feature) compares them without stopping at the first difference
+// Not claimed: the limb level arithmetic inside rug/GMP (allocation, normalization of leading
+// zero limbs), the special cases in affine point addition, which only run when two partial sums
+// collide (negligible for a non-adversarial k; mul_ct blinds its table with a secret offset so
+// a chosen P can't force one either), RFC 6979's retry loop and DER encoding.
+// Verification only handles public data and keeps using the vartime g_mul and mul_vartime
+//
+// CT-REVIEW checklist for any change that touches a secret (grep CT-REVIEW for the call sites):
+//  1. no if / match / early return on a secret value, select with conditional_assign instead
+//  2. no comparison of secrets with == on Integer, use ct_eq on the fixed-width encodings
+//  3. no table index derived from a secret, scan every entry as select does
+//  4. temporaries holding secret bytes or integers are zeroized / wiped before they drop
+//  5. anything still variable time on the signing path is listed under "Not claimed" above
+use super::{
+    EcPoint, Finite, Infinity, S256Point, Scalar, g_mul, order, prime, sha256, to_bytes32, wipe,
+};
+use crate::ecc::field::FieldElement;
+use rug::integer::Order;
+use rug::ops::RemRoundingAssign;
+use rug::{Complete, Integer};
+use std::sync::OnceLock;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+const CT_WINDOWS: usize = 64;
+
+// affine x || y, big-endian
+type Entry = [u8; 64];
+
+struct CtTable {
+    // row i holds j * 16^i * G + C for j in 0..16, so no entry is ever infinity
+    rows: Vec<[Entry; 16]>,
+    // -(64 * C), cancels the offset added by every row
+    correction: EcPoint,
+}
+
+fn to_entry(point: &S256Point) -> Entry {
+    let mut entry = [0u8; 64];
+    match (&point.point.x, &point.point.y) {
+        (Finite(x), Finite(y)) => {
+            entry[..32].copy_from_slice(&to_bytes32(&x.value));
+            entry[32..].copy_from_slice(&to_bytes32(&y.value));
+        }
+        _ => unreachable!("offset table entries are never infinity"),
+    }
+    entry
+}
+
+fn ct_table() -> &'static CtTable {
+    static TABLE: OnceLock<CtTable> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let n = order();
+        // nothing up my sleeve offset, C = h*G
+        let mut h = Integer::from_digits(&sha256(b"galactic_credit/ct-offset"), Order::Msf);
+        h.rem_euc_assign(&n);
+
+        let mut rows = Vec::with_capacity(CT_WINDOWS);
+        let mut base = Integer::from(1);
+        for _ in 0..CT_WINDOWS {
+            let mut row = [[0u8; 64]; 16];
+            for (j, entry) in row.iter_mut().enumerate() {
+                let scalar = Integer::from(&base * j as u32) + &h;
+                *entry = to_entry(&g_mul(&scalar));
+            }
+            rows.push(row);
+            base <<= 4;
+        }
+
+        let mut offset = h * CT_WINDOWS as u32;
+        offset.rem_euc_assign(&n);
+        let correction = g_mul(&(n - offset)).point;
+        CtTable { rows, correction }
+    })
+}
+
+// dst = src when choice is set, byte by byte with no branch. Extra bytes of the longer side
+// are left alone
+pub(super) fn conditional_assign_bytes(dst: &mut [u8], src: &[u8], choice: Choice) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        d.conditional_assign(s, choice);
+    }
+}
+
+// b when choice is set, a otherwise
+pub(super) fn select_scalar(a: &Scalar, b: &Scalar, choice: Choice) -> Scalar {
+    let mut out = a.to_bytes32();
+    let mut other = b.to_bytes32();
+    conditional_assign_bytes(&mut out, &other, choice);
+    let selected = Scalar::new(Integer::from_digits(&out, Order::Msf));
+    out.zeroize();
+    other.zeroize();
+    selected
+}
+
+// b when choice is set, a otherwise, for elements of F_p
+pub(super) fn select_field(a: &FieldElement, b: &FieldElement, choice: Choice) -> FieldElement {
+    let mut out = to_bytes32(&a.value);
+    conditional_assign_bytes(&mut out, &to_bytes32(&b.value), choice);
+    FieldElement::new(Integer::from_digits(&out, Order::Msf), a.order.clone())
+}
+
+// CT-REVIEW: reads every entry of the row and keeps the one at index
+fn select(row: &[Entry; 16], index: u8) -> Entry {
+    let mut out = [0u8; 64];
+    for (j, entry) in row.iter().enumerate() {
+        conditional_assign_bytes(&mut out, entry, (j as u8).ct_eq(&index));
+    }
+    out
+}
+
+fn from_entry(entry: &Entry) -> EcPoint {
+    let p = prime();
+    let g = S256Point::get_generator().point;
+    EcPoint {
+        x: Finite(FieldElement::new(
+            Integer::from_digits(&entry[..32], Order::Msf),
+            p.clone(),
+        )),
+        y: Finite(FieldElement::new(
+            Integer::from_digits(&entry[32..], Order::Msf),
+            p,
+        )),
+        a: g.a,
+        b: g.b,
+    }
+}
+
+// k*G with a k independent sequence of table scans and additions, k is reduced mod n first
+pub fn g_mul_ct(k: &Integer) -> S256Point {
+    let table = ct_table();
+    let mut k = k.clone();
+    k.rem_euc_assign(&order());
+    let mut bytes = to_bytes32(&k);
+
+    let mut result = EcPoint::new(
+        Infinity,
+        Infinity,
+        table.correction.a.clone(),
+        table.correction.b.clone(),
+    );
+    for (i, row) in table.rows.iter().enumerate() {
+        let byte = bytes[31 - i / 2];
+        let nibble = (byte >> (4 * (i % 2))) & 0x0f;
+        let mut entry = select(row, nibble);
+        result = &result + &from_entry(&entry);
+        entry.zeroize();
+    }
+    result = &result + &table.correction;
+
+    wipe(&mut k);
+    bytes.zeroize();
+    S256Point { point: result }
+}
+
+// CT-REVIEW: k*P for a secret k and any public point P, k is reduced mod n first. A fixed 4-bit
+// window over a table of j*P + Q, read with select like g_mul_ct's rows. The offset Q = b*G
+// keeps every entry finite, and b is derived from k and P so a chosen P can't make one
+// infinity. Horner's rule adds (16^63 + ... + 16 + 1)*Q in total, taken off at the end
+pub fn mul_ct(point: &S256Point, k: &Integer) -> S256Point {
+    assert!(
+        point.point.x != Infinity,
+        "Error: cannot multiply the point at infinity in constant time"
+    );
+    let n = order();
+    let mut k = k.clone();
+    k.rem_euc_assign(&n);
+    let mut bytes = to_bytes32(&k);
+
+    let mut seed = [&b"galactic_credit/ct-blind"[..], &bytes, &point.sec(true)].concat();
+    let mut blind = Integer::from_digits(&sha256(&seed), Order::Msf);
+    blind.rem_euc_assign(&(&n - 1u8).complete());
+    blind += 1;
+    let offset = g_mul_ct(&blind);
+    // (16^64 - 1) / 15 copies of Q
+    let copies = (Integer::from(1) << (4 * CT_WINDOWS as u32)) / 15u8;
+    let mut total = copies * &blind;
+    total.rem_euc_assign(&n);
+    let correction = g_mul_ct(&(&n - &total).into()).point;
+
+    let mut table = [[0u8; 64]; 16];
+    let mut multiple = offset;
+    for entry in table.iter_mut() {
+        *entry = to_entry(&multiple);
+        multiple = &multiple + point;
+    }
+
+    let mut result = EcPoint::new(
+        Infinity,
+        Infinity,
+        correction.a.clone(),
+        correction.b.clone(),
+    );
+    for i in (0..CT_WINDOWS).rev() {
+        for _ in 0..4 {
+            result = &result + &result;
+        }
+        let byte = bytes[31 - i / 2];
+        let nibble = (byte >> (4 * (i % 2))) & 0x0f;
+        let mut entry = select(&table, nibble);
+        result = &result + &from_entry(&entry);
+        entry.zeroize();
+    }
+    result = &result + &correction;
+
+    wipe(&mut k);
+    wipe(&mut blind);
+    wipe(&mut total);
+    bytes.zeroize();
+    seed.zeroize();
+    table.zeroize();
+    S256Point { point: result }
+}
+
+// k^(n-2) mod n, for k in [1, n)
+pub(super) fn inv_mod_n(k: &Integer) -> Integer {
+    let n = order();
+    let exp = (&n - 2u8).complete();
+    k.secure_pow_mod_ref(&exp, &n).complete()
+}
+
+// set when a > b, from the borrow of b - a
+fn ct_gt(a: &[u8; 32], b: &[u8; 32]) -> Choice {
+    let mut borrow = 0u16;
+    for (x, y) in a.iter().zip(b).rev() {
+        let diff = (*y as u16).wrapping_sub(*x as u16).wrapping_sub(borrow);
+        borrow = (diff >> 15) & 1;
+    }
+    Choice::from(borrow as u8)
+}
+
+// CT-REVIEW: (s or n - s, whichever is <= n/2, and whether s was negated), s in [1, n)
+pub(super) fn low_s(s: &Integer) -> (Integer, bool) {
+    let n = order();
+    let half = to_bytes32(&(&n >> 1u32).complete());
+    let mut high = to_bytes32(s);
+    let mut negated = to_bytes32(&(&n - s).complete());
+
+    let flip = ct_gt(&high, &half);
+    conditional_assign_bytes(&mut high, &negated, flip);
+    let low = Integer::from_digits(&high, Order::Msf);
+    high.zeroize();
+    negated.zeroize();
+    (low, bool::from(flip))
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_g_mul_ct_matches_vartime() {
+        let n = order();
+        let mut scalars = vec![
+            Integer::new(),
+            Integer::from(1),
+            Integer::from(15),
+            Integer::from(16),
+            Integer::from(0x1234_5678),
+            (&n - 1u8).complete(),
+            n.clone(),
+        ];
+        scalars.extend((0..16u32).map(|i| {
+            let mut k = Integer::from_digits(&sha256(&i.to_be_bytes()), Order::Msf);
+            k.rem_euc_assign(&n);
+            k
+        }));
+        for k in scalars {
+            assert_eq!(g_mul_ct(&k), g_mul(&k), "{k:x}");
+        }
+    }
+
+    #[test]
+    fn test_mul_ct_matches_vartime() {
+        let n = order();
+        let points = [
+            S256Point::get_generator(),
+            g_mul(&Integer::from(0xc0ffee)),
+            g_mul(&(&n - 2u8).complete()),
+        ];
+        let mut scalars = vec![
+            Integer::new(),
+            Integer::from(1),
+            Integer::from(16),
+            (&n - 1u8).complete(),
+            n.clone(),
+        ];
+        scalars.extend((0..6u32).map(|i| {
+            let mut k = Integer::from_digits(&sha256(&i.to_le_bytes()), Order::Msf);
+            k.rem_euc_assign(&n);
+            k
+        }));
+        for point in &points {
+            for k in &scalars {
+                assert_eq!(mul_ct(point, k), point * k.clone(), "{k:x}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        let mut row = [[0u8; 64]; 16];
+        for (j, entry) in row.iter_mut().enumerate() {
+            entry.fill(j as u8 * 17);
+        }
+        for j in 0..16u8 {
+            assert_eq!(select(&row, j), [j * 17; 64]);
+        }
+    }
+
+    #[test]
+    fn test_selects_match_branches() {
+        let n = order();
+        let a = Scalar::new(Integer::from(7));
+        let b = Scalar::new((&n - 3u8).complete());
+        for bit in [0u8, 1] {
+            let expected = if bit == 1 { &b } else { &a };
+            assert_eq!(&select_scalar(&a, &b, Choice::from(bit)), expected);
+        }
+
+        let p = prime();
+        let x = FieldElement::new(5, p.clone());
+        let y = FieldElement::new((&p - 1u8).complete(), p);
+        assert_eq!(select_field(&x, &y, Choice::from(0)), x);
+        assert_eq!(select_field(&x, &y, Choice::from(1)), y);
+
+        let mut dst = [1u8, 2, 3];
+        conditional_assign_bytes(&mut dst, &[9, 9], Choice::from(1));
+        assert_eq!(dst, [9, 9, 3]);
+        conditional_assign_bytes(&mut dst, &[0, 0, 0], Choice::from(0));
+        assert_eq!(dst, [9, 9, 3]);
+    }
+
+    #[test]
+    fn test_inv_mod_n() {
+        let n = order();
+        for k in [
+            Integer::from(1),
+            Integer::from(2),
+            Integer::from(0xdead_beefu32),
+            (&n - 1u8).complete(),
+        ] {
+            let expected = k.invert_ref(&n).unwrap().complete();
+            assert_eq!(inv_mod_n(&k), expected);
+        }
+    }
+
+    #[test]
+    fn test_low_s() {
+        let n = order();
+        let half = (&n >> 1u32).complete();
+        let cases = [
+            (Integer::from(1), Integer::from(1), false),
+            (half.clone(), half.clone(), false),
+            ((&half + 1u8).complete(), half.clone(), true),
+            ((&n - 1u8).complete(), Integer::from(1), true),
+        ];
+        for (s, expected, flipped) in cases {
+            assert_eq!(low_s(&s), (expected, flipped), "{s:x}");
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::ecc::hash::sha256;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+// Tags used by BIP340/BIP341 often enough that their midstate is worth caching
+const COMMON_TAGS: [&str; 6] = [
+    "BIP0340/aux",
+    "BIP0340/nonce",
+    "BIP0340/challenge",
+    "TapTweak",
+    "TapLeaf",
+    "TapBranch",
+];
+
+// SHA256(tag) || SHA256(tag) is exactly one 64 byte block, so the hasher state after
+// absorbing it is the midstate and can be cloned for every message with the same tag
+fn tag_prefix(tag: &str) -> Sha256 {
+    let tag_hash = sha256(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher
+}
+
+fn tagged_hasher(tag: &str) -> Sha256 {
+    static MIDSTATES: OnceLock<Vec<(&'static str, Sha256)>> = OnceLock::new();
+    let midstates = MIDSTATES.get_or_init(|| {
+        COMMON_TAGS
+            .iter()
+            .map(|tag| (*tag, tag_prefix(tag)))
+            .collect()
+    });
+
+    match midstates.iter().find(|(common, _)| *common == tag) {
+        Some((_, hasher)) => hasher.clone(),
+        None => tag_prefix(tag),
+    }
+}
+
+// SHA256(SHA256(tag) || SHA256(tag) || data)
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let mut hasher = tagged_hasher(tag);
+    hasher.update(data);
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&hasher.finalize());
+    result
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn decode_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_tagged_hash_bip341_vectors() {
+        // BIP341 wallet vectors: key path only output, tweak = hash_TapTweak(internal key)
+        let internal =
+            decode_hex("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+        assert_eq!(
+            hex(&tagged_hash("TapTweak", &internal)),
+            "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70"
+        );
+
+        // Leaf hash of a single script with leaf version 0xc0
+        let mut leaf = vec![0xc0, 34];
+        leaf.extend(decode_hex(
+            "20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac",
+        ));
+        assert_eq!(
+            hex(&tagged_hash("TapLeaf", &leaf)),
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+        );
+    }
+
+    #[test]
+    fn test_tagged_hash_matches_uncached() {
+        for tag in COMMON_TAGS.iter().chain(&["Custom/tag"]) {
+            let mut hasher = tag_prefix(tag);
+            hasher.update(b"abc");
+            assert_eq!(tagged_hash(tag, b"abc")[..], hasher.finalize()[..]);
+        }
+        assert_eq!(
+            hex(&tagged_hash("Custom/tag", b"abc")),
+            "3edb4215e0b19251499e8cd6cb6d3bdfd0b65aed739b87bae17f8866545496fe"
+        );
+    }
+
+    #[test]
+    fn test_different_tags_differ() {
+        let data = b"abc";
+        assert_ne!(tagged_hash("TapLeaf", data), tagged_hash("TapBranch", data));
+        assert_ne!(
+            tagged_hash("TapTweak", data),
+            tagged_hash("Custom/tag", data)
+        );
+        assert_ne!(tagged_hash("TapLeaf", data), sha256(data));
+    }
+}
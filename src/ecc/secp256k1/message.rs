@@ -0,0 +1,300 @@
+use super::{Network, PrivateKey, RecoverableSignature, S256Point, Secp256k1Error, Signature};
+use crate::ecc::hash::{hash160, hash256};
+use crate::encoding::{base58, base64, varint};
+use rug::Integer;
+use rug::integer::Order;
+
+const MAGIC: &[u8] = b"\x18Bitcoin Signed Message:\n";
+
+// hash256 of the magic prefix followed by the varint-prefixed message
+fn message_hash(msg: &[u8]) -> Integer {
+    let mut data = MAGIC.to_vec();
+    data.extend(varint::encode(msg.len() as u64));
+    data.extend_from_slice(msg);
+    Integer::from_digits(&hash256(&data), Order::Msf)
+}
+
+// The address a signed message's header byte says the key is used with. Bitcoin Core only
+// writes the first two, 27..=30 and 31..=34. Electrum and Trezor add 35..=38 for P2SH-wrapped
+// segwit and 39..=42 for native segwit (BIP137)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyEncoding {
+    Uncompressed,
+    #[default]
+    Compressed,
+    P2shP2wpkh,
+    P2wpkh,
+}
+
+impl KeyEncoding {
+    // the header byte for recovery id 0
+    fn header_base(&self) -> u8 {
+        match self {
+            KeyEncoding::Uncompressed => 27,
+            KeyEncoding::Compressed => 31,
+            KeyEncoding::P2shP2wpkh => 35,
+            KeyEncoding::P2wpkh => 39,
+        }
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        *self != KeyEncoding::Uncompressed
+    }
+
+    // the address of this kind for point. Only a custom network's hrp can fail the bech32 one
+    fn address(&self, point: &S256Point, network: Network) -> Option<String> {
+        match self {
+            KeyEncoding::Uncompressed | KeyEncoding::Compressed => {
+                Some(point.address(self.is_compressed(), network))
+            }
+            // the redeem script is OP_0 <hash160(compressed SEC)>
+            KeyEncoding::P2shP2wpkh => {
+                let redeem_script = [&[0x00, 0x14][..], &hash160(&point.sec(true))].concat();
+                let prefix = network.params().p2sh_prefix;
+                Some(base58::encode_check(
+                    &[&[prefix][..], &hash160(&redeem_script)].concat(),
+                ))
+            }
+            KeyEncoding::P2wpkh => point.p2wpkh_address(network).ok(),
+        }
+    }
+}
+
+impl RecoverableSignature {
+    // a signmessage signature: base64 of the header byte followed by r || s. The header holds
+    // the recovery id and the key encoding
+    pub fn from_rpc_base64(s: &str) -> Result<(RecoverableSignature, KeyEncoding), Secp256k1Error> {
+        let bytes = base64::decode(s).map_err(|_| Secp256k1Error::InvalidBase64)?;
+        let [header, compact @ ..]: [u8; 65] = bytes
+            .try_into()
+            .map_err(|_| Secp256k1Error::InvalidMessageSignature)?;
+        let encoding = match header {
+            27..=30 => KeyEncoding::Uncompressed,
+            31..=34 => KeyEncoding::Compressed,
+            35..=38 => KeyEncoding::P2shP2wpkh,
+            39..=42 => KeyEncoding::P2wpkh,
+            _ => return Err(Secp256k1Error::InvalidSignatureHeader(header)),
+        };
+        let sig = Signature::from_compact(&compact)?;
+        let recid = header - encoding.header_base();
+        Ok((RecoverableSignature { sig, recid }, encoding))
+    }
+
+    // inverse of from_rpc_base64
+    pub fn to_rpc_base64(&self, encoding: KeyEncoding) -> String {
+        let mut bytes = vec![encoding.header_base() + self.recid];
+        bytes.extend_from_slice(&self.sig.to_compact());
+        base64::encode(&bytes)
+    }
+}
+
+impl PrivateKey {
+    // Bitcoin Core's signmessage for a compressed key
+    pub fn sign_message(&self, msg: &[u8]) -> String {
+        self.sign_message_with(msg, KeyEncoding::Compressed)
+    }
+
+    // legacy signmessage for wallets whose address uses the uncompressed key
+    pub fn sign_message_uncompressed(&self, msg: &[u8]) -> String {
+        self.sign_message_with(msg, KeyEncoding::Uncompressed)
+    }
+
+    fn sign_message_with(&self, msg: &[u8], encoding: KeyEncoding) -> String {
+        self.sign_recoverable(&message_hash(msg))
+            .to_rpc_base64(encoding)
+    }
+}
+
+// recovers the signing key and checks it hashes to the given address on any standard network.
+// The header decides which kind of address that is: P2PKH, or P2SH-P2WPKH and P2WPKH for the
+// segwit headers
+pub fn verify_message(address: &str, msg: &[u8], sig_b64: &str) -> Result<bool, Secp256k1Error> {
+    let (sig, encoding) = RecoverableSignature::from_rpc_base64(sig_b64)?;
+    let point = match sig.recover(&message_hash(msg)) {
+        Ok(point) => point,
+        Err(Secp256k1Error::RecoveryFailed) => return Ok(false),
+        Err(err) => return Err(err),
+    };
+    Ok(Network::STANDARD
+        .iter()
+        .any(|network| encoding.address(&point, *network).as_deref() == Some(address)))
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"This is an example of a signed message.";
+
+    fn example_key() -> PrivateKey {
+        PrivateKey::from_wif("L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1")
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_sign_message_vector() {
+        // the bitcoinjs-message example, which interoperates with Bitcoin Core's signmessage
+        let key = example_key();
+        let sig = key.sign_message(MESSAGE);
+        assert_eq!(
+            sig,
+            "H9L5yLFjti0QTHhPyFrZCT1V/MMnBtXKmoiKDZ78NDBjERki6ZTQZdSMCtkgoNmp17By9ItJr8o7ChX0XxY91nk="
+        );
+        assert_eq!(
+            verify_message("1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV", MESSAGE, &sig),
+            Ok(true)
+        );
+
+        let sig = key.sign_message_uncompressed(MESSAGE);
+        assert_eq!(
+            sig,
+            "G9L5yLFjti0QTHhPyFrZCT1V/MMnBtXKmoiKDZ78NDBjERki6ZTQZdSMCtkgoNmp17By9ItJr8o7ChX0XxY91nk="
+        );
+        assert_eq!(
+            verify_message("1HZwkjkeaoZfTSaJxDw6aKkxp45agDiEzN", MESSAGE, &sig),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_sign_message_round_trip() {
+        let key = PrivateKey::new(Integer::from(12345));
+        for compressed in [true, false] {
+            let sig = if compressed {
+                key.sign_message(b"hello")
+            } else {
+                key.sign_message_uncompressed(b"hello")
+            };
+            let header = base64::decode(&sig).unwrap()[0];
+            assert_eq!(header >= 31, compressed);
+
+            for network in Network::STANDARD {
+                let address = key.public_key().point().address(compressed, network);
+                assert_eq!(verify_message(&address, b"hello", &sig), Ok(true));
+                assert_eq!(verify_message(&address, b"goodbye", &sig), Ok(false));
+            }
+            // the address of the other key encoding doesn't match
+            let other = key
+                .public_key()
+                .point()
+                .address(!compressed, Network::Mainnet);
+            assert_eq!(verify_message(&other, b"hello", &sig), Ok(false));
+        }
+    }
+
+    #[test]
+    fn test_verify_message_malformed() {
+        let address = "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV";
+        assert_eq!(
+            verify_message(address, MESSAGE, "not base64!"),
+            Err(Secp256k1Error::InvalidBase64)
+        );
+        assert_eq!(
+            verify_message(address, MESSAGE, &base64::encode(&[31u8; 64])),
+            Err(Secp256k1Error::InvalidMessageSignature)
+        );
+        for header in [0u8, 26, 43, 255] {
+            let mut bytes = vec![header];
+            bytes.extend_from_slice(&[1u8; 64]);
+            assert_eq!(
+                verify_message(address, MESSAGE, &base64::encode(&bytes)),
+                Err(Secp256k1Error::InvalidSignatureHeader(header))
+            );
+        }
+    }
+
+    #[test]
+    fn test_rpc_header_vectors() {
+        // the bitcoinjs-message example signature under each header range: (header, encoding,
+        // the address it recovers to)
+        let compact = "9L5yLFjti0QTHhPyFrZCT1V/MMnBtXKmoiKDZ78NDBjERki6ZTQZdSMCtkgoNmp17By9ItJr8o7ChX0XxY91nk=";
+        let cases = [
+            (
+                'G',
+                27,
+                KeyEncoding::Uncompressed,
+                "1HZwkjkeaoZfTSaJxDw6aKkxp45agDiEzN",
+            ),
+            (
+                'H',
+                31,
+                KeyEncoding::Compressed,
+                "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+            ),
+            (
+                'I',
+                35,
+                KeyEncoding::P2shP2wpkh,
+                "3DnW8JGpPViEZdpqat8qky1zc26EKbXnmM",
+            ),
+            (
+                'J',
+                39,
+                KeyEncoding::P2wpkh,
+                "bc1qngw83fg8dz0k749cg7k3emc7v98wy0c74dlrkd",
+            ),
+        ];
+        let key = S256Point::from(example_key().public_key());
+        for (first, header, encoding, address) in cases {
+            let sig_b64 = format!("{first}{compact}");
+            assert_eq!(base64::decode(&sig_b64).unwrap()[0], header);
+            let (sig, parsed) = RecoverableSignature::from_rpc_base64(&sig_b64).unwrap();
+            assert_eq!((sig.recid, parsed), (0, encoding));
+            assert_eq!(sig.to_rpc_base64(encoding), sig_b64);
+            assert_eq!(sig.recover(&message_hash(MESSAGE)), Ok(key.clone()));
+            assert_eq!(
+                encoding.address(&key, Network::Mainnet).as_deref(),
+                Some(address)
+            );
+            assert_eq!(verify_message(address, MESSAGE, &sig_b64), Ok(true));
+        }
+        // the segwit headers don't vouch for the P2PKH address of the same key, or the reverse
+        assert_eq!(
+            verify_message(
+                "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+                MESSAGE,
+                &format!("J{compact}")
+            ),
+            Ok(false)
+        );
+        assert_eq!(
+            verify_message(cases[3].3, MESSAGE, &format!("H{compact}")),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_rpc_base64_round_trip() {
+        let key = PrivateKey::new(Integer::from(12345));
+        let z = message_hash(b"hello");
+        let recoverable = key.sign_recoverable(&z);
+        for encoding in [
+            KeyEncoding::Uncompressed,
+            KeyEncoding::Compressed,
+            KeyEncoding::P2shP2wpkh,
+            KeyEncoding::P2wpkh,
+        ] {
+            let sig_b64 = key.sign_message_with(b"hello", encoding);
+            assert_eq!(sig_b64, recoverable.to_rpc_base64(encoding));
+            let header = base64::decode(&sig_b64).unwrap()[0];
+            assert_eq!(header, encoding.header_base() + recoverable.recid);
+            assert_eq!(
+                RecoverableSignature::from_rpc_base64(&sig_b64),
+                Ok((recoverable.clone(), encoding))
+            );
+            assert_eq!(encoding.is_compressed(), header >= 31);
+
+            for network in Network::STANDARD {
+                let address = encoding.address(key.public_key().point(), network).unwrap();
+                assert_eq!(verify_message(&address, b"hello", &sig_b64), Ok(true));
+                assert_eq!(verify_message(&address, b"goodbye", &sig_b64), Ok(false));
+            }
+        }
+    }
+}
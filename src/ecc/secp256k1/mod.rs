@@ -0,0 +1,4238 @@
+pub mod bip32;
+pub mod ct;
+pub mod hash;
+pub mod message;
+pub mod network;
+pub mod scalar;
+pub mod schnorr;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod spki;
+pub mod taproot;
+pub mod vanity;
+#[cfg(test)]
+mod wycheproof;
+
+pub use network::{Network, NetworkParams};
+pub use scalar::{Overflow, Scalar};
+
+use super::curve::{
+    Bounded::{self, Finite, Infinity},
+    EcPoint, PointTable, WnafTable, mul_interleaved, multi_mul, odd_multiples, par_map,
+};
+use super::curves::SECP256K1;
+use super::field::{AdditionChain, ChainOp, FieldElement, Pow};
+use super::hash::{hash160, hash256, sha256};
+use crate::encoding::base58::{self, Base58Error};
+use crate::encoding::bech32::{self, Bech32Error};
+use crate::encoding::hex::{self, HexError};
+use hash::tagged_hash;
+use hmac::{Hmac, Mac};
+use rand_core::{CryptoRng, RngCore};
+use rug::integer::Order;
+use rug::ops::RemRoundingAssign;
+use rug::{Complete, Integer};
+use sha2::{Digest, Sha256};
+use std::fmt::{Debug, Display, LowerHex};
+use std::io::{self, Read};
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use subtle::Choice;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const PRIME_STR: &str = SECP256K1.p;
+const A_STR: &str = SECP256K1.a;
+const B_STR: &str = SECP256K1.b;
+const GX_STR: &str = SECP256K1.gx;
+const GY_STR: &str = SECP256K1.gy;
+const N_STR: &str = SECP256K1.n;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Secp256k1Error {
+    XOutOfRange,
+    NotOnCurve,
+    DerTruncated,
+    DerTrailingBytes,
+    DerInvalidTag,
+    DerInvalidLength,
+    DerZeroLengthInteger,
+    DerNegativeInteger,
+    DerNonMinimalInteger,
+    DerIntegerTooLong,
+    SignatureOutOfRange,
+    InvalidRecoveryId,
+    RecoveryFailed,
+    InvalidTweak,
+    InvalidBase58Character,
+    BadChecksum,
+    InvalidWif,
+    SecretOutOfRange,
+    InvalidBase64,
+    InvalidMessageSignature,
+    InvalidSecLength,
+    InvalidSecPrefix,
+    SecPointAtInfinity,
+    SecTrailingBytes,
+    YOutOfRange,
+    HybridSecNotAllowed,
+    HybridParityMismatch,
+    WrongCurve,
+    WrongField,
+    ScalarOverflow,
+    BadHex,
+    BadHexLength,
+    OutOfRange,
+    EmptyKeyList,
+    DuplicateKey,
+    AggregateAtInfinity,
+    InvalidSpki,
+    UnsupportedKeyAlgorithm,
+    UnsupportedCurve,
+    InvalidPem,
+    NonceOutOfRange,
+    WrongNetwork,
+    ZeroSignatureComponent,
+    HighS,
+    // a signed message header byte outside 27..=42
+    InvalidSignatureHeader(u8),
+    // which argument of verify_hex or sign_hex was bad, and why
+    InvalidArgument(HexArgument, Box<Secp256k1Error>),
+}
+
+// The inputs of verify_hex and sign_hex, for InvalidArgument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexArgument {
+    PublicKey,
+    PrivateKey,
+    Hash,
+    Signature,
+}
+
+impl Display for HexArgument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HexArgument::PublicKey => write!(f, "public key"),
+            HexArgument::PrivateKey => write!(f, "private key"),
+            HexArgument::Hash => write!(f, "message hash"),
+            HexArgument::Signature => write!(f, "signature"),
+        }
+    }
+}
+
+impl Display for Secp256k1Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Secp256k1Error::XOutOfRange => {
+                write!(f, "x coordinate is not less than the field prime")
+            }
+            Secp256k1Error::NotOnCurve => write!(f, "point is not on the secp256k1 curve"),
+            Secp256k1Error::DerTruncated => write!(f, "DER signature ends unexpectedly"),
+            Secp256k1Error::DerTrailingBytes => write!(f, "DER signature has trailing bytes"),
+            Secp256k1Error::DerInvalidTag => write!(f, "DER signature has an unexpected tag"),
+            Secp256k1Error::DerInvalidLength => write!(f, "DER signature has an invalid length"),
+            Secp256k1Error::DerZeroLengthInteger => write!(f, "DER integer has zero length"),
+            Secp256k1Error::DerNegativeInteger => write!(f, "DER integer is negative"),
+            Secp256k1Error::DerNonMinimalInteger => {
+                write!(f, "DER integer is not minimally encoded")
+            }
+            Secp256k1Error::DerIntegerTooLong => write!(f, "DER integer is longer than 33 bytes"),
+            Secp256k1Error::SignatureOutOfRange => {
+                write!(f, "signature component is not less than the group order")
+            }
+            Secp256k1Error::InvalidRecoveryId => write!(f, "recovery id must be in 0..=3"),
+            Secp256k1Error::RecoveryFailed => {
+                write!(f, "no valid public key can be recovered from the signature")
+            }
+            Secp256k1Error::InvalidTweak => write!(f, "tweak is out of range or cancels the key"),
+            Secp256k1Error::InvalidBase58Character => write!(f, "invalid base58 character"),
+            Secp256k1Error::BadChecksum => write!(f, "base58 checksum does not match"),
+            Secp256k1Error::InvalidWif => write!(f, "WIF has an invalid prefix or length"),
+            Secp256k1Error::SecretOutOfRange => write!(f, "secret is not in [1, n)"),
+            Secp256k1Error::InvalidBase64 => write!(f, "signature is not valid base64"),
+            Secp256k1Error::InvalidMessageSignature => {
+                write!(f, "message signature must be 65 bytes")
+            }
+            Secp256k1Error::InvalidSignatureHeader(header) => {
+                write!(f, "message signature header {header} is not in 27..=42")
+            }
+            Secp256k1Error::InvalidSecLength => write!(f, "SEC public key has the wrong length"),
+            Secp256k1Error::InvalidSecPrefix => write!(f, "SEC public key has an unknown prefix"),
+            Secp256k1Error::SecPointAtInfinity => {
+                write!(f, "SEC public key encodes the point at infinity")
+            }
+            Secp256k1Error::SecTrailingBytes => write!(f, "SEC public key has trailing bytes"),
+            Secp256k1Error::YOutOfRange => {
+                write!(f, "y coordinate is not less than the field prime")
+            }
+            Secp256k1Error::HybridSecNotAllowed => {
+                write!(
+                    f,
+                    "hybrid SEC public keys are not allowed by the parse policy"
+                )
+            }
+            Secp256k1Error::HybridParityMismatch => {
+                write!(f, "hybrid SEC prefix does not match the parity of y")
+            }
+            Secp256k1Error::WrongCurve => write!(f, "point is not on a curve with a = 0, b = 7"),
+            Secp256k1Error::WrongField => {
+                write!(f, "field element is not modulo the secp256k1 prime")
+            }
+            Secp256k1Error::ScalarOverflow => write!(f, "scalar is not less than the group order"),
+            Secp256k1Error::BadHex => write!(f, "expected a non-empty string of hex digits"),
+            Secp256k1Error::BadHexLength => write!(f, "expected exactly 64 hex digits"),
+            Secp256k1Error::EmptyKeyList => write!(f, "no public keys to aggregate"),
+            Secp256k1Error::DuplicateKey => write!(f, "the same public key appears twice"),
+            Secp256k1Error::AggregateAtInfinity => {
+                write!(f, "aggregate public key is the point at infinity")
+            }
+            Secp256k1Error::OutOfRange => {
+                write!(f, "field element is not less than the field prime")
+            }
+            Secp256k1Error::InvalidSpki => {
+                write!(f, "SubjectPublicKeyInfo is not valid DER")
+            }
+            Secp256k1Error::UnsupportedKeyAlgorithm => {
+                write!(f, "SubjectPublicKeyInfo is not an elliptic curve key")
+            }
+            Secp256k1Error::UnsupportedCurve => {
+                write!(f, "SubjectPublicKeyInfo names a curve other than secp256k1")
+            }
+            Secp256k1Error::InvalidPem => write!(f, "expected a PEM PUBLIC KEY block"),
+            Secp256k1Error::NonceOutOfRange => write!(f, "nonce k is not in [1, n)"),
+            Secp256k1Error::ZeroSignatureComponent => write!(f, "signature r or s is zero"),
+            Secp256k1Error::HighS => write!(f, "signature s is above n/2"),
+            Secp256k1Error::WrongNetwork => write!(f, "key or address is for a different network"),
+            Secp256k1Error::InvalidArgument(argument, err) => {
+                write!(f, "invalid {argument}: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Secp256k1Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Secp256k1Error::InvalidArgument(_, err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Base58Error> for Secp256k1Error {
+    fn from(err: Base58Error) -> Self {
+        match err {
+            Base58Error::InvalidCharacter(_) => Secp256k1Error::InvalidBase58Character,
+            Base58Error::TooShort | Base58Error::BadChecksum => Secp256k1Error::BadChecksum,
+        }
+    }
+}
+
+impl From<HexError> for Secp256k1Error {
+    fn from(_: HexError) -> Self {
+        Secp256k1Error::BadHex
+    }
+}
+
+// How tolerant S256Point::parse is of non-canonical SEC encodings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParsePolicy {
+    // only 0x02/0x03 compressed and 0x04 uncompressed keys
+    #[default]
+    Strict,
+    // also OpenSSL's hybrid 0x06/0x07 keys, as long as the prefix matches y's parity
+    AllowHybrid,
+}
+
+// How strictly verify_der treats a DER signature. Standard is what Bitcoin Core relays, Consensus
+// what blocks could hold before BIP66 made strict DER a consensus rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyPolicy {
+    // strict DER, low-S only, and r or s of 0 is an error like any other malformed signature
+    #[default]
+    Standard,
+    // DER as Bitcoin Core's lax parser reads it, high-S allowed, and r or s outside [1, n) only
+    // makes the signature invalid
+    Consensus,
+}
+
+// z for signing or verifying msg: hash256 of the bytes read as a big-endian integer
+pub fn z_from_message(msg: &[u8]) -> Integer {
+    Integer::from_digits(&hash256(msg), Order::Msf)
+}
+
+// how much hash_reader reads at a time
+pub const HASH_READER_CHUNK: usize = 64 * 1024;
+
+// hash256 of everything reader yields, without holding it all in memory. Only the inner SHA256
+// sees the stream, the outer one hashes its 32 byte digest
+pub fn hash_reader(reader: &mut impl Read) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_READER_CHUNK];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(sha256(&hasher.finalize()))
+}
+
+// z_from_message for a stream
+fn z_from_reader(reader: &mut impl Read) -> io::Result<Integer> {
+    Ok(Integer::from_digits(&hash_reader(reader)?, Order::Msf))
+}
+
+fn prime() -> Integer {
+    static PRIME: OnceLock<Integer> = OnceLock::new();
+    PRIME
+        .get_or_init(|| Integer::from_str_radix(PRIME_STR, 16).unwrap())
+        .clone()
+}
+
+fn order() -> Integer {
+    static ORDER: OnceLock<Integer> = OnceLock::new();
+    ORDER
+        .get_or_init(|| Integer::from_str_radix(N_STR, 16).unwrap())
+        .clone()
+}
+
+// The shared prefix of the p - 2 and (p + 1) / 4 chains from libsecp256k1. Saved value i is
+// x^(2^k - 1) for k = 1, 2, 3, 6, 9, 11, 22, 44, 88, 176, 220, and it ends on x^(2^223 - 1)
+fn chain_prefix() -> Vec<ChainOp> {
+    use ChainOp::{Mul, Save, Square};
+    let mut ops = vec![Square(1), Mul(0), Save, Square(1), Mul(0), Save];
+    // (squarings, saved value to multiply by) for x6, x9, x11, x22, x44, x88, x176, x220
+    for (times, index) in [
+        (3, 2),
+        (3, 2),
+        (2, 1),
+        (11, 5),
+        (22, 6),
+        (44, 7),
+        (88, 8),
+        (44, 7),
+    ] {
+        ops.extend([Square(times), Mul(index), Save]);
+    }
+    ops.extend([Square(3), Mul(2)]);
+    ops
+}
+
+// 253 squarings and 13 multiplications, against about 128 multiplications for pow
+fn sqrt_chain() -> &'static AdditionChain {
+    use ChainOp::{Mul, Square};
+    static CHAIN: OnceLock<AdditionChain> = OnceLock::new();
+    CHAIN.get_or_init(|| {
+        let mut ops = chain_prefix();
+        ops.extend([Square(23), Mul(6), Square(6), Mul(1), Square(2)]);
+        AdditionChain::new(ops)
+    })
+}
+
+// 255 squarings and 15 multiplications
+fn inverse_chain() -> &'static AdditionChain {
+    use ChainOp::{Mul, Square};
+    static CHAIN: OnceLock<AdditionChain> = OnceLock::new();
+    CHAIN.get_or_init(|| {
+        let mut ops = chain_prefix();
+        ops.extend([
+            Square(23),
+            Mul(6),
+            Square(5),
+            Mul(0),
+            Square(3),
+            Mul(1),
+            Square(2),
+            Mul(0),
+        ]);
+        AdditionChain::new(ops)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct S256Field {
+    element: FieldElement,
+}
+
+impl S256Field {
+    pub fn new(element: &str) -> Self {
+        Self::try_new(element).unwrap_or_else(|err| panic!("Error: {err}, cannot create S256Field"))
+    }
+
+    // big-endian hex without prefix or sign, the value must be below p
+    pub fn try_new(element: &str) -> Result<Self, Secp256k1Error> {
+        if element.is_empty() || !element.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Secp256k1Error::BadHex);
+        }
+        let value = Integer::from_str_radix(element, 16).map_err(|_| Secp256k1Error::BadHex)?;
+        let prime = prime();
+        if value >= prime {
+            return Err(Secp256k1Error::OutOfRange);
+        }
+        Ok(Self {
+            element: FieldElement::new(value, prime),
+        })
+    }
+
+    // exactly 64 hex digits, the fixed-width form BIPs print 32-byte values in
+    pub fn from_hex(hex: &str) -> Result<Self, Secp256k1Error> {
+        if hex.len() != 64 {
+            return Err(Secp256k1Error::BadHexLength);
+        }
+        Self::try_new(hex)
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+
+    // p % 4 == 3, so a square root (if one exists) is v^((p + 1) / 4)
+    pub fn sqrt(&self) -> Self {
+        Self {
+            element: sqrt_chain().exec(&self.element),
+        }
+    }
+
+    // v^(p - 2) by Fermat, None for zero
+    pub fn invert(&self) -> Option<Self> {
+        (!self.element.is_zero()).then(|| Self {
+            element: inverse_chain().exec(&self.element),
+        })
+    }
+}
+
+// always 64 lowercase hex digits, zero padded
+impl Display for S256Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:064x}", self.element.value)
+    }
+}
+
+impl LowerHex for S256Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:064x}", self.element.value)
+    }
+}
+
+impl From<S256Field> for FieldElement {
+    fn from(field: S256Field) -> Self {
+        field.element
+    }
+}
+
+impl AsRef<FieldElement> for S256Field {
+    fn as_ref(&self) -> &FieldElement {
+        &self.element
+    }
+}
+
+// only elements of F_p with p the secp256k1 prime, the value itself is already reduced
+impl TryFrom<FieldElement> for S256Field {
+    type Error = Secp256k1Error;
+
+    fn try_from(element: FieldElement) -> Result<Self, Self::Error> {
+        if element.order != prime() {
+            return Err(Secp256k1Error::WrongField);
+        }
+        Ok(Self { element })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct S256Point {
+    point: EcPoint,
+}
+
+impl S256Point {
+    pub fn new(x: Bounded<S256Field>, y: Bounded<S256Field>) -> Self {
+        Self::try_new(x, y).unwrap_or_else(|err| panic!("Error: {err}, cannot create S256Point"))
+    }
+
+    // (Infinity, Infinity) is the point at infinity, a pair with only one Infinity is rejected
+    pub fn try_new(x: Bounded<S256Field>, y: Bounded<S256Field>) -> Result<Self, Secp256k1Error> {
+        let a = S256Field::new(A_STR);
+        let b = S256Field::new(B_STR);
+
+        let point = match (x, y) {
+            (Finite(x), Finite(y)) => {
+                if y.element.pow(2) != &x.element.pow(3) + &b.element {
+                    return Err(Secp256k1Error::NotOnCurve);
+                }
+                EcPoint::new(Finite(x.element), Finite(y.element), a.element, b.element)
+            }
+            (Infinity, Infinity) => EcPoint::new(Infinity, Infinity, a.element, b.element),
+            _ => return Err(Secp256k1Error::NotOnCurve),
+        };
+        Ok(Self { point })
+    }
+
+    // parsed once and shared between threads, every sign and verify needs it
+    pub fn get_generator() -> Self {
+        static GENERATOR: OnceLock<S256Point> = OnceLock::new();
+        GENERATOR
+            .get_or_init(|| {
+                let a = S256Field::new(A_STR);
+                let b = S256Field::new(B_STR);
+                let x = S256Field::new(GX_STR);
+                let y = S256Field::new(GY_STR);
+                let p = EcPoint::new(Finite(x.element), Finite(y.element), a.element, b.element);
+                Self { point: p }
+            })
+            .clone()
+    }
+
+    pub fn has_even_y(&self) -> bool {
+        match &self.point.y {
+            Finite(y) => y.value.is_even(),
+            Infinity => false,
+        }
+    }
+
+    // -P = (x, p - y), infinity stays infinity
+    pub fn negate(&self) -> Self {
+        Self {
+            point: -&self.point,
+        }
+    }
+
+    // the even-y point with the same x, and whether P had to be negated to get it.
+    // The point at infinity is returned as is
+    pub fn even_y(&self) -> (Self, bool) {
+        if self.point.x == Infinity || self.has_even_y() {
+            (self.clone(), false)
+        } else {
+            (self.negate(), true)
+        }
+    }
+
+    // BIP340 x-only encoding: the 32-byte big-endian x coordinate, y is implied even
+    pub fn xonly(&self) -> [u8; 32] {
+        match &self.point.x {
+            Finite(x) => to_bytes32(&x.value),
+            Infinity => panic!("Error: the point at infinity has no x-only encoding"),
+        }
+    }
+
+    // SEC encoding: 02/03 prefix (y parity) + x when compressed, 04 + x + y otherwise
+    pub fn sec(&self, compressed: bool) -> Vec<u8> {
+        let (x, y) = match (&self.point.x, &self.point.y) {
+            (Finite(x), Finite(y)) => (to_bytes32(&x.value), to_bytes32(&y.value)),
+            _ => panic!("Error: the point at infinity has no SEC encoding"),
+        };
+        if compressed {
+            let prefix = if self.has_even_y() { 0x02 } else { 0x03 };
+            [&[prefix][..], &x].concat()
+        } else {
+            [&[0x04][..], &x, &y].concat()
+        }
+    }
+
+    // sec as lowercase hex, the form FromStr reads back
+    pub fn to_sec_hex(&self, compressed: bool) -> String {
+        hex::encode(&self.sec(compressed))
+    }
+
+    // P2PKH address: Base58Check of the network's version byte + hash160(SEC)
+    pub fn address(&self, compressed: bool, network: Network) -> String {
+        let prefix = network.params().p2pkh_prefix;
+        let h160 = hash160(&self.sec(compressed));
+        base58::encode_check(&[&[prefix][..], &h160].concat())
+    }
+
+    // native segwit v0 address: the witness program is hash160 of the compressed SEC. Only a
+    // custom network's hrp can make this fail
+    pub fn p2wpkh_address(&self, network: Network) -> Result<String, Bech32Error> {
+        bech32::encode_segwit(network.params().hrp, 0, &hash160(&self.sec(true)))
+    }
+
+    // inverse of sec: 33-byte compressed or 65-byte uncompressed encodings
+    pub fn from_sec(bytes: &[u8]) -> Result<Self, Secp256k1Error> {
+        Self::parse(bytes, ParsePolicy::Strict)
+    }
+
+    // SEC decoding that rejects everything but a single canonical point encoding, with hybrid
+    // keys (0x06/0x07 + x + y, where the prefix repeats y's parity) only let through by policy
+    pub fn parse(bytes: &[u8], policy: ParsePolicy) -> Result<Self, Secp256k1Error> {
+        let expected = match bytes.first() {
+            None => return Err(Secp256k1Error::InvalidSecLength),
+            Some(0x00) => return Err(Secp256k1Error::SecPointAtInfinity),
+            Some(0x02 | 0x03) => 33,
+            Some(0x04) => 65,
+            Some(0x06 | 0x07) if policy == ParsePolicy::AllowHybrid => 65,
+            Some(0x06 | 0x07) => return Err(Secp256k1Error::HybridSecNotAllowed),
+            Some(_) => return Err(Secp256k1Error::InvalidSecPrefix),
+        };
+        if bytes.len() < expected {
+            return Err(Secp256k1Error::InvalidSecLength);
+        }
+        if bytes.len() > expected {
+            return Err(Secp256k1Error::SecTrailingBytes);
+        }
+
+        let prime = prime();
+        let x = Integer::from_digits(&bytes[1..33], Order::Msf);
+        if x >= prime {
+            return Err(Secp256k1Error::XOutOfRange);
+        }
+        if expected == 33 {
+            let even = Self::lift_x(x)?;
+            return match bytes[0] {
+                0x02 => Ok(even),
+                _ => Ok(Self {
+                    point: -&even.point,
+                }),
+            };
+        }
+
+        let y = Integer::from_digits(&bytes[33..], Order::Msf);
+        if y >= prime {
+            return Err(Secp256k1Error::YOutOfRange);
+        }
+        if bytes[0] != 0x04 && (bytes[0] == 0x07) != y.is_odd() {
+            return Err(Secp256k1Error::HybridParityMismatch);
+        }
+        let x = FieldElement::new(x, prime.clone());
+        let y = FieldElement::new(y, prime);
+        let a = S256Field::new(A_STR);
+        let b = S256Field::new(B_STR);
+        let point = EcPoint::try_new(Finite(x), Finite(y), a.element, b.element)
+            .map_err(|_| Secp256k1Error::NotOnCurve)?;
+        Ok(Self { point })
+    }
+
+    // lift_x from BIP340: always picks the root with even y
+    pub fn from_xonly(bytes: &[u8; 32]) -> Result<Self, Secp256k1Error> {
+        Self::lift_x(Integer::from_digits(bytes, Order::Msf))
+    }
+
+    fn lift_x(x: Integer) -> Result<Self, Secp256k1Error> {
+        let prime = prime();
+        if x >= prime {
+            return Err(Secp256k1Error::XOutOfRange);
+        }
+
+        let a = S256Field::new(A_STR);
+        let b = S256Field::new(B_STR);
+        let x = S256Field {
+            element: FieldElement::new(x, prime),
+        };
+        let alpha = S256Field {
+            element: &x.element.pow(3) + &b.element,
+        };
+        let beta = alpha.sqrt();
+        if beta.element.pow(2) != alpha.element {
+            return Err(Secp256k1Error::NotOnCurve);
+        }
+
+        let odd = Choice::from(beta.element.value.is_odd() as u8);
+        let y = ct::select_field(&beta.element, &-&beta.element, odd);
+        let p = EcPoint::new(Finite(x.element), Finite(y), a.element, b.element);
+        Ok(Self { point: p })
+    }
+
+    // ECDSA: with u = z/s and v = r/s (mod n), the signature is valid when (u*G + v*P).x == r
+    pub fn verify(&self, z: &Integer, sig: &Signature) -> bool {
+        self.verify_using(z, sig, &odd_multiples(&self.point, GLV_WIDTH))
+    }
+
+    // The odd multiples of this key for verify_with_table, so verifying many signatures from
+    // one key builds them once. Wider than the tables verify makes for itself
+    pub fn precompute(&self) -> WnafTable {
+        WnafTable::new(&self.point, PRECOMPUTE_WIDTH)
+    }
+
+    // verify with a table from precompute. Panics if the table is for a different key
+    pub fn verify_with_table(&self, z: &Integer, sig: &Signature, table: &WnafTable) -> bool {
+        assert!(
+            table.point() == self.point,
+            "Error: wNAF table was built for a different public key"
+        );
+        self.verify_using(z, sig, table.table())
+    }
+
+    fn verify_using(&self, z: &Integer, sig: &Signature, table: &PointTable) -> bool {
+        // r and s are Scalars and already below n
+        if sig.r.is_zero() || sig.s.is_zero() {
+            return false;
+        }
+        if self.point.x == Infinity {
+            return false;
+        }
+
+        // everything here is public, so the vartime inversion and multiplications are fine
+        let n = order();
+        let exp = (&n - 2u8).complete();
+        let s_inv = Scalar::new(sig.s.as_integer().pow_mod_ref(&exp, &n).unwrap().complete());
+        let u = &Scalar::new(z.clone()) * &s_inv;
+        let v = &sig.r * &s_inv;
+
+        let total = mul_add_g_with(table, u.as_integer(), v.as_integer());
+        match total.point.x {
+            Finite(x) => Scalar::new(x.value) == sig.r,
+            Infinity => false,
+        }
+    }
+
+    // verify with z = z_from_message(msg)
+    pub fn verify_message_hash256(&self, msg: &[u8], sig: &Signature) -> bool {
+        self.verify(&z_from_message(msg), sig)
+    }
+
+    // same as verify, but also rejects high-S signatures like Bitcoin's standardness rules do
+    pub fn verify_strict(&self, z: &Integer, sig: &Signature) -> bool {
+        sig.is_low_s() && self.verify(z, sig)
+    }
+
+    // Ok(false) for a signature the policy lets through that doesn't verify, Err when the
+    // policy rejects the signature without getting that far
+    pub fn verify_der(
+        &self,
+        z: &Integer,
+        der: &[u8],
+        policy: VerifyPolicy,
+    ) -> Result<bool, Secp256k1Error> {
+        let sig = match policy {
+            VerifyPolicy::Standard => {
+                let sig = Signature::parse_der(der)?;
+                if sig.r.is_zero() || sig.s.is_zero() {
+                    return Err(Secp256k1Error::ZeroSignatureComponent);
+                }
+                if !sig.is_low_s() {
+                    return Err(Secp256k1Error::HighS);
+                }
+                sig
+            }
+            VerifyPolicy::Consensus => {
+                let (r, s) = parse_der_lax(der)?;
+                let n = order();
+                if r >= n || s >= n {
+                    return Ok(false);
+                }
+                Signature::new(Scalar::new(r), Scalar::new(s))
+            }
+        };
+        Ok(self.verify(z, &sig))
+    }
+
+    // BIP341 output key: Q = P + hash_TapTweak(P.x || merkle_root)*G, where P is the even-y
+    // lift of the internal key. The bool is true when Q has odd y (the control block parity)
+    pub fn tap_tweak(
+        &self,
+        merkle_root: Option<&[u8; 32]>,
+    ) -> Result<(S256Point, bool), Secp256k1Error> {
+        if self.point.x == Infinity {
+            return Err(Secp256k1Error::InvalidTweak);
+        }
+        let internal = Self::from_xonly(&self.xonly())?;
+        let t = tap_tweak_scalar(&self.xonly(), merkle_root)?;
+        let output = &internal + &g_mul(&t);
+        if output.point.x == Infinity {
+            return Err(Secp256k1Error::InvalidTweak);
+        }
+        let odd = !output.has_even_y();
+        Ok((output, odd))
+    }
+}
+
+fn tap_tweak_scalar(
+    internal_x: &[u8; 32],
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<Integer, Secp256k1Error> {
+    let mut data = internal_x.to_vec();
+    if let Some(root) = merkle_root {
+        data.extend_from_slice(root);
+    }
+    let t = Integer::from_digits(&tagged_hash("TapTweak", &data), Order::Msf);
+    if t >= order() {
+        return Err(Secp256k1Error::InvalidTweak);
+    }
+    Ok(t)
+}
+
+// Fixed-base comb for G: row i holds j * 16^i * G for j in 1..16, so k*G costs one addition per
+// nonzero nibble of k and no doublings. Built on first use and shared by every thread. The
+// row bases 16^i * G are a chain of doublings, but the rows themselves are independent and
+// are built in parallel with the parallel feature
+const G_WINDOWS: usize = 64;
+
+fn g_table() -> &'static [PointTable] {
+    static TABLE: OnceLock<Vec<PointTable>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut bases = vec![S256Point::get_generator().point];
+        for i in 1..G_WINDOWS {
+            let mut base = bases[i - 1].clone();
+            for _ in 0..4 {
+                base = &base + &base;
+            }
+            bases.push(base);
+        }
+        par_map(&bases, |base| {
+            let mut row = vec![base.clone()];
+            for j in 1..15 {
+                let next = &row[j - 1] + base;
+                row.push(next);
+            }
+            PointTable::new(row)
+        })
+    })
+}
+
+// k*G using the precomputed table, k is reduced mod n first
+pub fn g_mul(k: &Integer) -> S256Point {
+    let mut k = k.clone();
+    k.rem_euc_assign(&order());
+    let mut bytes = to_bytes32(&k);
+
+    let g = S256Point::get_generator();
+    let mut result = EcPoint::new(Infinity, Infinity, g.point.a, g.point.b);
+    for (i, row) in g_table().iter().enumerate() {
+        let byte = bytes[31 - i / 2];
+        let nibble = if i % 2 == 0 { byte & 0x0f } else { byte >> 4 };
+        if nibble != 0 {
+            result = &result + row.get(nibble as usize - 1);
+        }
+    }
+
+    wipe(&mut k);
+    bytes.zeroize();
+    S256Point { point: result }
+}
+
+// GLV endomorphism: (x, y) -> (beta*x, y) is the same as multiplying by lambda, so k*P can be
+// split into k1*P + k2*(lambda*P) with both halves around 128 bits and a shared doubling chain.
+// a1, b1, a2, b2 is the short lattice basis of {(x, y) : x + y*lambda = 0 mod n}, b2 = a1
+const BETA_STR: &str = "7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee";
+const GLV_A1_STR: &str = "3086d221a7d46bcde86c90e49284eb15";
+const GLV_B1_STR: &str = "-e4437ed6010e88286f547fa90abfe4c3";
+const GLV_A2_STR: &str = "114ca50f7a8e2f3f657c1108d9d44cfd8";
+const GLV_WIDTH: u32 = 5;
+
+struct GlvConstants {
+    beta: FieldElement,
+    a1: Integer,
+    b1: Integer,
+    a2: Integer,
+}
+
+fn glv() -> &'static GlvConstants {
+    static GLV: OnceLock<GlvConstants> = OnceLock::new();
+    GLV.get_or_init(|| GlvConstants {
+        beta: FieldElement::from_str(BETA_STR, PRIME_STR),
+        a1: Integer::from_str_radix(GLV_A1_STR, 16).unwrap(),
+        b1: Integer::from_str_radix(GLV_B1_STR, 16).unwrap(),
+        a2: Integer::from_str_radix(GLV_A2_STR, 16).unwrap(),
+    })
+}
+
+// k = k1 + k2*lambda mod n for k in [0, n), by rounding k against the lattice basis
+fn glv_split(k: &Integer) -> (Integer, Integer) {
+    let n = order();
+    let GlvConstants { a1, b1, a2, .. } = glv();
+    let b2 = a1;
+    let half = (&n >> 1u32).complete();
+    let c1 = ((b2 * k).complete() + &half) / &n;
+    let c2 = (&half - (b1 * k).complete()) / &n;
+    let k1 = k - (&c1 * a1).complete() - (&c2 * a2).complete();
+    let k2 = -(&c1 * b1).complete() - (&c2 * b2).complete();
+    (k1, k2)
+}
+
+// lambda*P, computed as (beta*x, y)
+fn endomorphism(point: &EcPoint) -> EcPoint {
+    match &point.x {
+        Finite(x) => EcPoint {
+            x: Finite(&glv().beta * x),
+            ..point.clone()
+        },
+        Infinity => point.clone(),
+    }
+}
+
+// Window of the per-key tables from S256Point::precompute, built once and then reused
+const PRECOMPUTE_WIDTH: u32 = 7;
+
+// Odd multiple tables of G and lambda*G for the G half of verification. G never changes, so
+// these are wider than the per-key tables
+const G_WNAF_WIDTH: u32 = 8;
+
+fn g_wnaf_tables() -> &'static [PointTable; 2] {
+    static TABLES: OnceLock<[PointTable; 2]> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let g = odd_multiples(&S256Point::get_generator().point, G_WNAF_WIDTH);
+        let g_endo = endomorphism_table(&g);
+        [g, g_endo]
+    })
+}
+
+fn endomorphism_table(table: &PointTable) -> PointTable {
+    table.iter().map(|p| endomorphism(&p.into())).collect()
+}
+
+impl S256Point {
+    // k*P through the GLV split and interleaved wNAF. Variable time, so only for public
+    // scalars like the ones in verification; the result matches k * P exactly
+    pub fn mul_vartime(&self, k: &Integer) -> S256Point {
+        if self.point.x == Infinity {
+            return self.clone();
+        }
+        let mut k = k.clone();
+        k.rem_euc_assign(&order());
+        let (k1, k2) = glv_split(&k);
+
+        let endo = endomorphism(&self.point);
+        let terms = [(&self.point, k1), (&endo, k2)];
+        S256Point {
+            point: mul_interleaved(&terms, GLV_WIDTH),
+        }
+    }
+
+    // u*G + v*P in a single doubling chain, see mul_add_g_with
+    #[cfg(test)]
+    fn mul_add_g(&self, u: &Integer, v: &Integer) -> S256Point {
+        mul_add_g_with(&odd_multiples(&self.point, GLV_WIDTH), u, v)
+    }
+}
+
+// u*G + v*P given the odd multiples of P: both scalars are GLV split, and the four halves are
+// summed by multi_mul with the cached tables for G. The lambda*P table is only beta times the
+// x coordinates, so it is cheap to make on every call. u and v must already be reduced mod n
+fn mul_add_g_with(p: &PointTable, u: &Integer, v: &Integer) -> S256Point {
+    let (u1, u2) = glv_split(u);
+    let (v1, v2) = glv_split(v);
+    let [g, g_endo] = g_wnaf_tables();
+    let p_endo = endomorphism_table(p);
+    let terms = [(g, u1), (g_endo, u2), (p, v1), (&p_endo, v2)];
+    S256Point {
+        point: multi_mul(&terms),
+    }
+}
+
+// Verifies every (z, signature, public key) independently, so one bad entry only fails itself.
+// With the parallel feature the work is spread over rayon's thread pool; either way the
+// results are in the same order as the input
+pub fn verify_batch(items: &[(Integer, Signature, S256Point)]) -> Vec<bool> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items
+            .par_iter()
+            .map(|(z, sig, point)| point.verify(z, sig))
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items
+            .iter()
+            .map(|(z, sig, point)| point.verify(z, sig))
+            .collect()
+    }
+}
+
+// Two signatures from the same key that reused a nonce share r, and then
+// k = (z1 - z2) / (s1 - s2) and e = (s*k - z) / r. Low-S normalization may have negated either
+// s, so s1 + s2 is tried as well and a candidate is only returned if it produced both
+// signatures. None when r differs, or when the signatures can't be separated (same s and z)
+pub fn recover_key_from_reused_nonce(
+    z1: &Integer,
+    sig1: &Signature,
+    z2: &Integer,
+    sig2: &Signature,
+) -> Option<PrivateKey> {
+    if sig1.r != sig2.r || sig1.r.is_zero() {
+        return None;
+    }
+    let (z1, z2) = (Scalar::new(z1.clone()), Scalar::new(z2.clone()));
+    let r_inv = sig1.r.invert()?;
+    for s2 in [sig2.s.clone(), -&sig2.s] {
+        let Some(ds_inv) = (&sig1.s - &s2).invert() else {
+            continue;
+        };
+        let k = &(&z1 - &z2) * &ds_inv;
+        let e = &(&(&sig1.s * &k) - &z1) * &r_inv;
+        if e.is_zero() {
+            continue;
+        }
+        let key = PrivateKey::new(e.as_integer().clone());
+        let point = key.public_key();
+        if point.verify(z1.as_integer(), sig1) && point.verify(z2.as_integer(), sig2) {
+            return Some(key);
+        }
+    }
+    None
+}
+
+// MuSig-style key aggregation: sum of a_i * P_i with a_i = hash_KeyAgg coefficient(L || P_i),
+// where L = hash_KeyAgg list of the sorted compressed keys. The coefficients stop a participant
+// from choosing their key as a function of the others' to cancel them out, which a plain sum of
+// keys allows. Keys are sorted first, so the input order doesn't matter
+pub fn aggregate_keys(keys: &[S256Point]) -> Result<S256Point, Secp256k1Error> {
+    if keys.is_empty() {
+        return Err(Secp256k1Error::EmptyKeyList);
+    }
+    if keys.iter().any(|key| key.point.x == Infinity) {
+        return Err(Secp256k1Error::SecPointAtInfinity);
+    }
+    let mut sorted: Vec<(Vec<u8>, &S256Point)> =
+        keys.iter().map(|key| (key.sec(true), key)).collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    if sorted.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return Err(Secp256k1Error::DuplicateKey);
+    }
+
+    let list: Vec<u8> = sorted.iter().flat_map(|(sec, _)| sec.clone()).collect();
+    let list_hash = tagged_hash("KeyAgg list", &list);
+    let terms: Vec<(&EcPoint, Integer)> = sorted
+        .iter()
+        .map(|(sec, key)| {
+            let a = tagged_hash("KeyAgg coefficient", &[&list_hash[..], sec].concat());
+            (
+                &key.point,
+                Scalar::from_bytes_be(&a, Overflow::Reduce)
+                    .expect("reducing never fails")
+                    .as_integer()
+                    .clone(),
+            )
+        })
+        .collect();
+
+    let point = S256Point {
+        point: mul_interleaved(&terms, GLV_WIDTH),
+    };
+    if point.point.x == Infinity {
+        return Err(Secp256k1Error::AggregateAtInfinity);
+    }
+    Ok(point)
+}
+
+impl Add for &S256Point {
+    type Output = S256Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        S256Point {
+            point: &self.point + &rhs.point,
+        }
+    }
+}
+
+// the group has order n, so the scalar can be reduced before multiplying
+impl Mul<Integer> for &S256Point {
+    type Output = S256Point;
+
+    fn mul(self, rhs: Integer) -> Self::Output {
+        let mut coef = rhs;
+        coef.rem_euc_assign(&order());
+        S256Point {
+            point: &self.point * coef,
+        }
+    }
+}
+
+impl Mul<&S256Point> for Integer {
+    type Output = S256Point;
+
+    fn mul(self, rhs: &S256Point) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl From<S256Point> for EcPoint {
+    fn from(point: S256Point) -> Self {
+        point.point
+    }
+}
+
+impl AsRef<EcPoint> for S256Point {
+    fn as_ref(&self) -> &EcPoint {
+        &self.point
+    }
+}
+
+// EcPoint's fields are public, so besides a and b the coordinates' field and the curve
+// equation are checked again rather than trusted
+impl TryFrom<EcPoint> for S256Point {
+    type Error = Secp256k1Error;
+
+    fn try_from(point: EcPoint) -> Result<Self, Self::Error> {
+        let g = S256Point::get_generator();
+        if point.a != g.point.a || point.b != g.point.b {
+            return Err(Secp256k1Error::WrongCurve);
+        }
+        match (&point.x, &point.y) {
+            (Finite(x), Finite(y)) => {
+                let p = prime();
+                if x.order != p || y.order != p {
+                    return Err(Secp256k1Error::WrongField);
+                }
+                if y.pow(2) != &x.pow(3) + &point.b {
+                    return Err(Secp256k1Error::NotOnCurve);
+                }
+            }
+            (Infinity, Infinity) => {}
+            _ => return Err(Secp256k1Error::NotOnCurve),
+        }
+        Ok(Self { point })
+    }
+}
+
+// SEC hex as it comes from RPCs and config files, optionally 0x prefixed. BadHex when the string
+// isn't hex, otherwise whatever S256Point::parse says about the bytes
+impl FromStr for S256Point {
+    type Err = Secp256k1Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        let bytes = hex::decode(digits)?;
+        S256Point::parse(&bytes, ParsePolicy::Strict)
+    }
+}
+
+// 32 bytes as exactly 64 hex digits
+fn decode_hex32(digits: &str) -> Result<[u8; 32], Secp256k1Error> {
+    hex::decode(digits)?
+        .try_into()
+        .map_err(|_| Secp256k1Error::BadHexLength)
+}
+
+// verify with everything as hex strings, the way JSON-RPC hands them over: a SEC public key
+// (optionally 0x prefixed), the 64 digit z and a DER signature. Ok(false) is a well-formed
+// signature that does not verify, errors say which argument could not be parsed
+pub fn verify_hex(pubkey_hex: &str, z_hex: &str, der_hex: &str) -> Result<bool, Secp256k1Error> {
+    let invalid = |argument| move |err| Secp256k1Error::InvalidArgument(argument, Box::new(err));
+    let point: S256Point = pubkey_hex
+        .parse()
+        .map_err(invalid(HexArgument::PublicKey))?;
+    let z = decode_hex32(z_hex).map_err(invalid(HexArgument::Hash))?;
+    let sig = hex::decode(der_hex)
+        .map_err(Secp256k1Error::from)
+        .and_then(|der| Signature::parse_der(&der))
+        .map_err(invalid(HexArgument::Signature))?;
+    Ok(point.verify(&Integer::from_digits(&z, Order::Msf), &sig))
+}
+
+// sign with a 64 digit private key and z, giving the DER signature as hex
+pub fn sign_hex(priv_hex: &str, z_hex: &str) -> Result<String, Secp256k1Error> {
+    let invalid = |argument| move |err| Secp256k1Error::InvalidArgument(argument, Box::new(err));
+    let key = decode_hex32(priv_hex)
+        .and_then(|bytes| PrivateKey::from_bytes(&bytes))
+        .map_err(invalid(HexArgument::PrivateKey))?;
+    let z = decode_hex32(z_hex).map_err(invalid(HexArgument::Hash))?;
+    let sig = key.sign(&Integer::from_digits(&z, Order::Msf));
+    Ok(hex::encode(&sig.der()))
+}
+
+// A public key: an S256Point that is known not to be Infinity. S256Point is for arithmetic,
+// this is what gets serialized, shown and fingerprinted. Converts to and from S256Point
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKey {
+    point: S256Point,
+}
+
+impl PublicKey {
+    pub fn try_new(point: S256Point) -> Result<Self, Secp256k1Error> {
+        if point.point.x == Infinity {
+            return Err(Secp256k1Error::SecPointAtInfinity);
+        }
+        Ok(Self { point })
+    }
+
+    pub fn point(&self) -> &S256Point {
+        &self.point
+    }
+
+    pub fn to_sec(&self, compressed: bool) -> Vec<u8> {
+        self.point.sec(compressed)
+    }
+
+    // strict SEC parsing, see S256Point::parse. The 0x00 encoding of Infinity is rejected there
+    pub fn from_sec(bytes: &[u8]) -> Result<Self, Secp256k1Error> {
+        Self::try_new(S256Point::from_sec(bytes)?)
+    }
+
+    // the first 4 bytes of hash160 of the compressed SEC, which BIP32 uses to name a parent key
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let mut result = [0u8; 4];
+        result.copy_from_slice(&hash160(&self.to_sec(true))[..4]);
+        result
+    }
+
+    pub fn verify(&self, z: &Integer, sig: &Signature) -> bool {
+        self.point.verify(z, sig)
+    }
+
+    // verify_message_hash256 for a stream, see hash_reader. Read errors are returned, not false
+    pub fn verify_reader(&self, reader: &mut impl Read, sig: &Signature) -> io::Result<bool> {
+        Ok(self.verify(&z_from_reader(reader)?, sig))
+    }
+
+    // P + t*G, the public side of PrivateKey::add_tweak. Fails if the sum is Infinity
+    pub fn add_tweak(&self, t: &Scalar) -> Result<PublicKey, Secp256k1Error> {
+        Self::try_new(&self.point + &g_mul(t.as_integer()))
+            .map_err(|_| Secp256k1Error::InvalidTweak)
+    }
+
+    // t*P, the public side of PrivateKey::mul_tweak. Fails if t is 0
+    pub fn mul_tweak(&self, t: &Scalar) -> Result<PublicKey, Secp256k1Error> {
+        if t.is_zero() {
+            return Err(Secp256k1Error::InvalidTweak);
+        }
+        Ok(Self {
+            point: &self.point * t.as_integer().clone(),
+        })
+    }
+}
+
+impl TryFrom<S256Point> for PublicKey {
+    type Error = Secp256k1Error;
+
+    fn try_from(point: S256Point) -> Result<Self, Self::Error> {
+        Self::try_new(point)
+    }
+}
+
+impl From<PublicKey> for S256Point {
+    fn from(key: PublicKey) -> Self {
+        key.point
+    }
+}
+
+// compressed SEC hex, which FromStr reads back
+impl Display for PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.point.to_sec_hex(true))
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = Secp256k1Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_new(s.parse()?)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct PrivateKey {
+    secret: Scalar,
+    point: S256Point,
+}
+
+// Overwrites the limbs holding a secret before clearing it. Importing zero bytes makes GMP write
+// a zero into every limb covering them, and at least 32 bytes covers any value below n
+fn wipe(value: &mut Integer) {
+    let len = value.significant_digits::<u8>().max(32);
+    value.assign_digits(&vec![0u8; len], Order::Msf);
+}
+
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
+impl Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PrivateKey(REDACTED)")
+    }
+}
+
+impl PrivateKey {
+    // panicking wrapper around try_new, for secrets known to be in range
+    pub fn new(secret: Integer) -> Self {
+        Self::try_new(secret)
+            .unwrap_or_else(|_| panic!("Error: secret out of range, cannot create PrivateKey"))
+    }
+
+    pub fn try_new(secret: Integer) -> Result<Self, Secp256k1Error> {
+        if secret <= 0 || secret >= order() {
+            return Err(Secp256k1Error::SecretOutOfRange);
+        }
+        let point = ct::g_mul_ct(&secret);
+        Ok(Self {
+            secret: Scalar::new(secret),
+            point,
+        })
+    }
+
+    // big-endian secret, rejected rather than reduced when it is 0 or >= n
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, Secp256k1Error> {
+        Self::try_new(Integer::from_digits(bytes, Order::Msf))
+    }
+
+    // rejection sampling keeps the key uniform in [1, n); reducing mod n would bias it
+    pub fn generate<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        loop {
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes);
+            if let Ok(key) = Self::from_bytes(&bytes) {
+                return key;
+            }
+        }
+    }
+
+    pub fn generate_os() -> Self {
+        Self::generate(&mut rand_core::OsRng)
+    }
+
+    // A brain wallet key, for showing why they are a bad idea: PBKDF2-HMAC-SHA256 of the
+    // passphrase, taking blocks T_1, T_2, ... until one is in [1, n) so the key is as uniform as
+    // generate's. Salt and iterations only slow each guess down, and a passphrase someone can
+    // remember is one an attacker can guess
+    pub fn from_passphrase(pass: &str, salt: &[u8], iterations: u32) -> PrivateKey {
+        (1..)
+            .find_map(|index| {
+                let mut block = pbkdf2_block(pass.as_bytes(), salt, iterations, index);
+                let key = Self::from_bytes(&block).ok();
+                block.zeroize();
+                key
+            })
+            .expect("some block is a valid key")
+    }
+
+    // INSECURE, for the demo only: the classic brain wallet, SHA256 of the passphrase used as the
+    // key with no salt or stretching. Keys made like this were swept by people hashing word lists,
+    // often within seconds of being funded. Panics if the hash is 0 or >= n (chance about 2^-128)
+    pub fn from_passphrase_insecure_sha256(pass: &str) -> PrivateKey {
+        Self::from_bytes(&sha256(pass.as_bytes()))
+            .expect("Error: SHA256 of the passphrase is not a valid key")
+    }
+
+    // the secret as 64 hex digits, for the rare case it really has to be shown
+    pub fn reveal_hex(&self) -> String {
+        format!("{:064x}", self.secret)
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            point: self.point.clone(),
+        }
+    }
+
+    // the key whose public point is P.even_y(), n - e when P has odd y
+    pub fn for_even_y(&self) -> PrivateKey {
+        let (point, negated) = self.point.even_y();
+        // CT-REVIEW: the parity of P is public, but negating only on one branch would still
+        // time differently, so both are computed
+        let secret = ct::select_scalar(&self.secret, &-&self.secret, Choice::from(negated as u8));
+        PrivateKey { secret, point }
+    }
+
+    // Wallet Import Format: Base58Check of prefix + secret, with a 0x01 suffix for compressed keys
+    pub fn wif(&self, compressed: bool, network: Network) -> String {
+        let mut payload = vec![network.params().wif_prefix];
+        payload.extend_from_slice(&self.secret.to_bytes32());
+        if compressed {
+            payload.push(0x01);
+        }
+        base58::encode_check(&payload)
+    }
+
+    // returns the key along with whether it is compressed and the network its prefix belongs to,
+    // Testnet for the prefix Testnet, Regtest and Signet share
+    pub fn from_wif(s: &str) -> Result<(PrivateKey, bool, Network), Secp256k1Error> {
+        let (payload, compressed) = Self::wif_payload(s)?;
+        let network = Network::from_wif_prefix(payload[0]).ok_or(Secp256k1Error::InvalidWif)?;
+        Ok((Self::from_wif_secret(&payload)?, compressed, network))
+    }
+
+    // from_wif for a WIF that must belong to network, which may be a custom one
+    pub fn from_wif_on(s: &str, network: Network) -> Result<(PrivateKey, bool), Secp256k1Error> {
+        let (payload, compressed) = Self::wif_payload(s)?;
+        if payload[0] != network.params().wif_prefix {
+            return Err(Secp256k1Error::WrongNetwork);
+        }
+        Ok((Self::from_wif_secret(&payload)?, compressed))
+    }
+
+    // the checked payload, 33 or 34 bytes, and whether it has the compressed suffix
+    fn wif_payload(s: &str) -> Result<(Vec<u8>, bool), Secp256k1Error> {
+        let payload = base58::decode_check(s)?;
+        let compressed = match payload.len() {
+            33 => false,
+            34 if payload[33] == 0x01 => true,
+            _ => return Err(Secp256k1Error::InvalidWif),
+        };
+        Ok((payload, compressed))
+    }
+
+    fn from_wif_secret(payload: &[u8]) -> Result<PrivateKey, Secp256k1Error> {
+        PrivateKey::try_new(Integer::from_digits(&payload[1..33], Order::Msf))
+    }
+
+    // s = (z + r*e) / k with k from RFC 6979, always returned in low-S form. Runs on the
+    // hardened path in ct.rs, see there for what is and isn't constant-time
+    pub fn sign(&self, z: &Integer) -> Signature {
+        self.sign_recoverable(z).sig
+    }
+
+    // sign with z = z_from_message(msg)
+    pub fn sign_message_hash256(&self, msg: &[u8]) -> Signature {
+        self.sign(&z_from_message(msg))
+    }
+
+    // sign_message_hash256 for a stream, see hash_reader
+    pub fn sign_reader(&self, reader: &mut impl Read) -> io::Result<Signature> {
+        Ok(self.sign(&z_from_reader(reader)?))
+    }
+
+    // sign with options, see SignOptions. Defaults give exactly the same signature as sign
+    pub fn sign_with(&self, z: &Integer, options: &SignOptions) -> Signature {
+        self.sign_recoverable_with(z, options).sig
+    }
+
+    pub fn sign_recoverable(&self, z: &Integer) -> RecoverableSignature {
+        self.sign_recoverable_with(z, &SignOptions::default())
+    }
+
+    // recovery id: bit 0 is the parity of R.y, bit 1 is set when R.x overflowed n.
+    // With grind_low_r, a signature whose r has the top bit set is thrown away and signed again
+    // with the counter 1, 2, ... written little endian into the first 4 bytes of 32 bytes of
+    // extra data, as Bitcoin Core does. That saves the DER padding byte on r, so with low S the
+    // signature is at most 70 bytes, after two tries on average. aux_rand, if any, is XORed with the
+    // counter bytes, so it is still mixed in
+    pub fn sign_recoverable_with(
+        &self,
+        z: &Integer,
+        options: &SignOptions,
+    ) -> RecoverableSignature {
+        let k = self.deterministic_k(z, options.aux_rand.as_ref());
+        let mut result = self.sign_with_nonce(z, k);
+        let mut counter = 0u32;
+        while options.grind_low_r && !result.sig.is_low_r() {
+            counter += 1;
+            let mut extra = options.aux_rand.unwrap_or([0u8; 32]);
+            for (byte, c) in extra.iter_mut().zip(counter.to_le_bytes()) {
+                *byte ^= c;
+            }
+            let k = self.deterministic_k(z, Some(&extra));
+            result = self.sign_with_nonce(z, k);
+        }
+        result
+    }
+
+    // DANGER: signs with a nonce the caller picked. Two signatures with the same k give away the
+    // private key (recover_key_from_reused_nonce does it), and so does a k that can be guessed
+    // or is even slightly biased. Only for reproducing textbook examples and attack demos, so it
+    // is only there with the dangerous-test-apis feature. s comes out low as with sign
+    #[cfg(any(test, feature = "dangerous-test-apis"))]
+    pub fn sign_with_k(&self, z: &Integer, k: &Integer) -> Result<Signature, Secp256k1Error> {
+        if *k < 1 || *k >= order() {
+            return Err(Secp256k1Error::NonceOutOfRange);
+        }
+        Ok(self.sign_with_nonce(z, Scalar::new(k.clone())).sig)
+    }
+
+    // the signing equation for a given nonce, which is wiped afterwards. k must be in [1, n)
+    fn sign_with_nonce(&self, z: &Integer, k: Scalar) -> RecoverableSignature {
+        let n = order();
+        let mut k = k;
+        let big_r = ct::g_mul_ct(k.as_integer());
+        let (r, y_odd) = match (big_r.point.x, big_r.point.y) {
+            (Finite(x), Finite(y)) => (x.value, y.value.is_odd()),
+            _ => unreachable!("k is in [1, n), so k*G is never infinity"),
+        };
+        let mut recid = u8::from(y_odd);
+        if r >= n {
+            recid |= 2;
+        }
+        let r = Scalar::new(r);
+
+        let mut k_inv = k.invert().expect("k is in [1, n)");
+        let s = &(&Scalar::new(z.clone()) + &(&r * &self.secret)) * &k_inv;
+        k.zeroize();
+        k_inv.zeroize();
+
+        // negating s is the same as signing with -k, which flips the parity of R
+        let (s, flipped) = ct::low_s(s.as_integer());
+        recid ^= u8::from(flipped);
+        RecoverableSignature {
+            sig: Signature::new(r, Scalar::new(s)),
+            recid,
+        }
+    }
+
+    // libsecp256k1's default ECDH: SHA256 of the compressed shared point
+    pub fn ecdh(&self, their_pub: &S256Point) -> [u8; 32] {
+        sha256(&self.shared_point(their_pub).sec(true))
+    }
+
+    // raw x coordinate of the shared point, for protocols that do their own hashing
+    pub fn ecdh_xonly(&self, their_pub: &S256Point) -> [u8; 32] {
+        self.shared_point(their_pub).xonly()
+    }
+
+    fn shared_point(&self, their_pub: &S256Point) -> S256Point {
+        assert!(
+            their_pub.point.x != Infinity,
+            "Error: cannot compute ECDH with the point at infinity"
+        );
+        // S256Point can only be built on the curve, and n is prime, so a finite key times
+        // a secret in [1, n) is never infinity. The secret is long-term, so the multiplication
+        // is the constant-time one
+        ct::mul_ct(their_pub, self.secret.as_integer())
+    }
+
+    // secret + t mod n, with PublicKey::add_tweak giving the matching public key. Fails if the
+    // sum is 0, which happens for t = -secret
+    pub fn add_tweak(&self, t: &Scalar) -> Result<PrivateKey, Secp256k1Error> {
+        let tweaked = &self.secret + t;
+        if tweaked.is_zero() {
+            return Err(Secp256k1Error::InvalidTweak);
+        }
+        Ok(PrivateKey::new(tweaked.as_integer().clone()))
+    }
+
+    // secret * t mod n, matching PublicKey::mul_tweak. n is prime, so only t = 0 fails
+    pub fn mul_tweak(&self, t: &Scalar) -> Result<PrivateKey, Secp256k1Error> {
+        if t.is_zero() {
+            return Err(Secp256k1Error::InvalidTweak);
+        }
+        Ok(PrivateKey::new((&self.secret * t).as_integer().clone()))
+    }
+
+    // secret for the BIP341 output key, negated first if the internal key has odd y
+    pub fn tap_tweak_seckey(
+        &self,
+        merkle_root: Option<&[u8; 32]>,
+    ) -> Result<PrivateKey, Secp256k1Error> {
+        let d = self.for_even_y();
+        let t = Scalar::new(tap_tweak_scalar(&self.point.xonly(), merkle_root)?);
+        let tweaked = &d.secret + &t;
+        if tweaked.is_zero() {
+            return Err(Secp256k1Error::InvalidTweak);
+        }
+        Ok(PrivateKey::new(tweaked.as_integer().clone()))
+    }
+
+    // RFC 6979 nonce generation with HMAC-SHA256. aux_rand goes in as the additional data of
+    // section 3.6, the same place libsecp256k1 puts its noncedata
+    fn deterministic_k(&self, z: &Integer, aux_rand: Option<&[u8; 32]>) -> Scalar {
+        let z_bytes = Scalar::new(z.clone()).to_bytes32();
+        let mut secret_bytes = self.secret.to_bytes32();
+        let extra: &[u8] = aux_rand.map_or(&[], |aux| aux);
+
+        let mut k = [0u8; 32];
+        let mut v = [1u8; 32];
+        k = hmac_sha256(&k, &[&v, &[0x00], &secret_bytes, &z_bytes, extra]);
+        v = hmac_sha256(&k, &[&v]);
+        k = hmac_sha256(&k, &[&v, &[0x01], &secret_bytes, &z_bytes, extra]);
+        v = hmac_sha256(&k, &[&v]);
+        loop {
+            v = hmac_sha256(&k, &[&v]);
+            if let Ok(candidate) = Scalar::from_bytes_be(&v, Overflow::Reject)
+                && !candidate.is_zero()
+            {
+                // the HMAC state determines k, so it is as sensitive as the key
+                secret_bytes.zeroize();
+                k.zeroize();
+                v.zeroize();
+                return candidate;
+            }
+            k = hmac_sha256(&k, &[&v, &[0x00]]);
+            v = hmac_sha256(&k, &[&v]);
+        }
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&mac.finalize().into_bytes());
+    result
+}
+
+// PBKDF2-HMAC-SHA256 (RFC 8018) block T_index = U_1 ^ ... ^ U_iterations, where
+// U_1 = HMAC(password, salt || index) and U_j = HMAC(password, U_(j-1))
+fn pbkdf2_block(password: &[u8], salt: &[u8], iterations: u32, index: u32) -> [u8; 32] {
+    assert!(iterations > 0, "Error: PBKDF2 needs at least one iteration");
+    // keying HMAC hashes the password, so that is done once and the keyed state cloned
+    let keyed = Hmac::<Sha256>::new_from_slice(password).expect("HMAC accepts keys of any length");
+    let mut u = hmac_sha256(password, &[salt, &index.to_be_bytes()]);
+    let mut t = u;
+    for _ in 1..iterations {
+        let mut mac = keyed.clone();
+        mac.update(&u);
+        u.copy_from_slice(&mac.finalize().into_bytes());
+        for (t, u) in t.iter_mut().zip(u) {
+            *t ^= u;
+        }
+    }
+    u.zeroize();
+    t
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Signature {
+    pub r: Scalar,
+    pub s: Scalar,
+}
+
+// r and s are public, so unlike Scalar's own Debug this shows them
+impl Debug for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signature")
+            .field("r", &format_args!("{:x}", self.r))
+            .field("s", &format_args!("{:x}", self.s))
+            .finish()
+    }
+}
+
+impl Signature {
+    pub fn new(r: Scalar, s: Scalar) -> Self {
+        Self { r, s }
+    }
+
+    pub fn is_low_s(&self) -> bool {
+        !self.s.is_high()
+    }
+
+    // r below 2^255, so it needs no 0x00 padding byte in DER
+    pub fn is_low_r(&self) -> bool {
+        self.r.as_integer().significant_bits() < 256
+    }
+
+    // (r, s) and (r, n - s) are both valid, Bitcoin only relays the one with s <= n/2
+    pub fn normalize_s(&self) -> Self {
+        if self.is_low_s() {
+            self.clone()
+        } else {
+            Self {
+                r: self.r.clone(),
+                s: -&self.s,
+            }
+        }
+    }
+
+    // fixed 64-byte r || s, each half 32 bytes big-endian
+    pub fn to_compact(&self) -> [u8; 64] {
+        let mut result = [0u8; 64];
+        result[..32].copy_from_slice(&self.r.to_bytes32());
+        result[32..].copy_from_slice(&self.s.to_bytes32());
+        result
+    }
+
+    pub fn from_compact(bytes: &[u8; 64]) -> Result<Self, Secp256k1Error> {
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..]);
+        match (
+            Scalar::from_bytes_be(&r, Overflow::Reject),
+            Scalar::from_bytes_be(&s, Overflow::Reject),
+        ) {
+            (Ok(r), Ok(s)) => Ok(Self { r, s }),
+            _ => Err(Secp256k1Error::SignatureOutOfRange),
+        }
+    }
+
+    // P = (s*R - z*G) / r, where R is lifted from r using the recovery id
+    pub fn recover(&self, z: &Integer, recid: u8) -> Result<S256Point, Secp256k1Error> {
+        let n = order();
+        if recid > 3 {
+            return Err(Secp256k1Error::InvalidRecoveryId);
+        }
+        if self.r.is_zero() || self.s.is_zero() {
+            return Err(Secp256k1Error::SignatureOutOfRange);
+        }
+
+        // R.x is r, or r + n in the rare case k*G had x >= n
+        let mut x = self.r.as_integer().clone();
+        if recid & 2 != 0 {
+            x += &n;
+        }
+        let mut big_r = S256Point::lift_x(x).map_err(|_| Secp256k1Error::RecoveryFailed)?;
+        if (recid & 1 == 1) == big_r.has_even_y() {
+            big_r = S256Point {
+                point: -&big_r.point,
+            };
+        }
+
+        let r_inv = self.r.invert().expect("r is nonzero");
+        let u = -&(&Scalar::new(z.clone()) * &r_inv);
+        let v = &self.s * &r_inv;
+
+        let g = S256Point::get_generator();
+        let point = &(&g * u.as_integer().clone()) + &(&big_r * v.as_integer().clone());
+        if point.point.x == Infinity || !point.verify(z, self) {
+            return Err(Secp256k1Error::RecoveryFailed);
+        }
+        Ok(point)
+    }
+
+    // SEQUENCE { INTEGER r, INTEGER s }, both integers minimally encoded
+    pub fn der(&self) -> Vec<u8> {
+        let mut body = der_integer(self.r.as_integer());
+        body.extend(der_integer(self.s.as_integer()));
+
+        let mut result = vec![0x30, body.len() as u8];
+        result.extend(body);
+        result
+    }
+
+    // strict (BIP66) DER: short-form lengths only, minimal non-negative integers, nothing left over
+    pub fn parse_der(bytes: &[u8]) -> Result<Self, Secp256k1Error> {
+        let (&tag, rest) = bytes.split_first().ok_or(Secp256k1Error::DerTruncated)?;
+        if tag != 0x30 {
+            return Err(Secp256k1Error::DerInvalidTag);
+        }
+        let (len, rest) = der_length(rest)?;
+        if rest.len() < len {
+            return Err(Secp256k1Error::DerTruncated);
+        }
+        if rest.len() > len {
+            return Err(Secp256k1Error::DerTrailingBytes);
+        }
+
+        let (r, rest) = parse_der_integer(rest)?;
+        let (s, rest) = parse_der_integer(rest)?;
+        if !rest.is_empty() {
+            return Err(Secp256k1Error::DerTrailingBytes);
+        }
+        // Scalar would silently reduce r and s, so anything >= n is rejected up front
+        let n = order();
+        if r >= n || s >= n {
+            return Err(Secp256k1Error::SignatureOutOfRange);
+        }
+        Ok(Self {
+            r: Scalar::new(r),
+            s: Scalar::new(s),
+        })
+    }
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Signature({:x}, {:x})", self.r, self.s)
+    }
+}
+
+// Extra inputs to ECDSA signing. With aux_rand set, fresh randomness is mixed into the RFC 6979
+// nonce ("synthetic nonces"), so a fault or glitch while signing the same message twice doesn't
+// hand out two signatures with the same k. The nonce stays safe even if aux_rand is bad.
+// grind_low_r retries like Bitcoin Core until r is below 2^255, see sign_recoverable_with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignOptions {
+    pub aux_rand: Option<[u8; 32]>,
+    pub grind_low_r: bool,
+}
+
+// What the recovery id byte of a 65-byte recoverable signature holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecoveryByte {
+    // the recovery id itself, 0..=3 (Ethereum's yParity)
+    #[default]
+    Raw,
+    // 27 + recovery id, the legacy v from Bitcoin's signmessage and pre-EIP-155 Ethereum
+    Legacy,
+}
+
+// A signature plus the recovery id needed to get the public key back from it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoverableSignature {
+    pub sig: Signature,
+    pub recid: u8,
+}
+
+impl RecoverableSignature {
+    // r || s || v, 65 bytes
+    pub fn to_bytes(&self, format: RecoveryByte) -> [u8; 65] {
+        let mut result = [0u8; 65];
+        result[..64].copy_from_slice(&self.sig.to_compact());
+        result[64] = match format {
+            RecoveryByte::Raw => self.recid,
+            RecoveryByte::Legacy => 27 + self.recid,
+        };
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8; 65], format: RecoveryByte) -> Result<Self, Secp256k1Error> {
+        let recid = match format {
+            RecoveryByte::Raw => bytes[64],
+            RecoveryByte::Legacy => bytes[64].wrapping_sub(27),
+        };
+        if recid > 3 {
+            return Err(Secp256k1Error::InvalidRecoveryId);
+        }
+        let mut compact = [0u8; 64];
+        compact.copy_from_slice(&bytes[..64]);
+        Ok(Self {
+            sig: Signature::from_compact(&compact)?,
+            recid,
+        })
+    }
+
+    pub fn recover(&self, z: &Integer) -> Result<S256Point, Secp256k1Error> {
+        self.sig.recover(z, self.recid)
+    }
+}
+
+fn der_integer(value: &Integer) -> Vec<u8> {
+    // to_digits drops leading zeros; a set high bit would read as negative, so pad it
+    let mut bytes = value.to_digits::<u8>(Order::Msf);
+    if bytes.is_empty() || bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+
+    let mut result = vec![0x02, bytes.len() as u8];
+    result.extend(bytes);
+    result
+}
+
+fn der_length(bytes: &[u8]) -> Result<(usize, &[u8]), Secp256k1Error> {
+    let (&len, rest) = bytes.split_first().ok_or(Secp256k1Error::DerTruncated)?;
+    if len & 0x80 != 0 {
+        return Err(Secp256k1Error::DerInvalidLength);
+    }
+    Ok((len as usize, rest))
+}
+
+fn parse_der_integer(bytes: &[u8]) -> Result<(Integer, &[u8]), Secp256k1Error> {
+    let (&tag, rest) = bytes.split_first().ok_or(Secp256k1Error::DerTruncated)?;
+    if tag != 0x02 {
+        return Err(Secp256k1Error::DerInvalidTag);
+    }
+    let (len, rest) = der_length(rest)?;
+    if len == 0 {
+        return Err(Secp256k1Error::DerZeroLengthInteger);
+    }
+    if rest.len() < len {
+        return Err(Secp256k1Error::DerTruncated);
+    }
+
+    let (value, rest) = rest.split_at(len);
+    if value[0] & 0x80 != 0 {
+        return Err(Secp256k1Error::DerNegativeInteger);
+    }
+    if len > 1 && value[0] == 0 && value[1] & 0x80 == 0 {
+        return Err(Secp256k1Error::DerNonMinimalInteger);
+    }
+    if len > 33 {
+        return Err(Secp256k1Error::DerIntegerTooLong);
+    }
+    Ok((Integer::from_digits(value, Order::Msf), rest))
+}
+
+// Bitcoin Core's ecdsa_signature_parse_der_lax: any length form, lengths of the outer SEQUENCE
+// ignored, integers read as unsigned with leading zeros skipped, and anything after s ignored.
+// Only a missing tag or a length past the end fails
+fn parse_der_lax(bytes: &[u8]) -> Result<(Integer, Integer), Secp256k1Error> {
+    let rest = match bytes {
+        [0x30, rest @ ..] => rest,
+        [] => return Err(Secp256k1Error::DerTruncated),
+        _ => return Err(Secp256k1Error::DerInvalidTag),
+    };
+    let (_, rest) = der_length_lax(rest)?;
+    let (r, rest) = parse_der_integer_lax(rest)?;
+    let (s, _) = parse_der_integer_lax(rest)?;
+    Ok((r, s))
+}
+
+fn der_length_lax(bytes: &[u8]) -> Result<(usize, &[u8]), Secp256k1Error> {
+    let (&len, rest) = bytes.split_first().ok_or(Secp256k1Error::DerTruncated)?;
+    if len & 0x80 == 0 {
+        return Ok((len as usize, rest));
+    }
+    let count = (len & 0x7f) as usize;
+    if count > rest.len() {
+        return Err(Secp256k1Error::DerTruncated);
+    }
+    let (digits, rest) = rest.split_at(count);
+    let digits = &digits[digits.iter().take_while(|&&b| b == 0).count()..];
+    if digits.len() >= size_of::<usize>() {
+        return Err(Secp256k1Error::DerInvalidLength);
+    }
+    let len = digits.iter().fold(0, |acc, &b| (acc << 8) | b as usize);
+    Ok((len, rest))
+}
+
+fn parse_der_integer_lax(bytes: &[u8]) -> Result<(Integer, &[u8]), Secp256k1Error> {
+    let rest = match bytes {
+        [0x02, rest @ ..] => rest,
+        [] => return Err(Secp256k1Error::DerTruncated),
+        _ => return Err(Secp256k1Error::DerInvalidTag),
+    };
+    let (len, rest) = der_length_lax(rest)?;
+    if len > rest.len() {
+        return Err(Secp256k1Error::DerTruncated);
+    }
+    let (value, rest) = rest.split_at(len);
+    Ok((Integer::from_digits(value, Order::Msf), rest))
+}
+
+fn to_bytes32(value: &Integer) -> [u8; 32] {
+    let digits = value.to_digits::<u8>(Order::Msf);
+    let mut bytes = [0u8; 32];
+    bytes[32 - digits.len()..].copy_from_slice(&digits);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rug::rand::RandState;
+
+    #[test]
+    fn test_generator() {
+        let g = S256Point::get_generator();
+        let n: Integer = Integer::from_str_radix(N_STR, 16).unwrap();
+
+        assert_eq!(
+            n.clone() * &g.point,
+            EcPoint::new(Infinity, Infinity, g.point.a.clone(), g.point.b.clone())
+        );
+        assert_eq!(g.mul_vartime(&n), S256Point::new(Infinity, Infinity));
+    }
+
+    fn bytes32(hex: &str) -> [u8; 32] {
+        to_bytes32(&Integer::from_str_radix(hex, 16).unwrap())
+    }
+
+    #[test]
+    fn test_private_key_try_new() {
+        assert!(PrivateKey::try_new(Integer::from(1)).is_ok());
+        assert!(PrivateKey::try_new(order() - 1u8).is_ok());
+        for secret in [Integer::new(), Integer::from(-1), order()] {
+            assert_eq!(
+                PrivateKey::try_new(secret).err(),
+                Some(Secp256k1Error::SecretOutOfRange)
+            );
+        }
+    }
+
+    #[test]
+    fn test_even_y_normalization() {
+        let n = order();
+        let mut rand = RandState::new();
+        rand.seed(&Integer::from(146));
+
+        let mut negations = 0;
+        for _ in 0..16 {
+            let secret = Integer::from(1) + (&n - 1u8).complete().random_below(&mut rand);
+            let key = PrivateKey::new(secret.clone());
+            let p = S256Point::from(key.public_key());
+
+            let (even, negated) = p.even_y();
+            assert!(even.has_even_y());
+            assert_eq!(negated, !p.has_even_y());
+            assert_eq!(even.xonly(), p.xonly());
+            if negated {
+                assert_eq!(&even + &p, S256Point::new(Infinity, Infinity));
+            } else {
+                assert_eq!(even, p);
+            }
+
+            let normalized = key.for_even_y();
+            assert_eq!(S256Point::from(normalized.public_key()), even);
+            assert_eq!(g_mul(normalized.secret.as_integer()), even);
+            let expected = if negated {
+                (&n - &secret).complete()
+            } else {
+                secret
+            };
+            assert_eq!(normalized.secret.as_integer(), &expected);
+            negations += usize::from(negated);
+        }
+        // both branches get exercised with this seed
+        assert!(negations > 0 && negations < 16);
+
+        let g = S256Point::get_generator();
+        assert_eq!(g.negate().negate(), g);
+        let infinity = S256Point::new(Infinity, Infinity);
+        assert_eq!(infinity.negate(), infinity);
+        assert_eq!(infinity.even_y(), (infinity, false));
+    }
+
+    #[test]
+    fn test_xonly_bip340_pubkeys() {
+        // (secret key, x-only public key) from the BIP340 test vectors
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            ),
+            (
+                "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+                "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            ),
+            (
+                "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+                "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            ),
+            (
+                "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+                "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            ),
+        ];
+        let g = S256Point::get_generator();
+        for (secret, pubkey) in vectors {
+            let secret = Integer::from_str_radix(secret, 16).unwrap();
+            let p = S256Point {
+                point: secret * &g.point,
+            };
+            assert_eq!(p.xonly(), bytes32(pubkey));
+
+            let lifted = S256Point::from_xonly(&bytes32(pubkey)).unwrap();
+            assert!(lifted.has_even_y());
+            if p.has_even_y() {
+                assert_eq!(lifted, p);
+            } else {
+                assert_eq!(lifted.point, -&p.point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_xonly_generator_round_trip() {
+        let g = S256Point::get_generator();
+        assert!(g.has_even_y());
+        assert_eq!(g.xonly(), bytes32(GX_STR));
+        assert_eq!(S256Point::from_xonly(&g.xonly()).unwrap(), g);
+    }
+
+    fn hex(value: &str) -> Integer {
+        Integer::from_str_radix(value, 16).unwrap()
+    }
+
+    fn chapter3_point() -> S256Point {
+        S256Point::new(
+            Finite(S256Field::new(
+                "887387e452b8eacc4acfde10d9aaf7f6d9a0f975aabb10d006e4da568744d06c",
+            )),
+            Finite(S256Field::new(
+                "61de6d95231cd89026e286df3b6ae4a894a3378e393e93a0f45b666329a0ae34",
+            )),
+        )
+    }
+
+    fn scalar(value: &str) -> Scalar {
+        Scalar::new(hex(value))
+    }
+
+    // (z, r, s) signed by chapter3_point(), from Programming Bitcoin chapter 3
+    fn chapter3_vectors() -> [(Integer, Signature); 2] {
+        [
+            (
+                hex("ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60"),
+                Signature::new(
+                    scalar("ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395"),
+                    scalar("68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4"),
+                ),
+            ),
+            (
+                hex("7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d"),
+                Signature::new(
+                    scalar("eff69ef2b1bd93a66ed5219add4fb51e11a840f404876325a1e8ffe0529a2c"),
+                    scalar("c7207fee197d27c618aea621406f6bf5ef6fca38681d82b2f06fddbdce6feab6"),
+                ),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_verify() {
+        let p = chapter3_point();
+        for (z, sig) in chapter3_vectors() {
+            assert!(p.verify(&z, &sig));
+        }
+    }
+
+    #[test]
+    fn test_public_key() {
+        // BIP32 test vector 1: the master key, whose fingerprint its children name as parent
+        let sec = "0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2";
+        let key: PublicKey = sec.parse().unwrap();
+        assert_eq!(key.fingerprint(), [0x34, 0x42, 0x19, 0x3e]);
+        assert_eq!(key.to_string(), sec);
+        assert_eq!(PublicKey::from_sec(&key.to_sec(false)), Ok(key.clone()));
+
+        let private = test_key(201);
+        let point = S256Point::from(private.public_key());
+        assert_eq!(PublicKey::try_from(point.clone()), Ok(private.public_key()));
+        assert_eq!(
+            S256Point::from(PublicKey::try_new(point.clone()).unwrap()),
+            point
+        );
+        assert_eq!(private.public_key().point(), &point);
+        assert_eq!(
+            private.public_key().to_string().parse(),
+            Ok(private.public_key())
+        );
+        assert_eq!(
+            PublicKey::from_sec(&private.public_key().to_sec(true)),
+            Ok(private.public_key())
+        );
+    }
+
+    #[test]
+    fn test_tweaks() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(202);
+        for _ in 0..8 {
+            let key = PrivateKey::generate(&mut rng);
+            let t = Scalar::random(&mut rng);
+            assert_eq!(
+                key.add_tweak(&t).unwrap().public_key(),
+                key.public_key().add_tweak(&t).unwrap()
+            );
+            assert_eq!(
+                key.mul_tweak(&t).unwrap().public_key(),
+                key.public_key().mul_tweak(&t).unwrap()
+            );
+        }
+
+        let key = test_key(202);
+        assert_eq!(key.add_tweak(&Scalar::zero()), Ok(key.clone()));
+        assert_eq!(key.mul_tweak(&Scalar::one()), Ok(key.clone()));
+        assert_eq!(
+            key.public_key().add_tweak(&Scalar::zero()),
+            Ok(key.public_key())
+        );
+
+        // t = -secret cancels the key on both sides
+        let cancel = -&key.secret;
+        assert_eq!(key.add_tweak(&cancel), Err(Secp256k1Error::InvalidTweak));
+        assert_eq!(
+            key.public_key().add_tweak(&cancel),
+            Err(Secp256k1Error::InvalidTweak)
+        );
+        assert_eq!(
+            key.mul_tweak(&Scalar::zero()),
+            Err(Secp256k1Error::InvalidTweak)
+        );
+        assert_eq!(
+            key.public_key().mul_tweak(&Scalar::zero()),
+            Err(Secp256k1Error::InvalidTweak)
+        );
+    }
+
+    #[test]
+    fn test_public_key_rejects_infinity() {
+        assert_eq!(
+            PublicKey::try_new(S256Point::new(Infinity, Infinity)),
+            Err(Secp256k1Error::SecPointAtInfinity)
+        );
+        assert_eq!(
+            PublicKey::from_sec(&[0x00]),
+            Err(Secp256k1Error::SecPointAtInfinity)
+        );
+        assert_eq!(
+            "00".parse::<PublicKey>(),
+            Err(Secp256k1Error::SecPointAtInfinity)
+        );
+    }
+
+    #[test]
+    fn test_verify_with_table() {
+        let p = chapter3_point();
+        let table = p.precompute();
+        for (z, sig) in chapter3_vectors() {
+            assert!(p.verify_with_table(&z, &sig, &table));
+            let z2 = (&z + 1u8).complete();
+            assert!(!p.verify_with_table(&z2, &sig, &table));
+        }
+
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(190);
+        let key = PrivateKey::generate(&mut rng);
+        let point = S256Point::from(key.public_key());
+        let table = point.precompute();
+        for i in 0..8u8 {
+            let z = Integer::from(i) << 200u32;
+            let sig = key.sign(&z);
+            assert!(point.verify_with_table(&z, &sig, &table));
+            assert_eq!(
+                point.verify_with_table(&(z.clone() + 1u8), &sig, &table),
+                point.verify(&(z + 1u8), &sig)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Error: wNAF table was built for a different public key")]
+    fn test_verify_with_table_for_other_key() {
+        let (z, sig) = chapter3_vectors()[0].clone();
+        let table = S256Point::get_generator().precompute();
+        chapter3_point().verify_with_table(&z, &sig, &table);
+    }
+
+    #[test]
+    fn test_verify_hex() {
+        let pubkey = chapter3_point().to_sec_hex(false);
+        for (z, sig) in chapter3_vectors() {
+            let z_hex = format!("{z:064x}");
+            let der_hex = hex::encode(&sig.der());
+            assert_eq!(verify_hex(&pubkey, &z_hex, &der_hex), Ok(true));
+            assert_eq!(
+                verify_hex(&chapter3_point().to_sec_hex(true), &z_hex, &der_hex),
+                Ok(true)
+            );
+            let other = format!("{:064x}", z + 1u8);
+            assert_eq!(verify_hex(&pubkey, &other, &der_hex), Ok(false));
+        }
+
+        // the book's chapter 3 key and first signature, written out
+        assert_eq!(
+            verify_hex(
+                "04887387e452b8eacc4acfde10d9aaf7f6d9a0f975aabb10d006e4da568744d06c\
+                 61de6d95231cd89026e286df3b6ae4a894a3378e393e93a0f45b666329a0ae34",
+                "ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60",
+                "3045022100ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395\
+                 0220068342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4"
+            ),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_sign_hex() {
+        let key = PrivateKey::new(Integer::from(12345));
+        let z_hex = format!("{:064x}", z_from_message(b"Programming Bitcoin!"));
+        let der_hex = sign_hex(&format!("{:064x}", 12345), &z_hex).unwrap();
+        assert_eq!(
+            der_hex,
+            hex::encode(&key.sign_message_hash256(b"Programming Bitcoin!").der())
+        );
+        assert_eq!(
+            verify_hex(&key.public_key().to_string(), &z_hex, &der_hex),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_hex_entry_points_name_the_bad_argument() {
+        fn invalid<T>(argument: HexArgument, err: Secp256k1Error) -> Result<T, Secp256k1Error> {
+            Err(Secp256k1Error::InvalidArgument(argument, Box::new(err)))
+        }
+        let pubkey = chapter3_point().to_sec_hex(true);
+        let (z, sig) = chapter3_vectors()[0].clone();
+        let z_hex = format!("{z:064x}");
+        let der_hex = hex::encode(&sig.der());
+
+        assert_eq!(
+            verify_hex("02zz", &z_hex, &der_hex),
+            invalid(HexArgument::PublicKey, Secp256k1Error::BadHex)
+        );
+        assert_eq!(
+            verify_hex(&pubkey[..64], &z_hex, &der_hex),
+            invalid(HexArgument::PublicKey, Secp256k1Error::InvalidSecLength)
+        );
+        assert_eq!(
+            verify_hex(&pubkey, &z_hex[2..], &der_hex),
+            invalid(HexArgument::Hash, Secp256k1Error::BadHexLength)
+        );
+        assert_eq!(
+            verify_hex(&pubkey, &format!("{z_hex}00"), &der_hex),
+            invalid(HexArgument::Hash, Secp256k1Error::BadHexLength)
+        );
+        assert_eq!(
+            verify_hex(&pubkey, &z_hex.replace('e', "g"), &der_hex),
+            invalid(HexArgument::Hash, Secp256k1Error::BadHex)
+        );
+        assert_eq!(
+            verify_hex(&pubkey, &z_hex, "304"),
+            invalid(HexArgument::Signature, Secp256k1Error::BadHex)
+        );
+        assert_eq!(
+            verify_hex(&pubkey, &z_hex, &der_hex[..der_hex.len() - 2]),
+            invalid(HexArgument::Signature, Secp256k1Error::DerTruncated)
+        );
+
+        assert_eq!(
+            sign_hex("abcd", &z_hex),
+            invalid(HexArgument::PrivateKey, Secp256k1Error::BadHexLength)
+        );
+        assert_eq!(
+            sign_hex(&"0".repeat(64), &z_hex),
+            invalid(HexArgument::PrivateKey, Secp256k1Error::SecretOutOfRange)
+        );
+        assert_eq!(
+            sign_hex(&"0".repeat(63), &z_hex),
+            invalid(HexArgument::PrivateKey, Secp256k1Error::BadHex)
+        );
+        assert_eq!(
+            sign_hex(&format!("{:064x}", 1), "0x"),
+            invalid(HexArgument::Hash, Secp256k1Error::BadHex)
+        );
+
+        let err = verify_hex(&pubkey, "", &der_hex).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("invalid message hash: {}", Secp256k1Error::BadHexLength)
+        );
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_verify_mutated() {
+        let p = chapter3_point();
+        for (z, sig) in chapter3_vectors() {
+            let z2 = (&z + 1u8).complete();
+            assert!(!p.verify(&z2, &sig));
+
+            let bad_r = Signature::new(&sig.r + &Scalar::one(), sig.s.clone());
+            assert!(!p.verify(&z, &bad_r));
+
+            let bad_s = Signature::new(
+                sig.r.clone(),
+                Scalar::new((sig.s.as_integer() ^ 1u8).complete()),
+            );
+            assert!(!p.verify(&z, &bad_s));
+
+            assert!(!S256Point::get_generator().verify(&z, &sig));
+        }
+    }
+
+    #[test]
+    fn test_verify_out_of_range() {
+        let p = chapter3_point();
+        let (z, sig) = chapter3_vectors()[0].clone();
+        let cases = [
+            Signature::new(Scalar::zero(), sig.s.clone()),
+            Signature::new(sig.r.clone(), Scalar::zero()),
+            Signature::new(Scalar::new(order()), sig.s.clone()),
+        ];
+        for bad in cases {
+            assert!(!p.verify(&z, &bad));
+        }
+
+        // a Signature can't hold r or s >= n, so those are rejected when parsing instead
+        let n = to_bytes32(&order());
+        let mut compact = sig.to_compact();
+        compact[..32].copy_from_slice(&n);
+        assert_eq!(
+            Signature::from_compact(&compact),
+            Err(Secp256k1Error::SignatureOutOfRange)
+        );
+        let mut der = vec![0x30, 0x26, 0x02, 0x21, 0x00];
+        der.extend_from_slice(&n);
+        der.extend_from_slice(&[0x02, 0x01, 0x01]);
+        assert_eq!(
+            Signature::parse_der(&der),
+            Err(Secp256k1Error::SignatureOutOfRange)
+        );
+    }
+
+    fn decode_hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_der() {
+        let sig = Signature::new(
+            scalar("37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6"),
+            scalar("8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec"),
+        );
+        let expected = decode_hex(
+            "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6\
+             0221008ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec",
+        );
+        assert_eq!(sig.der(), expected);
+    }
+
+    #[test]
+    fn test_der_short_r_and_padded_s() {
+        // r is only 30 bytes long, s has its high bit set and needs the 0x00 pad
+        let sig = Signature::new(
+            scalar("3a6f08b1f6f1d1d1c0e6b8ea8c2d4d5a7f3c3b2a1908f7e6d5c4b3a2918"),
+            scalar("8000000000000000000000000000000000000000000000000000000000000001"),
+        );
+        let expected = decode_hex(
+            "3043021e03a6f08b1f6f1d1d1c0e6b8ea8c2d4d5a7f3c3b2a1908f7e6d5c4b3a2918\
+             0221008000000000000000000000000000000000000000000000000000000000000001",
+        );
+        assert_eq!(sig.der(), expected);
+
+        let sig = Signature::new(
+            Scalar::new(Integer::from(0x80)),
+            Scalar::new(Integer::from(0x7f)),
+        );
+        assert_eq!(sig.der(), decode_hex("30070202008002017f"));
+    }
+
+    #[test]
+    fn test_parse_der() {
+        let der = decode_hex(
+            "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6\
+             0221008ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec",
+        );
+        let sig = Signature::parse_der(&der).unwrap();
+        assert_eq!(
+            sig.r,
+            scalar("37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6")
+        );
+        assert_eq!(
+            sig.s,
+            scalar("8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec")
+        );
+        assert_eq!(sig.der(), der);
+    }
+
+    #[test]
+    fn test_parse_der_round_trip() {
+        let sigs = [
+            Signature::new(Scalar::new(Integer::from(1)), Scalar::new(Integer::from(1))),
+            Signature::new(
+                Scalar::new(Integer::from(0x80)),
+                Scalar::new(Integer::from(0x7f)),
+            ),
+            Signature::new(
+                Scalar::new(order() - 1u8),
+                scalar("8000000000000000000000000000000000000000"),
+            ),
+            Signature::new(
+                scalar("3a6f08b1f6f1d1d1c0e6b8ea8c2d4d5a7f3c3b2a1908f7e6d5c4b3a2918"),
+                scalar("8000000000000000000000000000000000000000000000000000000000000001"),
+            ),
+        ];
+        for sig in sigs
+            .into_iter()
+            .chain(chapter3_vectors().map(|(_, sig)| sig))
+        {
+            assert_eq!(Signature::parse_der(&sig.der()), Ok(sig));
+        }
+    }
+
+    #[test]
+    fn test_parse_der_malformed() {
+        use Secp256k1Error::*;
+
+        let cases = [
+            ("", DerTruncated),
+            ("30", DerTruncated),
+            ("3007020101020101", DerTruncated),
+            ("300602010102010100", DerTrailingBytes),
+            ("3009020101020101020101", DerTrailingBytes),
+            ("310602010102010100", DerInvalidTag),
+            ("3006030101020101", DerInvalidTag),
+            ("3006020101030101", DerInvalidTag),
+            ("308106020101020101", DerInvalidLength),
+            ("300702810101020101", DerInvalidLength),
+            ("30050200020101", DerZeroLengthInteger),
+            ("30050201010200", DerZeroLengthInteger),
+            ("3006020181020101", DerNegativeInteger),
+            ("3006020101020181", DerNegativeInteger),
+            ("300702020001020101", DerNonMinimalInteger),
+            ("30070201010202007f", DerNonMinimalInteger),
+            ("30070201010202", DerTruncated),
+            ("3006020101020201", DerTruncated),
+        ];
+        for (der, expected) in cases {
+            assert_eq!(
+                Signature::parse_der(&decode_hex(der)),
+                Err(expected),
+                "input {der}"
+            );
+        }
+
+        // 33-byte r that starts with a needless zero byte
+        let mut der = vec![0x30, 0x26, 0x02, 0x21, 0x00];
+        der.extend([0x11; 32]);
+        der.extend([0x02, 0x01, 0x01]);
+        assert_eq!(Signature::parse_der(&der), Err(DerNonMinimalInteger));
+
+        // 34-byte r that is otherwise well formed
+        let mut der = vec![0x30, 0x27, 0x02, 0x22, 0x01];
+        der.extend([0x11; 33]);
+        der.extend([0x02, 0x01, 0x01]);
+        assert_eq!(Signature::parse_der(&der), Err(DerIntegerTooLong));
+    }
+
+    #[test]
+    fn test_compact() {
+        // both halves have leading zero bytes
+        let sig = Signature::new(
+            scalar("00000000000000000000000000000000000000000000000000000000000000ab"),
+            scalar("0000ffeeddccbbaa99887766554433221100ffeeddccbbaa9988776655443322"),
+        );
+        let compact = sig.to_compact();
+        let mut expected = [0u8; 64];
+        expected[31] = 0xab;
+        expected[34..].copy_from_slice(&decode_hex(
+            "ffeeddccbbaa99887766554433221100ffeeddccbbaa9988776655443322",
+        ));
+        assert_eq!(compact, expected);
+        assert_eq!(Signature::from_compact(&compact), Ok(sig.clone()));
+
+        let der = Signature::from_compact(&compact).unwrap().der();
+        assert_eq!(Signature::parse_der(&der).unwrap().to_compact(), compact);
+    }
+
+    #[test]
+    fn test_compact_out_of_range() {
+        let n = to_bytes32(&order());
+        let one = to_bytes32(&Integer::from(1));
+
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&n);
+        bytes[32..].copy_from_slice(&one);
+        assert_eq!(
+            Signature::from_compact(&bytes),
+            Err(Secp256k1Error::SignatureOutOfRange)
+        );
+
+        bytes[..32].copy_from_slice(&one);
+        bytes[32..].copy_from_slice(&[0xff; 32]);
+        assert_eq!(
+            Signature::from_compact(&bytes),
+            Err(Secp256k1Error::SignatureOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_compact_sign_round_trip() {
+        let key = PrivateKey::new(hex("12345deadbeef"));
+        let z = hex("231c6f3d980a6b0fb7152f85cee7eb52bf92433d9919b9c5218cb08e79cce78");
+        let sig = key.sign(&z);
+        let parsed = Signature::from_compact(&sig.to_compact()).unwrap();
+        assert_eq!(parsed, sig);
+        assert!(key.public_key().verify(&z, &parsed));
+        assert_eq!(Signature::parse_der(&parsed.der()), Ok(sig));
+    }
+
+    fn test_key(seed: u32) -> PrivateKey {
+        let digest = sha256(&seed.to_be_bytes());
+        let mut secret = Integer::from_digits(&digest, Order::Msf);
+        secret.rem_euc_assign(&order());
+        PrivateKey::new(secret)
+    }
+
+    #[test]
+    fn test_recover() {
+        for seed in 0..6u32 {
+            let key = test_key(seed);
+            let z = test_key(seed + 100).secret.as_integer().clone();
+            let RecoverableSignature { sig, recid } = key.sign_recoverable(&z);
+            assert_eq!(sig, key.sign(&z));
+            assert_eq!(sig.recover(&z, recid), Ok(key.public_key().into()));
+
+            // any other recovery id either fails or produces somebody else's key
+            for other in (0..4u8).filter(|&id| id != recid) {
+                if let Ok(p) = sig.recover(&z, other) {
+                    assert_ne!(p, S256Point::from(key.public_key()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_recoverable_signature_round_trip() {
+        let n = order();
+        let mut rand = RandState::new();
+        rand.seed(&Integer::from(143));
+
+        for _ in 0..16 {
+            let key =
+                PrivateKey::new(Integer::from(1) + (&n - 1u8).complete().random_below(&mut rand));
+            let z = n.clone().random_below(&mut rand);
+            let recoverable = key.sign_recoverable(&z);
+            assert_eq!(recoverable.recover(&z), Ok(key.public_key().into()));
+
+            for format in [RecoveryByte::Raw, RecoveryByte::Legacy] {
+                let bytes = recoverable.to_bytes(format);
+                assert_eq!(bytes[..64], recoverable.sig.to_compact());
+                let parsed = RecoverableSignature::from_bytes(&bytes, format).unwrap();
+                assert_eq!(parsed, recoverable);
+                assert_eq!(parsed.recover(&z), Ok(key.public_key().into()));
+            }
+            let raw = recoverable.to_bytes(RecoveryByte::Raw)[64];
+            assert_eq!(recoverable.to_bytes(RecoveryByte::Legacy)[64], raw + 27);
+        }
+    }
+
+    #[test]
+    fn test_recoverable_signature_vector() {
+        // signed by OpenSSL (random k, s normalized to low-S), so only recovery is checked here
+        let hex_int = |value: &str| Integer::from_str_radix(value, 16).unwrap();
+        let z = hex_int("175468cfb6cdcf99e7868008eaf3e3cd72d1cfcf243072749af795ef678f7eb7");
+        let bytes: [u8; 65] = decode_hex(concat!(
+            "f8c4baab981538bbbb7b10ce8d17ce5fd2fb73dc36d06633cc4fea51bb3331e0",
+            "68943cbe0becfd48d691cb744c497eb6931072c6f1f714f53e21478383574267",
+            "1b"
+        ))
+        .try_into()
+        .unwrap();
+        let expected = S256Point::from_sec(&decode_hex(
+            "036206be3a77c0e7485f5afcd7f481c85a468c091f7509a9cb714d96e3cfc6cfc9",
+        ))
+        .unwrap();
+        let key = PrivateKey::new(hex_int(
+            "6fc77805cae52a387553816626fa7a38fa00e3919b24ba0370641aa104dffb85",
+        ));
+        assert_eq!(S256Point::from(key.public_key()), expected);
+
+        let recoverable = RecoverableSignature::from_bytes(&bytes, RecoveryByte::Legacy).unwrap();
+        assert_eq!(recoverable.recid, 0);
+        assert_eq!(recoverable.recover(&z), Ok(expected.clone()));
+        assert!(expected.verify(&z, &recoverable.sig));
+
+        // the same bytes read as a raw recovery id of 27 are rejected
+        assert_eq!(
+            RecoverableSignature::from_bytes(&bytes, RecoveryByte::Raw),
+            Err(Secp256k1Error::InvalidRecoveryId)
+        );
+        let mut bad = bytes;
+        bad[64] = 31;
+        assert_eq!(
+            RecoverableSignature::from_bytes(&bad, RecoveryByte::Legacy),
+            Err(Secp256k1Error::InvalidRecoveryId)
+        );
+        bad[64] = 26;
+        assert_eq!(
+            RecoverableSignature::from_bytes(&bad, RecoveryByte::Legacy),
+            Err(Secp256k1Error::InvalidRecoveryId)
+        );
+    }
+
+    #[test]
+    fn test_recover_invalid() {
+        let (z, sig) = chapter3_vectors()[0].clone();
+        assert_eq!(sig.recover(&z, 4), Err(Secp256k1Error::InvalidRecoveryId));
+
+        let zero_r = Signature::new(Scalar::new(Integer::from(0)), sig.s.clone());
+        assert_eq!(
+            zero_r.recover(&z, 0),
+            Err(Secp256k1Error::SignatureOutOfRange)
+        );
+
+        // r + n is larger than p for this r, so recovery ids 2 and 3 cannot apply
+        assert_eq!(sig.recover(&z, 2), Err(Secp256k1Error::RecoveryFailed));
+
+        let recovered = (0..2u8)
+            .filter_map(|recid| sig.recover(&z, recid).ok())
+            .collect::<Vec<_>>();
+        assert!(recovered.contains(&chapter3_point()));
+    }
+
+    #[test]
+    fn test_signature_display() {
+        let sig = Signature::new(
+            Scalar::new(Integer::from(0xabc)),
+            Scalar::new(Integer::from(0x123)),
+        );
+        assert_eq!(sig.to_string(), "Signature(abc, 123)");
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 6979 known answers: secret 1 signing sha256("Satoshi Nakamoto"),
+        // and secret 12345 signing hash256("Programming Bitcoin!")
+        let cases = [
+            (
+                Integer::from(1),
+                "a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e",
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8",
+                "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            ),
+            (
+                Integer::from(12345),
+                "969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48",
+                "8eeacac05e4c29e793b5287ed044637132ce9ead7fded533e7441d87a8dc9c23",
+                "36674f81f10c7fb347c1224bd546813ea24ada6f642c02f2248516e3aa8cb303",
+            ),
+        ];
+        for (secret, z, r, s) in cases {
+            let key = PrivateKey::new(secret);
+            let sig = key.sign(&hex(z));
+            assert_eq!(sig, Signature::new(scalar(r), scalar(s)));
+            assert!(key.public_key().verify(&hex(z), &sig));
+        }
+    }
+
+    #[test]
+    fn test_sign_with_aux_rand() {
+        // secret 12345 signing sha256("synthetic nonce"), aux_rand as RFC 6979 additional data
+        let key = PrivateKey::new(Integer::from(12345));
+        let z = hex("7c60029713f2150f81482229c01ed8cf2348ace9b0c95c292cb3c3a3b28d7ef0");
+        let counting: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let cases = [
+            (
+                None,
+                "0eb898126ec4d121de4e2e332f74f5a80e0ae441f47bc5371cc299edcd8eda6c",
+                "417a19325a73e0e3fe2e4ae3a39d5ceb2fbb6f40dfcee9c96bfb7566f60eceb0",
+            ),
+            (
+                Some(counting),
+                "8e5e2e0ddce13adddbeffb2b4d3c243c103b799f66f32cff4462acd1977618e5",
+                "3105986f796ef79448c0ed46b4a192d09f0f7a89b4bb466b2735bc7a12bd1a01",
+            ),
+            (
+                Some([0x42; 32]),
+                "6ad3664330824ed86b2db050a6ce0a1736044a591c28a35d317605d7fb0f77fb",
+                "0785877c5efe722ddf5429b46ed89f1254008a2a8fd4d36be2030d18160a66a3",
+            ),
+        ];
+        for (aux_rand, r, s) in cases {
+            let options = SignOptions {
+                aux_rand,
+                ..SignOptions::default()
+            };
+            let sig = key.sign_with(&z, &options);
+            assert_eq!(sig, Signature::new(scalar(r), scalar(s)));
+            assert_eq!(key.sign_with(&z, &options), sig);
+            assert!(key.public_key().verify(&z, &sig));
+
+            let recoverable = key.sign_recoverable_with(&z, &options);
+            assert_eq!(recoverable.sig, sig);
+            assert_eq!(recoverable.recover(&z), Ok(key.public_key().into()));
+        }
+        assert_eq!(key.sign_with(&z, &SignOptions::default()), key.sign(&z));
+    }
+
+    #[test]
+    fn test_grind_low_r() {
+        let grind = SignOptions {
+            grind_low_r: true,
+            ..SignOptions::default()
+        };
+        let key = PrivateKey::new(Integer::from(12345));
+        let mut long = 0;
+        for i in 0..200u32 {
+            let z = z_from_message(&i.to_le_bytes());
+            let plain = key.sign(&z).der();
+            // s is always low, so only r can need the padding byte
+            assert!(plain.len() <= 71);
+            long += usize::from(plain.len() == 71);
+
+            let ground = key.sign_with(&z, &grind);
+            assert!(ground.is_low_r());
+            assert!(ground.der().len() <= 70, "{i}");
+            assert!(key.public_key().verify(&z, &ground));
+            assert_eq!(key.sign_with(&z, &grind), ground);
+            if plain[3] == 0x20 {
+                // already low, so the first try is kept
+                assert_eq!(ground.der(), plain);
+            }
+        }
+        // about half of the plain signatures have a high r
+        assert!((60..140).contains(&long), "{long}");
+    }
+
+    #[test]
+    fn test_grind_low_r_matches_bitcoin_core() {
+        // key_tests.cpp in Bitcoin Core, CKey::Sign grinds by default
+        let grind = SignOptions {
+            grind_low_r: true,
+            ..SignOptions::default()
+        };
+        let z = z_from_message(b"Very deterministic message");
+        let cases = [
+            (
+                "5HxWvvfubhXpYYpS3tJkw6fq9jE9j18THftkZjHHfmFiWtmAbrj",
+                "304402205dbbddda71772d95ce91cd2d14b592cfbc1dd0aabd6a394b6c2d377bbe59d31d\
+                 022014ddda21494a4e221f0824f0b8b924c43fa43c0ad57dccdaa11f81a6bd4582f6",
+            ),
+            (
+                "5KC4ejrDjv152FGwP386VD1i2NYc5KkfSMyv1nGy1VGDxGHqVY3",
+                "3044022052d8a32079c11e79db95af63bb9600c5b04f21a9ca33dc129c2bfa8ac9dc1cd5\
+                 022061d8ae5e0f6c1a16bde3719c64c2fd70e404b6428ab9a69566962e8771b5944d",
+            ),
+        ];
+        for (wif, der) in cases {
+            let (key, _, _) = PrivateKey::from_wif(wif).unwrap();
+            assert_eq!(hex::encode(&key.sign_with(&z, &grind).der()), der);
+        }
+    }
+
+    #[test]
+    fn test_recover_key_from_reused_nonce() {
+        let k = Scalar::new(hex("c0ffee"));
+        for seed in 0..4 {
+            let key = test_key(seed);
+            let z1 = test_key(seed + 200).secret.as_integer().clone();
+            let z2 = test_key(seed + 300).secret.as_integer().clone();
+            let sig1 = key.sign_with_nonce(&z1, k.clone()).sig;
+            let sig2 = key.sign_with_nonce(&z2, k.clone()).sig;
+            assert_eq!(sig1.r, sig2.r);
+
+            let recovered = recover_key_from_reused_nonce(&z1, &sig1, &z2, &sig2).unwrap();
+            assert_eq!(recovered, key);
+            // order doesn't matter
+            assert_eq!(
+                recover_key_from_reused_nonce(&z2, &sig2, &z1, &sig1),
+                Some(key.clone())
+            );
+
+            // the same signature twice gives nothing to solve with
+            assert_eq!(recover_key_from_reused_nonce(&z1, &sig1, &z1, &sig1), None);
+
+            // RFC 6979 nonces differ per message, so r differs and nothing leaks
+            let (fresh1, fresh2) = (key.sign(&z1), key.sign(&z2));
+            assert_ne!(fresh1.r, fresh2.r);
+            assert_eq!(
+                recover_key_from_reused_nonce(&z1, &fresh1, &z2, &fresh2),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_aggregate_keys() {
+        let g = S256Point::get_generator();
+        let two = g_mul(&Integer::from(2));
+        // computed independently from the definition for the keys 1*G and 2*G
+        let expected = S256Point::from_sec(&decode_hex(
+            "031e152449596818f7dbe4453cc5cfb57bafe25a816bcfae2ad6d7f19861b737f2",
+        ))
+        .unwrap();
+        assert_eq!(
+            aggregate_keys(&[g.clone(), two.clone()]),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            aggregate_keys(&[two.clone(), g.clone()]),
+            Ok(expected.clone())
+        );
+        assert_ne!(expected, &g + &two);
+
+        let keys: Vec<S256Point> = (0..5)
+            .map(|i| S256Point::from(test_key(i).public_key()))
+            .collect();
+        let aggregate = aggregate_keys(&keys).unwrap();
+        let mut reversed = keys.clone();
+        reversed.reverse();
+        assert_eq!(aggregate_keys(&reversed), Ok(aggregate.clone()));
+        let naive = keys
+            .iter()
+            .skip(1)
+            .fold(keys[0].clone(), |sum, key| &sum + key);
+        assert_ne!(aggregate, naive);
+
+        // one key still gets a coefficient
+        assert_ne!(aggregate_keys(&keys[..1]), Ok(keys[0].clone()));
+    }
+
+    #[test]
+    fn test_aggregate_keys_errors() {
+        let key = S256Point::from(test_key(0).public_key());
+        assert_eq!(aggregate_keys(&[]), Err(Secp256k1Error::EmptyKeyList));
+        assert_eq!(
+            aggregate_keys(&[key.clone(), test_key(1).public_key().into(), key.clone()]),
+            Err(Secp256k1Error::DuplicateKey)
+        );
+        assert_eq!(
+            aggregate_keys(&[key, S256Point::new(Infinity, Infinity)]),
+            Err(Secp256k1Error::SecPointAtInfinity)
+        );
+    }
+
+    #[test]
+    fn test_hash_reader_chunk_boundaries() {
+        for len in [
+            0,
+            1,
+            HASH_READER_CHUNK - 1,
+            HASH_READER_CHUNK,
+            HASH_READER_CHUNK + 1,
+            2 * HASH_READER_CHUNK,
+        ] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let mut cursor = std::io::Cursor::new(&data);
+            assert_eq!(hash_reader(&mut cursor).unwrap(), hash256(&data), "{len}");
+        }
+    }
+
+    // yields data a few bytes at a time with interruptions in between, then fails
+    struct FlakyReader<'a> {
+        data: &'a [u8],
+        calls: usize,
+    }
+
+    impl Read for FlakyReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls.is_multiple_of(2) {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            if self.data.is_empty() {
+                return Err(io::Error::other("disk on fire"));
+            }
+            let n = self.data.len().min(buf.len()).min(3);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_hash_reader_errors() {
+        let key = test_key(203);
+        let mut reader = FlakyReader {
+            data: b"Programming Bitcoin!",
+            calls: 0,
+        };
+        let e = hash_reader(&mut reader).unwrap_err();
+        assert_eq!(e.to_string(), "disk on fire");
+        // every byte was read first, interruptions were retried
+        assert!(reader.data.is_empty());
+
+        let mut reader = FlakyReader {
+            data: b"",
+            calls: 0,
+        };
+        assert!(key.sign_reader(&mut reader).is_err());
+        let sig = key.sign_message_hash256(b"");
+        let mut reader = FlakyReader {
+            data: b"",
+            calls: 0,
+        };
+        assert!(key.public_key().verify_reader(&mut reader, &sig).is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify_reader() {
+        let key = test_key(203);
+        let public_key = key.public_key();
+        let data: Vec<u8> = (0..10_000_000u32).map(|i| (i % 251) as u8).collect();
+
+        let sig = key.sign_reader(&mut std::io::Cursor::new(&data)).unwrap();
+        assert_eq!(sig, key.sign_message_hash256(&data));
+        assert!(
+            public_key
+                .verify_reader(&mut std::io::Cursor::new(&data), &sig)
+                .unwrap()
+        );
+        assert!(S256Point::from(public_key.clone()).verify_message_hash256(&data, &sig));
+
+        let mut tampered = data;
+        tampered[5_000_000] ^= 1;
+        assert!(
+            !public_key
+                .verify_reader(&mut std::io::Cursor::new(&tampered), &sig)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_der_policies() {
+        let key = test_key(205);
+        let point = S256Point::from(key.public_key());
+        let z = z_from_message(b"policy");
+        let sig = key.sign(&z);
+        let (r, s) = (sig.r.as_integer(), sig.s.as_integer());
+        let high_s = Signature::new(sig.r.clone(), -&sig.s);
+        // r and s as DER integers with their lengths spelled out, so they can be made non-minimal
+        let der_with = |r: &[u8], s: &[u8], long_form: bool| {
+            let int = |v: &[u8]| {
+                let len = if long_form {
+                    vec![0x81, v.len() as u8]
+                } else {
+                    vec![v.len() as u8]
+                };
+                [&[0x02][..], &len, v].concat()
+            };
+            let body = [int(r), int(s)].concat();
+            [&[0x30, body.len() as u8][..], &body].concat()
+        };
+        let bytes = |v: &Integer| {
+            let mut digits = v.to_digits::<u8>(Order::Msf);
+            if digits.is_empty() || digits[0] & 0x80 != 0 {
+                digits.insert(0, 0);
+            }
+            digits
+        };
+        let padded = |v: &Integer| [&[0x00, 0x00][..], &bytes(v)].concat();
+        assert_eq!(der_with(&bytes(r), &bytes(s), false), sig.der());
+
+        use Secp256k1Error::*;
+        use VerifyPolicy::*;
+        let cases = [
+            ("valid", sig.der(), Ok(true), Ok(true)),
+            ("high-S", high_s.der(), Err(HighS), Ok(true)),
+            (
+                "non-minimal r",
+                der_with(&padded(r), &bytes(s), false),
+                Err(DerNonMinimalInteger),
+                Ok(true),
+            ),
+            (
+                "long-form lengths",
+                der_with(&bytes(r), &bytes(s), true),
+                Err(DerInvalidLength),
+                Ok(true),
+            ),
+            (
+                "trailing bytes",
+                [sig.der(), vec![0x01]].concat(),
+                Err(DerTrailingBytes),
+                Ok(true),
+            ),
+            (
+                "r = 0",
+                der_with(&[0], &bytes(s), false),
+                Err(ZeroSignatureComponent),
+                Ok(false),
+            ),
+            (
+                "s = n",
+                der_with(&bytes(r), &bytes(&order()), false),
+                Err(SignatureOutOfRange),
+                Ok(false),
+            ),
+            (
+                "not DER",
+                vec![0x31, 0x00],
+                Err(DerInvalidTag),
+                Err(DerInvalidTag),
+            ),
+        ];
+        for (name, der, standard, consensus) in cases {
+            assert_eq!(point.verify_der(&z, &der, Standard), standard, "{name}");
+            assert_eq!(point.verify_der(&z, &der, Consensus), consensus, "{name}");
+        }
+        assert_eq!(VerifyPolicy::default(), Standard);
+
+        // a different z fails under both without an error
+        let other = z_from_message(b"other");
+        for policy in [Standard, Consensus] {
+            assert_eq!(point.verify_der(&other, &sig.der(), policy), Ok(false));
+        }
+        // the lax parser still needs every length to fit
+        let der = sig.der();
+        assert_eq!(
+            point.verify_der(&z, &der[..der.len() - 1], Consensus),
+            Err(DerTruncated)
+        );
+    }
+
+    // the first len bytes of PBKDF2-HMAC-SHA256
+    fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+        (1..)
+            .flat_map(|index| pbkdf2_block(password, salt, iterations, index))
+            .take(len)
+            .collect()
+    }
+
+    #[test]
+    fn test_pbkdf2_hmac_sha256() {
+        // RFC 6070's cases with SHA-256 in place of SHA-1, as in RFC 7914 and most libraries
+        let cases: [(&[u8], &[u8], u32, &str); 5] = [
+            (
+                b"password",
+                b"salt",
+                1,
+                "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b",
+            ),
+            (
+                b"password",
+                b"salt",
+                2,
+                "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43",
+            ),
+            (
+                b"password",
+                b"salt",
+                4096,
+                "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a",
+            ),
+            (
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9",
+            ),
+            (
+                b"pass\0word",
+                b"sa\0lt",
+                4096,
+                "89b69d0516f829893c696226650a8687",
+            ),
+        ];
+        for (password, salt, iterations, expected) in cases {
+            let derived = pbkdf2(password, salt, iterations, expected.len() / 2);
+            assert_eq!(hex::encode(&derived), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one iteration")]
+    fn test_pbkdf2_zero_iterations() {
+        pbkdf2_block(b"password", b"salt", 0, 1);
+    }
+
+    #[test]
+    fn test_from_passphrase() {
+        let pass = "correct horse battery staple";
+        let key = PrivateKey::from_passphrase(pass, b"galactic_credit", 2048);
+        // T_1 is already below n, so it is the key
+        assert_eq!(
+            key.reveal_hex(),
+            "cd177df4a9dbea633830095a67cd5b69ea95f6089aeabf4a454f3d6e923031ed"
+        );
+        assert_eq!(
+            key.public_key().point().address(true, Network::Mainnet),
+            "19iosPKijjf7GxfK2M7X4jyLjpvkQaEBpn"
+        );
+        assert_eq!(
+            PrivateKey::from_passphrase(pass, b"galactic_credit", 2048),
+            key
+        );
+        assert_ne!(PrivateKey::from_passphrase(pass, b"galactic", 2048), key);
+        assert_ne!(
+            PrivateKey::from_passphrase(pass, b"galactic_credit", 2047),
+            key
+        );
+    }
+
+    #[test]
+    fn test_from_passphrase_insecure_sha256() {
+        // the best known brain wallet, emptied again and again since 2011
+        let key = PrivateKey::from_passphrase_insecure_sha256("correct horse battery staple");
+        assert_eq!(
+            key.reveal_hex(),
+            "c4bbcb1fbec99d65bf59d85c8cb62ee2db963f0fe106f483d9afa73bd4e39a8a"
+        );
+        assert_eq!(
+            key.public_key().point().address(false, Network::Mainnet),
+            "1JwSSubhmg6iPtRjtyqhUYYH7bZg3Lfy1T"
+        );
+    }
+
+    #[test]
+    fn test_message_hash256() {
+        // Programming Bitcoin chapter 3: secret hash256("my secret"), message "my message"
+        // signed with k = 1234567890
+        let key = PrivateKey::new(z_from_message(b"my secret"));
+        let point = S256Point::from(key.public_key());
+        assert_eq!(
+            point,
+            S256Point::new(
+                Finite(S256Field::new(
+                    "28d003eab2e428d11983f3e97c3fa0addf3b42740df0d211795ffb3be2f6c52"
+                )),
+                Finite(S256Field::new(
+                    "ae987b9ec6ea159c78cb2a937ed89096fb218d9e7594f02b547526d8cd309e2"
+                )),
+            )
+        );
+        let book = Signature::new(
+            scalar("2b698a0f0a4041b77e63488ad48c23e8e8838dd1fb7520408b121697b782ef22"),
+            scalar("bb14e602ef9e3f872e25fad328466b34e6734b7a0fcd58b1eb635447ffae8cb9"),
+        );
+        assert!(point.verify_message_hash256(b"my message", &book));
+
+        let sig = key.sign_message_hash256(b"my message");
+        assert_eq!(sig, key.sign(&z_from_message(b"my message")));
+        assert!(point.verify_message_hash256(b"my message", &sig));
+
+        // flip one bit of the message
+        for (index, bit) in [(0, 0), (3, 7), (9, 5)] {
+            let mut flipped = b"my message".to_vec();
+            flipped[index] ^= 1 << bit;
+            assert!(!point.verify_message_hash256(&flipped, &sig));
+            assert!(!point.verify_message_hash256(&flipped, &book));
+        }
+    }
+
+    #[test]
+    fn test_sign_with_k() {
+        // Programming Bitcoin chapter 3: e = 12345, z = hash256("Programming Bitcoin!")
+        let key = PrivateKey::new(Integer::from(12345));
+        let z = z_from_message(b"Programming Bitcoin!");
+        let k = Integer::from(1234567890);
+        let sig = key.sign_with_k(&z, &k).unwrap();
+        assert_eq!(
+            sig,
+            Signature::new(
+                scalar("2b698a0f0a4041b77e63488ad48c23e8e8838dd1fb7520408b121697b782ef22"),
+                scalar("1dbc63bfef4416705e602a7b564161167076d8b20990a0f26f316cff2cb0bc1a"),
+            )
+        );
+        assert!(key.public_key().verify(&z, &sig));
+        assert_ne!(sig, key.sign(&z));
+
+        // the "my message" example from test_message_hash256 has a high s, which comes back low
+        let key = PrivateKey::new(z_from_message(b"my secret"));
+        let sig = key.sign_with_k(&z_from_message(b"my message"), &k).unwrap();
+        assert_eq!(
+            sig.normalize_s(),
+            Signature::new(
+                scalar("2b698a0f0a4041b77e63488ad48c23e8e8838dd1fb7520408b121697b782ef22"),
+                scalar("bb14e602ef9e3f872e25fad328466b34e6734b7a0fcd58b1eb635447ffae8cb9"),
+            )
+            .normalize_s()
+        );
+        assert!(sig.is_low_s());
+
+        for bad in [Integer::ZERO, order(), order() + 1u8, Integer::from(-1)] {
+            assert_eq!(
+                key.sign_with_k(&z, &bad),
+                Err(Secp256k1Error::NonceOutOfRange)
+            );
+        }
+        assert!(key.sign_with_k(&z, &(order() - 1u8)).is_ok());
+    }
+
+    #[test]
+    fn test_sign_is_low_s() {
+        let key = PrivateKey::new(hex("deadbeef12345"));
+        let p = S256Point::from(key.public_key());
+        for i in 0..16u32 {
+            let z =
+                hex("1000000000000000000000000000000000000000000000000000000000000000") * i + 7u8;
+            let sig = key.sign(&z);
+            assert!(sig.is_low_s());
+            assert!(p.verify_strict(&z, &sig));
+        }
+    }
+
+    #[test]
+    fn test_high_s() {
+        let p = chapter3_point();
+        // the second chapter 3 signature happens to have s > n/2
+        let (z, sig) = chapter3_vectors()[1].clone();
+        assert!(!sig.is_low_s());
+        assert!(p.verify(&z, &sig));
+        assert!(!p.verify_strict(&z, &sig));
+
+        let low = sig.normalize_s();
+        assert!(low.is_low_s());
+        assert_eq!(low.r, sig.r);
+        assert_eq!(low.s, -&sig.s);
+        assert!(p.verify(&z, &low));
+        assert!(p.verify_strict(&z, &low));
+    }
+
+    #[test]
+    fn test_normalize_s_idempotent() {
+        let half = order() >> 1u32;
+        let sigs = [
+            chapter3_vectors()[0].1.clone(),
+            chapter3_vectors()[1].1.clone(),
+            Signature::new(Scalar::new(Integer::from(1)), Scalar::new(half.clone())),
+            Signature::new(Scalar::new(Integer::from(1)), Scalar::new(half + 1u8)),
+        ];
+        for sig in sigs {
+            let once = sig.normalize_s();
+            assert!(once.is_low_s());
+            assert_eq!(once.normalize_s(), once);
+        }
+    }
+
+    #[test]
+    fn test_from_xonly_not_on_curve() {
+        let x = bytes32("EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34");
+        assert_eq!(S256Point::from_xonly(&x), Err(Secp256k1Error::NotOnCurve));
+    }
+
+    #[test]
+    fn test_from_xonly_out_of_range() {
+        let x = bytes32("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30");
+        assert_eq!(S256Point::from_xonly(&x), Err(Secp256k1Error::XOutOfRange));
+        assert_eq!(
+            S256Point::from_xonly(&bytes32(PRIME_STR)),
+            Err(Secp256k1Error::XOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_tap_tweak_bip341() {
+        // key path only: no merkle root
+        let internal = S256Point::from_xonly(&bytes32(
+            "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+        ))
+        .unwrap();
+        let (output, odd) = internal.tap_tweak(None).unwrap();
+        assert_eq!(
+            output.xonly(),
+            bytes32("53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343")
+        );
+        assert!(odd);
+
+        // single leaf script tree
+        let internal = S256Point::from_xonly(&bytes32(
+            "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+        ))
+        .unwrap();
+        let root = bytes32("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+        let (output, _) = internal.tap_tweak(Some(&root)).unwrap();
+        assert_eq!(
+            output.xonly(),
+            bytes32("147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3")
+        );
+
+        let internal = S256Point::from_xonly(&bytes32(
+            "93478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+        ))
+        .unwrap();
+        let root = bytes32("c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b");
+        let (output, odd) = internal.tap_tweak(Some(&root)).unwrap();
+        assert_eq!(
+            output.xonly(),
+            bytes32("e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e")
+        );
+        assert!(!odd);
+    }
+
+    #[test]
+    fn test_tap_tweak_seckey_bip341() {
+        let key = PrivateKey::new(hex(
+            "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa",
+        ));
+        let tweaked = key.tap_tweak_seckey(None).unwrap();
+        assert_eq!(
+            tweaked.secret,
+            scalar("2405b971772ad26915c8dcdf10f238753a9b837e5f8e6a86fd7c0cce5b7296d9")
+        );
+
+        // the tweaked secret must control the tweaked output key
+        let (output, _) = key.public_key().point().tap_tweak(None).unwrap();
+        assert_eq!(tweaked.public_key().point().xonly(), output.xonly());
+    }
+
+    #[test]
+    fn test_tap_tweak_seckey_matches_output_key() {
+        let root = [7u8; 32];
+        for seed in 1..5 {
+            let key = test_key(seed);
+            let (output, odd) = key.public_key().point().tap_tweak(Some(&root)).unwrap();
+            let tweaked = key.tap_tweak_seckey(Some(&root)).unwrap();
+            assert_eq!(S256Point::from(tweaked.public_key()), output);
+            assert_eq!(tweaked.public_key().point().has_even_y(), !odd);
+        }
+    }
+
+    #[test]
+    fn test_sec() {
+        let g = S256Point::get_generator();
+        assert_eq!(
+            g.sec(true),
+            decode_hex("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+        );
+        assert_eq!(
+            g.sec(false),
+            decode_hex(
+                "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                 483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+            )
+        );
+    }
+
+    #[test]
+    fn test_ecdh_symmetric() {
+        for seed in 1..5 {
+            let alice = test_key(seed);
+            let bob = test_key(seed + 100);
+            assert_eq!(
+                alice.ecdh(bob.public_key().point()),
+                bob.ecdh(alice.public_key().point())
+            );
+            assert_eq!(
+                alice.ecdh_xonly(bob.public_key().point()),
+                bob.ecdh_xonly(alice.public_key().point())
+            );
+        }
+    }
+
+    #[test]
+    fn test_ecdh_vector() {
+        let alice = PrivateKey::new(Integer::from(0x1234567890abcdefu64));
+        let bob = PrivateKey::new(Integer::from(0xfedcba0987654321u64));
+        assert_eq!(
+            alice.ecdh(bob.public_key().point()),
+            bytes32("77f311523f11b6de67381ef15156c13d20eb5d09479c145af30ca4da510135c9")
+        );
+        assert_eq!(
+            alice.ecdh_xonly(bob.public_key().point()),
+            bytes32("31d81b1b68b98025811c7e773520130f1646661e83d54a0f607f7f04558de5f3")
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ecdh_infinity() {
+        test_key(1).ecdh(&S256Point::new(Infinity, Infinity));
+    }
+
+    #[test]
+    fn test_address() {
+        let cases = [
+            (
+                Integer::from(5002),
+                false,
+                Network::Testnet,
+                "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA",
+            ),
+            (
+                Integer::from(2020u64.pow(5)),
+                true,
+                Network::Testnet,
+                "mopVkxp8UhXqRYbCYJsbeE1h1fiF64jcoH",
+            ),
+            (
+                Integer::from(0x12345deadbeefu64),
+                true,
+                Network::Mainnet,
+                "1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1",
+            ),
+        ];
+        for (secret, compressed, network, expected) in cases {
+            let key = PrivateKey::new(secret);
+            assert_eq!(
+                key.public_key().point().address(compressed, network),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_wif() {
+        let cases = [
+            (
+                Integer::from(5003),
+                true,
+                Network::Testnet,
+                "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK",
+            ),
+            (
+                Integer::from(2021u64.pow(5)),
+                false,
+                Network::Testnet,
+                "91avARGdfge8E4tZfYLoxeJ5sGBdNJQH4kvjpWAxgzczjbCwxic",
+            ),
+            (
+                Integer::from(0x54321deadbeefu64),
+                true,
+                Network::Mainnet,
+                "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53a",
+            ),
+        ];
+        for (secret, compressed, network, expected) in cases {
+            let key = PrivateKey::new(secret);
+            assert_eq!(key.wif(compressed, network), expected);
+            assert_eq!(
+                PrivateKey::from_wif(expected),
+                Ok((key, compressed, network))
+            );
+        }
+    }
+
+    #[test]
+    fn test_networks() {
+        // the same key on mainnet and testnet
+        let key = PrivateKey::new(Integer::from(0x12345deadbeefu64));
+        let point = key.public_key().point().clone();
+        assert_eq!(
+            point.address(true, Network::Mainnet),
+            "1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1"
+        );
+        assert_eq!(
+            point.address(true, Network::Testnet),
+            "muXM5645dF2LuLZQFsH2RVGnCfdB4vR1bB"
+        );
+        // regtest and signet only differ from testnet in bech32
+        for network in [Network::Regtest, Network::Signet] {
+            assert_eq!(
+                point.address(true, network),
+                point.address(true, Network::Testnet)
+            );
+            assert_eq!(key.wif(true, network), key.wif(true, Network::Testnet));
+        }
+        assert!(
+            point
+                .p2wpkh_address(Network::Regtest)
+                .unwrap()
+                .starts_with("bcrt1q")
+        );
+
+        // a testnet WIF read as mainnet
+        let wif = key.wif(true, Network::Testnet);
+        assert_eq!(
+            PrivateKey::from_wif_on(&wif, Network::Mainnet),
+            Err(Secp256k1Error::WrongNetwork)
+        );
+        assert_eq!(
+            PrivateKey::from_wif_on(&wif, Network::Signet),
+            Ok((key.clone(), true))
+        );
+
+        // Litecoin's parameters as a private chain: only from_wif_on accepts its WIFs
+        let litecoin = Network::Custom(NetworkParams {
+            p2pkh_prefix: 0x30,
+            p2sh_prefix: 0x32,
+            wif_prefix: 0xb0,
+            hrp: "ltc",
+        });
+        assert!(point.address(true, litecoin).starts_with('L'));
+        let wif = key.wif(true, litecoin);
+        assert_eq!(PrivateKey::from_wif(&wif), Err(Secp256k1Error::InvalidWif));
+        assert_eq!(
+            PrivateKey::from_wif_on(&wif, litecoin),
+            Ok((key.clone(), true))
+        );
+    }
+
+    #[test]
+    fn test_wif_round_trip() {
+        for seed in 1..5 {
+            let key = test_key(seed);
+            for compressed in [false, true] {
+                for network in [Network::Mainnet, Network::Testnet] {
+                    let wif = key.wif(compressed, network);
+                    assert_eq!(
+                        PrivateKey::from_wif(&wif),
+                        Ok((key.clone(), compressed, network))
+                    );
+                    assert_eq!(
+                        PrivateKey::from_wif_on(&wif, network),
+                        Ok((key.clone(), compressed))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_wif_invalid() {
+        // last character changed, so the checksum no longer matches
+        assert_eq!(
+            PrivateKey::from_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S53b"),
+            Err(Secp256k1Error::BadChecksum)
+        );
+        assert_eq!(
+            PrivateKey::from_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgiuQJv1h8Ytr2S530"),
+            Err(Secp256k1Error::InvalidBase58Character)
+        );
+        // valid Base58Check, but a P2PKH address rather than a key
+        assert_eq!(
+            PrivateKey::from_wif("1F1Pn2y6pDb68E5nYJJeba4TLg2U7B6KF1"),
+            Err(Secp256k1Error::InvalidWif)
+        );
+        // right length, wrong version byte
+        let bad_prefix = base58::encode_check(&[[0x01u8].as_slice(), &[0x11; 32]].concat());
+        assert_eq!(
+            PrivateKey::from_wif(&bad_prefix),
+            Err(Secp256k1Error::InvalidWif)
+        );
+        // bad compression suffix
+        let bad_suffix =
+            base58::encode_check(&[[0x80u8].as_slice(), &[0x11; 32], &[0x02]].concat());
+        assert_eq!(
+            PrivateKey::from_wif(&bad_suffix),
+            Err(Secp256k1Error::InvalidWif)
+        );
+        let zero = base58::encode_check(&[[0x80u8].as_slice(), &[0x00; 32]].concat());
+        assert_eq!(
+            PrivateKey::from_wif(&zero),
+            Err(Secp256k1Error::SecretOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_p2wpkh_address() {
+        let cases = [
+            (
+                Integer::from(1),
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            ),
+            (
+                Integer::from(5002),
+                "bc1qwxzvns3xh69eyffl2w0skhqvgqt2m84sj36unw",
+                "tb1qwxzvns3xh69eyffl2w0skhqvgqt2m84schp0ga",
+            ),
+            (
+                Integer::from(0x12345deadbeefu64),
+                "bc1qnxjvv96s0zf98a5l6ag2crgzzf3nwvc97k9lkm",
+                "tb1qnxjvv96s0zf98a5l6ag2crgzzf3nwvc95s7vdg",
+            ),
+        ];
+        for (secret, mainnet, testnet) in cases {
+            let point = S256Point::from(PrivateKey::new(secret).public_key());
+            assert_eq!(point.p2wpkh_address(Network::Mainnet).unwrap(), mainnet);
+            assert_eq!(point.p2wpkh_address(Network::Testnet).unwrap(), testnet);
+        }
+        let no_hrp = Network::Custom(NetworkParams {
+            hrp: "",
+            ..Network::Mainnet.params()
+        });
+        assert_eq!(
+            S256Point::get_generator().p2wpkh_address(no_hrp),
+            Err(Bech32Error::InvalidHrp)
+        );
+    }
+
+    #[test]
+    fn test_from_sec() {
+        for seed in 1..5 {
+            let point = S256Point::from(test_key(seed).public_key());
+            assert_eq!(S256Point::from_sec(&point.sec(true)), Ok(point.clone()));
+            assert_eq!(S256Point::from_sec(&point.sec(false)), Ok(point));
+        }
+
+        let g = S256Point::get_generator().sec(false);
+        let mut off_curve = g.clone();
+        off_curve[64] ^= 1;
+        assert_eq!(
+            S256Point::from_sec(&off_curve),
+            Err(Secp256k1Error::NotOnCurve)
+        );
+        assert_eq!(
+            S256Point::from_sec(&g[..33]),
+            Err(Secp256k1Error::InvalidSecLength)
+        );
+        assert_eq!(
+            S256Point::from_sec(&[]),
+            Err(Secp256k1Error::InvalidSecLength)
+        );
+        let mut bad_prefix = S256Point::get_generator().sec(true);
+        bad_prefix[0] = 0x05;
+        assert_eq!(
+            S256Point::from_sec(&bad_prefix),
+            Err(Secp256k1Error::InvalidSecPrefix)
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_sec() {
+        use Secp256k1Error::*;
+
+        let g = S256Point::get_generator();
+        let compressed = g.sec(true);
+        let uncompressed = g.sec(false);
+        let with_prefix = |prefix: u8, body: &[u8]| [&[prefix], body].concat();
+        let field_prime = to_bytes32(&prime());
+        // x = 5 has no square root of x^3 + 7
+        let off_curve_x = to_bytes32(&Integer::from(5));
+        let mut off_curve = uncompressed.clone();
+        off_curve[64] ^= 1;
+        // G has even y, so 0x07 is the wrong hybrid prefix
+        let hybrid_even = with_prefix(0x06, &uncompressed[1..]);
+        let hybrid_odd = with_prefix(0x07, &uncompressed[1..]);
+
+        let cases: Vec<(&str, Vec<u8>, Secp256k1Error)> = vec![
+            ("empty", vec![], InvalidSecLength),
+            ("infinity", vec![0x00], SecPointAtInfinity),
+            (
+                "infinity with body",
+                with_prefix(0x00, &[0; 32]),
+                SecPointAtInfinity,
+            ),
+            (
+                "unknown prefix",
+                with_prefix(0x05, &compressed[1..]),
+                InvalidSecPrefix,
+            ),
+            ("prefix only", vec![0x02], InvalidSecLength),
+            (
+                "short compressed",
+                compressed[..32].to_vec(),
+                InvalidSecLength,
+            ),
+            (
+                "long compressed",
+                [&compressed[..], &[0]].concat(),
+                SecTrailingBytes,
+            ),
+            (
+                "compressed prefix on 65 bytes",
+                with_prefix(0x02, &uncompressed[1..]),
+                SecTrailingBytes,
+            ),
+            (
+                "short uncompressed",
+                uncompressed[..64].to_vec(),
+                InvalidSecLength,
+            ),
+            (
+                "long uncompressed",
+                [&uncompressed[..], &[0]].concat(),
+                SecTrailingBytes,
+            ),
+            (
+                "uncompressed prefix on 33 bytes",
+                with_prefix(0x04, &compressed[1..]),
+                InvalidSecLength,
+            ),
+            (
+                "compressed x = p",
+                with_prefix(0x03, &field_prime),
+                XOutOfRange,
+            ),
+            (
+                "compressed off curve",
+                with_prefix(0x02, &off_curve_x),
+                NotOnCurve,
+            ),
+            (
+                "uncompressed x = p",
+                [&[0x04], &field_prime[..], &uncompressed[33..]].concat(),
+                XOutOfRange,
+            ),
+            (
+                "uncompressed y = p",
+                [&uncompressed[..33], &field_prime[..]].concat(),
+                YOutOfRange,
+            ),
+            ("uncompressed off curve", off_curve, NotOnCurve),
+            ("hybrid", hybrid_even.clone(), HybridSecNotAllowed),
+            ("hybrid odd", hybrid_odd.clone(), HybridSecNotAllowed),
+        ];
+        for (name, bytes, err) in cases {
+            let strict = S256Point::parse(&bytes, ParsePolicy::Strict);
+            assert_eq!(strict, Err(err.clone()), "{name}");
+            if err != HybridSecNotAllowed {
+                let lax = S256Point::parse(&bytes, ParsePolicy::AllowHybrid);
+                assert_eq!(lax, Err(err), "{name}");
+            }
+        }
+
+        // with hybrid keys allowed, the prefix still has to agree with y
+        assert_eq!(
+            S256Point::parse(&hybrid_even, ParsePolicy::AllowHybrid),
+            Ok(g.clone())
+        );
+        assert_eq!(
+            S256Point::parse(&hybrid_odd, ParsePolicy::AllowHybrid),
+            Err(HybridParityMismatch)
+        );
+        let neg_g = S256Point { point: -&g.point };
+        let neg_uncompressed = neg_g.sec(false);
+        assert_eq!(
+            S256Point::parse(
+                &with_prefix(0x07, &neg_uncompressed[1..]),
+                ParsePolicy::AllowHybrid
+            ),
+            Ok(neg_g.clone())
+        );
+        assert_eq!(
+            S256Point::parse(
+                &with_prefix(0x06, &neg_uncompressed[1..]),
+                ParsePolicy::AllowHybrid
+            ),
+            Err(HybridParityMismatch)
+        );
+
+        // 0x02/0x03 pick the y parity, so flipping the prefix gives the negated point
+        assert_eq!(
+            S256Point::parse(&with_prefix(0x03, &compressed[1..]), ParsePolicy::Strict),
+            Ok(neg_g)
+        );
+    }
+
+    #[test]
+    fn test_ec_point_conversions() {
+        let g = S256Point::get_generator();
+        let raw: EcPoint = g.clone().into();
+        assert_eq!(&raw, g.as_ref());
+        assert_eq!(S256Point::try_from(raw.clone()), Ok(g.clone()));
+
+        let infinity = S256Point::new(Infinity, Infinity);
+        assert_eq!(
+            S256Point::try_from(EcPoint::from(infinity.clone())),
+            Ok(infinity)
+        );
+
+        // (192, 105) on y^2 = x^3 + 7 over F_223
+        let small = EcPoint::new(
+            Finite(FieldElement::new(192, 223)),
+            Finite(FieldElement::new(105, 223)),
+            FieldElement::new(0, 223),
+            FieldElement::new(7, 223),
+        );
+        assert_eq!(S256Point::try_from(small), Err(Secp256k1Error::WrongCurve));
+
+        let mut off_curve = raw;
+        if let Finite(y) = &mut off_curve.y {
+            y.value += 1;
+        }
+        assert_eq!(
+            S256Point::try_from(off_curve),
+            Err(Secp256k1Error::NotOnCurve)
+        );
+    }
+
+    #[test]
+    fn test_field_element_conversions() {
+        let gx = S256Field::new(GX_STR);
+        let raw: FieldElement = gx.clone().into();
+        assert_eq!(&raw, gx.as_ref());
+        assert_eq!(S256Field::try_from(raw), Ok(gx));
+        assert_eq!(
+            S256Field::try_from(FieldElement::new(192, 223)),
+            Err(Secp256k1Error::WrongField)
+        );
+    }
+
+    #[test]
+    fn test_field_chains_match_pow() {
+        use rand_core::SeedableRng;
+        let p = prime();
+        assert_eq!(sqrt_chain().exponent(), (&p + 1u8).complete() >> 2u32);
+        assert_eq!(inverse_chain().exponent(), (&p - 2u8).complete());
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(179);
+        for _ in 0..16 {
+            let element = FieldElement::random_nonzero(&mut rng, p.clone());
+            let field = S256Field::try_from(element.clone()).unwrap();
+            let root = field.sqrt();
+            assert_eq!(root.element, element.pow((&p + 1u8).complete() >> 2u32));
+            let inverse = field.invert().unwrap();
+            assert_eq!(&inverse.element * &element, FieldElement::new(1, p.clone()));
+        }
+        assert_eq!(S256Field::new("0").invert(), None);
+        assert_eq!(S256Field::new("1").invert(), Some(S256Field::new("1")));
+    }
+
+    #[test]
+    fn test_try_new_field() {
+        assert_eq!(S256Field::try_new(GX_STR), Ok(S256Field::new(GX_STR)));
+        assert_eq!(S256Field::try_new("7"), Ok(S256Field::new(B_STR)));
+
+        for bad in ["", "0x07", "-7", "+7", "7 ", "g", "12_34"] {
+            assert_eq!(
+                S256Field::try_new(bad),
+                Err(Secp256k1Error::BadHex),
+                "{bad}"
+            );
+        }
+        // p itself and anything larger
+        assert_eq!(
+            S256Field::try_new(PRIME_STR),
+            Err(Secp256k1Error::OutOfRange)
+        );
+        assert_eq!(
+            S256Field::try_new(&"f".repeat(65)),
+            Err(Secp256k1Error::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_field_hex() {
+        let gx = S256Field::new(GX_STR);
+        assert_eq!(gx.to_string(), GX_STR);
+        assert_eq!(format!("{gx:x}"), GX_STR);
+        assert_eq!(gx.to_hex(), GX_STR);
+
+        let seven = S256Field::new("7");
+        assert_eq!(seven.to_hex(), format!("{}7", "0".repeat(63)));
+        assert_eq!(S256Field::from_hex(&seven.to_hex()), Ok(seven));
+        assert_eq!(S256Field::from_hex(GY_STR).unwrap().to_hex(), GY_STR);
+        assert_eq!(S256Field::from_hex(&GX_STR.to_uppercase()), Ok(gx.clone()));
+
+        for bad in ["7", "", &GX_STR[1..], &format!("0{GX_STR}")] {
+            assert_eq!(
+                S256Field::from_hex(bad),
+                Err(Secp256k1Error::BadHexLength),
+                "{bad}"
+            );
+        }
+        let mut not_hex = GX_STR.to_string();
+        not_hex.replace_range(..1, "g");
+        assert_eq!(S256Field::from_hex(&not_hex), Err(Secp256k1Error::BadHex));
+        assert_eq!(
+            S256Field::from_hex(PRIME_STR),
+            Err(Secp256k1Error::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_point_from_str() {
+        let g = S256Point::get_generator();
+        let compressed = g.to_sec_hex(true);
+        let uncompressed = g.to_sec_hex(false);
+        assert_eq!(compressed, format!("02{GX_STR}"));
+        assert_eq!(uncompressed, format!("04{GX_STR}{GY_STR}"));
+
+        for text in [&compressed, &uncompressed] {
+            assert_eq!(text.parse::<S256Point>(), Ok(g.clone()));
+            assert_eq!(text.to_uppercase().parse::<S256Point>(), Ok(g.clone()));
+            assert_eq!(format!("0x{text}").parse::<S256Point>(), Ok(g.clone()));
+        }
+
+        // hex problems
+        for bad in [
+            &compressed[1..],
+            &format!(" {compressed}"),
+            &format!("{compressed}\n"),
+            &compressed.replace('7', "g"),
+            &format!("0x0x{compressed}"),
+        ] {
+            assert_eq!(
+                bad.parse::<S256Point>(),
+                Err(Secp256k1Error::BadHex),
+                "{bad:?}"
+            );
+        }
+
+        // valid hex, bad point: x = 5 has no y on the curve
+        let off_curve = format!("02{:064x}", 5);
+        assert_eq!(
+            off_curve.parse::<S256Point>(),
+            Err(Secp256k1Error::NotOnCurve)
+        );
+        for short in [&compressed[..64], "", "0x"] {
+            assert_eq!(
+                short.parse::<S256Point>(),
+                Err(Secp256k1Error::InvalidSecLength),
+                "{short:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_new_point() {
+        let gx = || Finite(S256Field::new(GX_STR));
+        let gy = || Finite(S256Field::new(GY_STR));
+        assert_eq!(
+            S256Point::try_new(gx(), gy()),
+            Ok(S256Point::get_generator())
+        );
+        assert_eq!(
+            S256Point::try_new(Infinity, Infinity),
+            Ok(S256Point::new(Infinity, Infinity))
+        );
+
+        let not_on_curve = [
+            (gx(), Finite(S256Field::new(GX_STR))),
+            (gx(), Infinity),
+            (Infinity, gy()),
+        ];
+        for (x, y) in not_on_curve {
+            assert_eq!(S256Point::try_new(x, y), Err(Secp256k1Error::NotOnCurve));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Error: point is not on the secp256k1 curve")]
+    fn test_new_point_panics_on_half_infinity() {
+        S256Point::new(Finite(S256Field::new(GX_STR)), Infinity);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error: expected a non-empty string of hex digits")]
+    fn test_new_field_panics_on_bad_hex() {
+        S256Field::new("not hex");
+    }
+
+    // always fills with 0xff (>= n) for the first few draws, then hands over to a real RNG
+    struct HighFirstRng {
+        rejects: usize,
+        inner: rand_chacha::ChaCha20Rng,
+    }
+
+    impl RngCore for HighFirstRng {
+        fn next_u32(&mut self) -> u32 {
+            self.inner.next_u32()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.inner.next_u64()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            if self.rejects > 0 {
+                self.rejects -= 1;
+                dest.fill(0xff);
+            } else {
+                self.inner.fill_bytes(dest);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for HighFirstRng {}
+
+    #[test]
+    fn test_generate() {
+        use rand_core::SeedableRng;
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(7);
+        for _ in 0..10 {
+            let key = PrivateKey::generate(&mut rng);
+            assert!(!key.secret.is_zero());
+            let point = S256Point::from(key.public_key());
+            assert_eq!(S256Point::from_sec(&point.sec(false)), Ok(point));
+        }
+    }
+
+    #[test]
+    fn test_generate_seeded_is_reproducible() {
+        use rand_core::SeedableRng;
+
+        let a = PrivateKey::generate(&mut rand_chacha::ChaCha20Rng::seed_from_u64(42));
+        let b = PrivateKey::generate(&mut rand_chacha::ChaCha20Rng::seed_from_u64(42));
+        let c = PrivateKey::generate(&mut rand_chacha::ChaCha20Rng::seed_from_u64(43));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_generate_rejects_out_of_range() {
+        use rand_core::SeedableRng;
+
+        let mut rng = HighFirstRng {
+            rejects: 3,
+            inner: rand_chacha::ChaCha20Rng::seed_from_u64(1),
+        };
+        let key = PrivateKey::generate(&mut rng);
+        assert_eq!(rng.rejects, 0);
+        assert!(!key.secret.is_zero());
+    }
+
+    #[test]
+    fn test_generate_os() {
+        let key = PrivateKey::generate_os();
+        assert!(!key.secret.is_zero());
+        assert_ne!(key, PrivateKey::generate_os());
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            PrivateKey::from_bytes(&one),
+            Ok(PrivateKey::new(Integer::from(1)))
+        );
+        assert_eq!(
+            PrivateKey::from_bytes(&[0u8; 32]),
+            Err(Secp256k1Error::SecretOutOfRange)
+        );
+        assert_eq!(
+            PrivateKey::from_bytes(&bytes32(N_STR)),
+            Err(Secp256k1Error::SecretOutOfRange)
+        );
+        let n_minus_one = (order() - 1u8).to_digits::<u8>(Order::Msf);
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&n_minus_one);
+        assert!(PrivateKey::from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_debug_redacts_secret() {
+        let key = PrivateKey::new(hex(
+            "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa",
+        ));
+        let debug = format!("{key:?}");
+        assert_eq!(debug, "PrivateKey(REDACTED)");
+        assert!(!debug.contains(&key.reveal_hex()[..8]));
+        assert!(!debug.chars().any(|c| c.is_ascii_digit()));
+        assert_eq!(
+            key.reveal_hex(),
+            "6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa"
+        );
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut key = test_key(1);
+        key.zeroize();
+        assert!(key.secret.is_zero());
+        assert_eq!(key.reveal_hex(), "0".repeat(64));
+
+        let mut value = Integer::from(u128::MAX) << 200u32;
+        wipe(&mut value);
+        assert_eq!(value, 0);
+    }
+
+    fn batch_items(count: u32) -> Vec<(Integer, Signature, S256Point)> {
+        (0..count)
+            .map(|i| {
+                let key = test_key(i);
+                let z = test_key(i + 1000).secret.as_integer().clone();
+                (z.clone(), key.sign(&z), key.public_key().into())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let mut items = batch_items(8);
+        // wrong message, wrong key, out of range r, and the point at infinity
+        items[1].0 += 1;
+        items[3].2 = test_key(99).public_key().into();
+        items[4].1 = Signature::new(Scalar::zero(), items[4].1.s.clone());
+        items[6].2 = S256Point::new(Infinity, Infinity);
+
+        let expected = vec![true, false, true, false, false, true, false, true];
+        assert_eq!(verify_batch(&items), expected);
+        assert!(verify_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_g_mul_matches_plain_multiplication() {
+        let g = S256Point::get_generator();
+        let n = order();
+        let mut scalars = vec![
+            Integer::from(1),
+            Integer::from(2),
+            Integer::from(15),
+            Integer::from(16),
+            Integer::from(0xffff),
+            (&n - 1u8).complete(),
+            (&n - 2u8).complete(),
+        ];
+        scalars.extend((0..8).map(|i| test_key(i).secret.as_integer().clone()));
+        for k in scalars {
+            assert_eq!(g_mul(&k), &g * k.clone(), "{k:x}");
+        }
+
+        let infinity = S256Point::new(Infinity, Infinity);
+        assert_eq!(g_mul(&Integer::new()), infinity);
+        assert_eq!(g_mul(&n), infinity);
+        assert_eq!(g_mul(&(&n + 5u8).complete()), g_mul(&Integer::from(5)));
+    }
+
+    #[test]
+    fn test_sign_matches_vartime_path() {
+        let n = order();
+        let exp = (&n - 2u8).complete();
+        for i in 0..8 {
+            let key = test_key(i);
+            let z = Integer::from_digits(&sha256(&[i as u8; 3]), Order::Msf);
+
+            // the same signature built from g_mul, pow_mod and a branchy low-S normalization
+            let k = key.deterministic_k(&z, None);
+            let k = k.as_integer().clone();
+            let big_r = g_mul(&k);
+            let Finite(x) = big_r.point.x.clone() else {
+                panic!()
+            };
+            let r = x.value % &n;
+            let k_inv = k.pow_mod_ref(&exp, &n).unwrap().complete();
+            let s = ((&r * key.secret.as_integer()).complete() + &z) * k_inv % &n;
+            let vartime = Signature::new(Scalar::new(r), Scalar::new(s));
+            let mut recid = u8::from(!big_r.has_even_y());
+            if !vartime.is_low_s() {
+                recid ^= 1;
+            }
+
+            let expected = RecoverableSignature {
+                sig: vartime.normalize_s(),
+                recid,
+            };
+            assert_eq!(key.sign_recoverable(&z), expected);
+        }
+    }
+
+    // eigenvalue of the endomorphism, lambda^3 = 1 mod n
+    fn glv_lambda() -> Integer {
+        Integer::from_str_radix(
+            "5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72",
+            16,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_glv_split() {
+        let n = order();
+        let lambda = glv_lambda();
+
+        // lambda*G is the endomorphism applied to G
+        let g = S256Point::get_generator();
+        let Finite(gx) = g.point.x.clone() else {
+            panic!()
+        };
+        let endo = &g * lambda.clone();
+        assert_eq!(endo.point.x, Finite(&glv().beta * &gx));
+        assert_eq!(endo.point.y, g.point.y);
+
+        let mut scalars = vec![Integer::new(), Integer::from(1), (&n - 1u8).complete()];
+        scalars.extend((0..16).map(|i| test_key(i).secret.as_integer().clone()));
+        for k in scalars {
+            let (k1, k2) = glv_split(&k);
+            assert!(k1.significant_bits() <= 129 && k2.significant_bits() <= 129);
+            let mut sum = k1 + k2 * &lambda;
+            sum.rem_euc_assign(&n);
+            assert_eq!(sum, k);
+        }
+    }
+
+    #[test]
+    fn test_mul_vartime_matches_plain_multiplication() {
+        let n = order();
+        let infinity = S256Point::new(Infinity, Infinity);
+        let mut rand = RandState::new();
+        rand.seed(&Integer::from(132));
+
+        let points: Vec<S256Point> = (0..4)
+            .map(|i| S256Point::from(test_key(i).public_key()))
+            .collect();
+        for point in &points {
+            for k in [
+                Integer::from(1),
+                Integer::from(2),
+                (&n - 1u8).complete(),
+                glv_lambda(),
+            ] {
+                assert_eq!(point.mul_vartime(&k), point * k.clone(), "{k:x}");
+            }
+            assert_eq!(point.mul_vartime(&Integer::new()), infinity);
+            assert_eq!(point.mul_vartime(&n), infinity);
+
+            for _ in 0..16 {
+                let k = n.clone().random_below(&mut rand);
+                assert_eq!(point.mul_vartime(&k), point * k.clone(), "{k:x}");
+            }
+        }
+        assert_eq!(infinity.mul_vartime(&Integer::from(7)), infinity);
+    }
+
+    // the pre-multi_mul formula, u*G + v*P as two multiplications and an addition
+    fn mul_add_g_reference(point: &S256Point, u: &Integer, v: &Integer) -> S256Point {
+        &g_mul(u) + &(point * v.clone())
+    }
+
+    #[test]
+    fn test_verify_matches_separate_multiplications() {
+        let n = order();
+        let mut rand = RandState::new();
+        rand.seed(&Integer::from(142));
+
+        for i in 0..12 {
+            let key = test_key(i);
+            let point = S256Point::from(key.public_key());
+            let u = n.clone().random_below(&mut rand);
+            let v = n.clone().random_below(&mut rand);
+            assert_eq!(
+                point.mul_add_g(&u, &v),
+                mul_add_g_reference(&point, &u, &v),
+                "{u:x} {v:x}"
+            );
+
+            let z = n.clone().random_below(&mut rand);
+            let sig = key.sign(&z);
+            assert!(point.verify(&z, &sig));
+            let other = (&z + 1u8).complete();
+            assert!(!point.verify(&other, &sig));
+        }
+
+        // edge scalars, including ones whose sum is the point at infinity
+        let point = S256Point::from(test_key(0).public_key());
+        let k = test_key(0).secret.as_integer().clone();
+        let minus_one = (&n - 1u8).complete();
+        let minus_k = (&n - &k).complete();
+        for (u, v) in [
+            (Integer::new(), Integer::from(1)),
+            (Integer::from(1), Integer::new()),
+            (minus_one.clone(), minus_one),
+            (minus_k, Integer::from(1)),
+        ] {
+            assert_eq!(point.mul_add_g(&u, &v), mul_add_g_reference(&point, &u, &v));
+        }
+    }
+}
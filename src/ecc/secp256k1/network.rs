@@ -0,0 +1,145 @@
+use crate::encoding::bech32::{self, Bech32Error};
+
+// The version bytes and bech32 prefix that tell one chain's keys and addresses from another's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkParams {
+    pub p2pkh_prefix: u8,
+    pub p2sh_prefix: u8,
+    pub wif_prefix: u8,
+    pub hrp: &'static str,
+}
+
+// Bitcoin's networks, plus Custom for private chains with their own parameters. Testnet, Regtest
+// and Signet share every Base58 prefix and Testnet and Signet share "tb", so decoding can only
+// tell them apart by the bech32 prefix "bcrt" and otherwise reports Testnet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    Regtest,
+    Signet,
+    Custom(NetworkParams),
+}
+
+impl Network {
+    // the networks decoding looks for, in the order ties are resolved
+    pub const STANDARD: [Network; 4] = [
+        Network::Mainnet,
+        Network::Testnet,
+        Network::Regtest,
+        Network::Signet,
+    ];
+
+    pub fn params(&self) -> NetworkParams {
+        match self {
+            Network::Mainnet => NetworkParams {
+                p2pkh_prefix: 0x00,
+                p2sh_prefix: 0x05,
+                wif_prefix: 0x80,
+                hrp: "bc",
+            },
+            Network::Testnet | Network::Signet => NetworkParams {
+                p2pkh_prefix: 0x6f,
+                p2sh_prefix: 0xc4,
+                wif_prefix: 0xef,
+                hrp: "tb",
+            },
+            Network::Regtest => NetworkParams {
+                hrp: "bcrt",
+                ..Network::Testnet.params()
+            },
+            Network::Custom(params) => *params,
+        }
+    }
+
+    pub fn from_p2pkh_prefix(prefix: u8) -> Option<Network> {
+        Self::find(|params| params.p2pkh_prefix == prefix)
+    }
+
+    pub fn from_p2sh_prefix(prefix: u8) -> Option<Network> {
+        Self::find(|params| params.p2sh_prefix == prefix)
+    }
+
+    pub fn from_wif_prefix(prefix: u8) -> Option<Network> {
+        Self::find(|params| params.wif_prefix == prefix)
+    }
+
+    // bech32 prefixes are case-insensitive
+    pub fn from_hrp(hrp: &str) -> Option<Network> {
+        Self::find(|params| params.hrp.eq_ignore_ascii_case(hrp))
+    }
+
+    fn find(matches: impl Fn(&NetworkParams) -> bool) -> Option<Network> {
+        Self::STANDARD
+            .into_iter()
+            .find(|network| matches(&network.params()))
+    }
+}
+
+// a segwit address on any standard network, returned with the network, witness version and program
+pub fn decode_segwit_address(address: &str) -> Result<(Network, u8, Vec<u8>), Bech32Error> {
+    let (hrp, _, _) = bech32::decode(address)?;
+    let network = Network::from_hrp(&hrp).ok_or(Bech32Error::InvalidHrp)?;
+    let (version, program) = bech32::decode_segwit(&hrp, address)?;
+    Ok((network, version, program))
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_lookups() {
+        assert_eq!(Network::from_p2pkh_prefix(0x00), Some(Network::Mainnet));
+        assert_eq!(Network::from_p2sh_prefix(0xc4), Some(Network::Testnet));
+        assert_eq!(Network::from_wif_prefix(0xef), Some(Network::Testnet));
+        assert_eq!(Network::from_wif_prefix(0x00), None);
+        assert_eq!(Network::from_hrp("BCRT"), Some(Network::Regtest));
+        assert_eq!(Network::from_hrp("tb"), Some(Network::Testnet));
+        assert_eq!(Network::from_hrp("ltc"), None);
+
+        // custom networks are never detected, only used when asked for
+        let litecoin = NetworkParams {
+            p2pkh_prefix: 0x30,
+            p2sh_prefix: 0x32,
+            wif_prefix: 0xb0,
+            hrp: "ltc",
+        };
+        assert_eq!(Network::Custom(litecoin).params(), litecoin);
+        assert_eq!(Network::from_p2pkh_prefix(0x30), None);
+    }
+
+    #[test]
+    fn test_decode_segwit_address() {
+        // BIP173's P2WPKH example on mainnet, testnet and regtest
+        let program = "751e76e8199196d454941c45d1b3a323f1433bd6";
+        for (address, network) in [
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                Network::Mainnet,
+            ),
+            (
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+                Network::Testnet,
+            ),
+            (
+                "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+                Network::Regtest,
+            ),
+        ] {
+            let (found, version, decoded) = decode_segwit_address(address).unwrap();
+            assert_eq!((found, version), (network, 0));
+            assert_eq!(crate::encoding::hex::encode(&decoded), program);
+        }
+        assert_eq!(
+            decode_segwit_address("ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9"),
+            Err(Bech32Error::InvalidHrp)
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use super::{Secp256k1Error, ct, order, to_bytes32, wipe};
+use crate::ecc::field::sample_below;
+use rand_core::{CryptoRng, RngCore};
+use rug::integer::Order;
+use rug::ops::RemRoundingAssign;
+use rug::{Complete, Integer};
+use std::fmt::{Debug, Display, LowerHex};
+use std::ops::{Add, Mul, Neg, Sub};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+// What from_bytes_be does with a 32-byte value that is >= n
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Reject,
+    Reduce,
+}
+
+// An integer mod n, the order of G. Keys, nonces and signature components live here rather
+// than in a FieldElement, so they can't be mixed up with coordinates mod p
+#[derive(Clone, Default)]
+pub struct Scalar {
+    value: Integer,
+}
+
+impl Scalar {
+    // reduces any integer, including negative ones, into [0, n)
+    pub fn new(value: Integer) -> Self {
+        let mut value = value;
+        value.rem_euc_assign(&order());
+        Self { value }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self {
+            value: Integer::from(1),
+        }
+    }
+
+    // uniform in [0, n), by rejection sampling rather than reducing 32 random bytes mod n
+    pub fn random<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        let (value, _) = sample_below(rng, &order());
+        Self { value }
+    }
+
+    // uniform in [1, n), what nonces and blinding factors need
+    pub fn random_nonzero<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        let (value, _) = sample_below(rng, &(order() - 1u8));
+        Self { value: value + 1u8 }
+    }
+
+    pub fn from_bytes_be(bytes: &[u8; 32], overflow: Overflow) -> Result<Self, Secp256k1Error> {
+        let value = Integer::from_digits(bytes, Order::Msf);
+        if overflow == Overflow::Reject && value >= order() {
+            return Err(Secp256k1Error::ScalarOverflow);
+        }
+        Ok(Self::new(value))
+    }
+
+    pub fn to_bytes32(&self) -> [u8; 32] {
+        to_bytes32(&self.value)
+    }
+
+    pub fn as_integer(&self) -> &Integer {
+        &self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    // above n/2, the half that low-S signatures avoid
+    pub fn is_high(&self) -> bool {
+        self.value > order() >> 1u32
+    }
+
+    // Fermat inversion through the constant-time path, None for zero
+    pub fn invert(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(Self {
+            value: ct::inv_mod_n(&self.value),
+        })
+    }
+
+    // compares the fixed 32-byte encodings without stopping at the first difference. Named so
+    // it doesn't shadow subtle's ct_eq, which returns a Choice, under the ct feature
+    pub fn ct_equals(&self, other: &Self) -> bool {
+        self.ct_eq_choice(other).into()
+    }
+
+    // CT-REVIEW: the one place Scalars are compared
+    fn ct_eq_choice(&self, other: &Self) -> Choice {
+        let mut lhs = self.to_bytes32();
+        let mut rhs = other.to_bytes32();
+        let eq = lhs[..].ct_eq(&rhs[..]);
+        lhs.zeroize();
+        rhs.zeroize();
+        eq
+    }
+}
+
+#[cfg(feature = "ct")]
+impl ConstantTimeEq for Scalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.ct_eq_choice(other)
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_equals(other)
+    }
+}
+
+impl Eq for Scalar {}
+
+// the value may be a key or a nonce, so like PrivateKey it is never printed by Debug. Display
+// and LowerHex still show it for the places that mean to
+impl Debug for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Scalar(REDACTED)")
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        wipe(&mut self.value);
+    }
+}
+
+impl Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:064x}", self.value)
+    }
+}
+
+impl LowerHex for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.value, f)
+    }
+}
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Self::Output {
+        Scalar::new((-&self.value).complete())
+    }
+}
+
+impl Add<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn add(self, rhs: &Scalar) -> Self::Output {
+        Scalar::new((&self.value + &rhs.value).complete())
+    }
+}
+
+impl Sub<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn sub(self, rhs: &Scalar) -> Self::Output {
+        Scalar::new((&self.value - &rhs.value).complete())
+    }
+}
+
+impl Mul<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn mul(self, rhs: &Scalar) -> Self::Output {
+        Scalar::new((&self.value * &rhs.value).complete())
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(hex: &str) -> Scalar {
+        Scalar::new(Integer::from_str_radix(hex, 16).unwrap())
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let n = order();
+        let a = scalar("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140");
+        let b = Scalar::new(Integer::from(5));
+
+        assert_eq!(&a + &b, Scalar::new(Integer::from(4)));
+        assert_eq!(&b - &a, Scalar::new(Integer::from(6)));
+        assert_eq!(&a * &a, Scalar::one());
+        assert_eq!(-&b, Scalar::new((&n - 5u8).complete()));
+        assert_eq!(-&Scalar::zero(), Scalar::zero());
+        assert_eq!(Scalar::new(n.clone()), Scalar::zero());
+        assert_eq!(Scalar::new(Integer::from(-1)), a);
+    }
+
+    #[test]
+    fn test_invert_matches_fermat() {
+        let n = order();
+        let exp = (&n - 2u8).complete();
+        for hex in [
+            "1",
+            "2",
+            "deadbeef",
+            "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+        ] {
+            let k = scalar(hex);
+            let fermat = k.as_integer().pow_mod_ref(&exp, &n).unwrap().complete();
+            let inverse = k.invert().unwrap();
+            assert_eq!(inverse.as_integer(), &fermat);
+            assert_eq!(&inverse * &k, Scalar::one());
+        }
+        assert_eq!(Scalar::zero().invert(), None);
+    }
+
+    #[test]
+    fn test_from_bytes_overflow() {
+        let n = to_bytes32(&order());
+        let mut n_plus_one = n;
+        n_plus_one[31] += 1;
+
+        for bytes in [n, n_plus_one, [0xff; 32]] {
+            assert_eq!(
+                Scalar::from_bytes_be(&bytes, Overflow::Reject),
+                Err(Secp256k1Error::ScalarOverflow)
+            );
+        }
+        assert_eq!(
+            Scalar::from_bytes_be(&n, Overflow::Reduce),
+            Ok(Scalar::zero())
+        );
+        assert_eq!(
+            Scalar::from_bytes_be(&n_plus_one, Overflow::Reduce),
+            Ok(Scalar::one())
+        );
+
+        let mut below = n;
+        below[31] -= 1;
+        for overflow in [Overflow::Reject, Overflow::Reduce] {
+            let k = Scalar::from_bytes_be(&below, overflow).unwrap();
+            assert_eq!(k.to_bytes32(), below);
+        }
+    }
+
+    #[test]
+    fn test_ct_eq_and_high() {
+        let half = Scalar::new(order() >> 1u32);
+        let above = &half + &Scalar::one();
+        assert!(half.ct_equals(&half.clone()));
+        assert!(!half.ct_equals(&above));
+        assert!(!half.is_high());
+        assert!(above.is_high());
+        assert_eq!(format!("{}", Scalar::one()).len(), 64);
+        assert_eq!(format!("{half:?}"), "Scalar(REDACTED)");
+    }
+
+    #[test]
+    fn test_random_scalars() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(177);
+        let draws: Vec<Scalar> = (0..8).map(|_| Scalar::random(&mut rng)).collect();
+        assert!(draws.iter().all(|k| k.value < order()));
+        assert!(draws.windows(2).all(|w| w[0] != w[1]));
+        assert!((0..8).all(|_| !Scalar::random_nonzero(&mut rng).is_zero()));
+    }
+
+    #[cfg(feature = "ct")]
+    #[test]
+    fn test_subtle_ct_eq_matches_eq() {
+        let a = Scalar::new(Integer::from(5));
+        let b = Scalar::new(order() + 5u8);
+        let c = Scalar::new(Integer::from(6));
+        // method syntax resolves to subtle's ct_eq, not a bool
+        let eq: Choice = a.ct_eq(&b);
+        assert!(bool::from(eq));
+        assert!(!bool::from(a.ct_eq(&c)));
+    }
+}
@@ -0,0 +1,280 @@
+use super::ct::{g_mul_ct, select_scalar};
+use super::hash::tagged_hash;
+use super::{GLV_WIDTH, Overflow, PrivateKey, S256Point, Scalar, g_mul, order, prime};
+use crate::ecc::curve::{EcPoint, mul_interleaved};
+use rand_core::{CryptoRng, RngCore};
+use rug::Integer;
+use rug::integer::Order;
+use subtle::Choice;
+use zeroize::Zeroize;
+
+fn int_mod_n(bytes: &[u8; 32]) -> Scalar {
+    Scalar::from_bytes_be(bytes, Overflow::Reduce).expect("reducing never fails")
+}
+
+// e = hash_BIP0340/challenge(R.x || P.x || msg) mod n
+fn challenge(r: &[u8], p: &[u8; 32], msg: &[u8]) -> Scalar {
+    int_mod_n(&tagged_hash(
+        "BIP0340/challenge",
+        &[r, &p[..], msg].concat(),
+    ))
+}
+
+// BIP340 signing: the key and the nonce are both negated as needed so that P and R have even y
+pub fn sign(key: &PrivateKey, msg: &[u8], aux_rand: &[u8; 32]) -> [u8; 64] {
+    let key = key.for_even_y();
+    let p = key.public_key().point().xonly();
+    let d = &key.secret;
+
+    let mut t = d.to_bytes32();
+    let aux_hash = tagged_hash("BIP0340/aux", aux_rand);
+    for (byte, mask) in t.iter_mut().zip(aux_hash) {
+        *byte ^= mask;
+    }
+
+    let mut nonce_input = [&t, &p[..], msg].concat();
+    let mut k0 = int_mod_n(&tagged_hash("BIP0340/nonce", &nonce_input));
+    t.zeroize();
+    nonce_input.zeroize();
+    assert!(!k0.is_zero(), "Error: BIP340 nonce is zero, cannot sign");
+    let big_r = g_mul_ct(k0.as_integer());
+    // CT-REVIEW: the parity of R depends on the nonce, so k and -k are both computed
+    let mut neg_k0 = -&k0;
+    let mut k = select_scalar(&k0, &neg_k0, Choice::from(!big_r.has_even_y() as u8));
+    k0.zeroize();
+    neg_k0.zeroize();
+
+    let r_bytes = big_r.xonly();
+    let e = challenge(&r_bytes, &p, msg);
+    let s = &(&e * d) + &k;
+    k.zeroize();
+
+    let mut sig = [0u8; 64];
+    sig[..32].copy_from_slice(&r_bytes);
+    sig[32..].copy_from_slice(&s.to_bytes32());
+    sig
+}
+
+// BIP340 verification: R = s*G - e*P must have even y and x equal to sig[0..32]
+pub fn verify(pubkey: &[u8; 32], msg: &[u8], sig: &[u8; 64]) -> bool {
+    let n = order();
+    let p = match S256Point::from_xonly(pubkey) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+    let r = Integer::from_digits(&sig[..32], Order::Msf);
+    let s = Integer::from_digits(&sig[32..], Order::Msf);
+    if r >= prime() || s >= n {
+        return false;
+    }
+
+    let e = challenge(&sig[..32], pubkey, msg);
+    let big_r = &g_mul(&s) + &(&p * (-&e).as_integer().clone());
+    if big_r.point.x == super::Infinity || !big_r.has_even_y() {
+        return false;
+    }
+    big_r.xonly()[..] == sig[..32]
+}
+
+// BIP340 batch verification of (public key, message, signature) items, true only if all of them
+// verify. With a_1 = 1 and random nonzero a_2..a_u it checks
+// (sum a_i*s_i)*G - sum a_i*R_i - sum a_i*e_i*P_i = Infinity as one multi-scalar multiplication,
+// where R_i is the even-y lift of r_i. A batch holding an invalid signature passes with
+// probability about 1/n over the choice of coefficients. Which item failed isn't reported, verify
+// them one by one to find out
+pub fn verify_batch<R: CryptoRng + RngCore>(
+    items: &[([u8; 32], [u8; 32], [u8; 64])],
+    rng: &mut R,
+) -> bool {
+    let n = order();
+    let mut s_sum = Scalar::zero();
+    let mut points = Vec::with_capacity(2 * items.len() + 1);
+    let mut scalars = Vec::with_capacity(2 * items.len() + 1);
+    for (i, (pubkey, msg, sig)) in items.iter().enumerate() {
+        let r_bytes: &[u8; 32] = sig[..32].try_into().expect("a 64 byte signature has r");
+        // from_xonly rejects x >= p as well as x not on the curve
+        let (Ok(p), Ok(big_r)) = (
+            S256Point::from_xonly(pubkey),
+            S256Point::from_xonly(r_bytes),
+        ) else {
+            return false;
+        };
+        let s = Integer::from_digits(&sig[32..], Order::Msf);
+        if s >= n {
+            return false;
+        }
+
+        let a = if i == 0 {
+            Scalar::one()
+        } else {
+            Scalar::random_nonzero(rng)
+        };
+        let e = challenge(r_bytes, pubkey, msg);
+        s_sum = &s_sum + &(&a * &Scalar::new(s));
+        points.push(big_r.point);
+        scalars.push(-&a);
+        points.push(p.point);
+        scalars.push(-&(&a * &e));
+    }
+    points.push(S256Point::get_generator().point);
+    scalars.push(s_sum);
+
+    let terms: Vec<(&EcPoint, Integer)> = points
+        .iter()
+        .zip(scalars)
+        .map(|(point, k)| (point, k.as_integer().clone()))
+        .collect();
+    mul_interleaved(&terms, GLV_WIDTH).x == super::Infinity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/vectors/bip340_vectors.csv"
+    ));
+
+    fn decode_hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_bip340_vectors() {
+        for line in VECTORS.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            let index = fields[0];
+            let pubkey: [u8; 32] = decode_hex(fields[2]).try_into().unwrap();
+            let msg = decode_hex(fields[4]);
+            let sig: [u8; 64] = decode_hex(fields[5]).try_into().unwrap();
+            let expected = fields[6] == "TRUE";
+
+            if !fields[1].is_empty() {
+                let key = PrivateKey::new(Integer::from_str_radix(fields[1], 16).unwrap());
+                let aux_rand: [u8; 32] = decode_hex(fields[3]).try_into().unwrap();
+                assert_eq!(key.public_key().point().xonly(), pubkey, "vector {index}");
+                assert_eq!(sign(&key, &msg, &aux_rand), sig, "vector {index}");
+            }
+            assert_eq!(verify(&pubkey, &msg, &sig), expected, "vector {index}");
+        }
+    }
+
+    // valid signatures from keys 1..=count over distinct messages
+    fn batch_items(count: u64) -> Vec<([u8; 32], [u8; 32], [u8; 64])> {
+        (1..=count)
+            .map(|i| {
+                let key = PrivateKey::new(Integer::from(i) * 0x1234_5678u32);
+                let msg = tagged_hash("batch", &i.to_be_bytes());
+                let sig = sign(&key, &msg, &[i as u8; 32]);
+                (key.public_key().point().xonly(), msg, sig)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(206);
+        let items = batch_items(8);
+        assert!(items.iter().all(|(p, msg, sig)| verify(p, msg, sig)));
+        assert!(verify_batch(&items, &mut rng));
+        assert!(verify_batch(&items[..1], &mut rng));
+        assert!(verify_batch(&[], &mut rng));
+
+        // one flipped bit anywhere in any single signature sinks the batch, whatever the
+        // coefficients. Bits of r usually make it unliftable, bits of s always reach the sum
+        for i in 0..items.len() {
+            for bit in [0, 255, 256, 511] {
+                let mut bad = items.clone();
+                bad[i].2[bit / 8] ^= 1 << (bit % 8);
+                for _ in 0..4 {
+                    assert!(!verify_batch(&bad, &mut rng), "item {i}, bit {bit}");
+                }
+            }
+        }
+
+        // a signature moved to another message or key
+        let mut swapped = items.clone();
+        swapped[3].1 = items[4].1;
+        assert!(!verify_batch(&swapped, &mut rng));
+        let mut swapped = items.clone();
+        swapped[0].0 = items[1].0;
+        assert!(!verify_batch(&swapped, &mut rng));
+    }
+
+    #[test]
+    fn test_verify_batch_malformed() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(206);
+        let items = batch_items(4);
+
+        // x = 5 is not on the curve, x = p is out of range
+        let p_bytes: [u8; 32] = prime().to_digits::<u8>(Order::Msf).try_into().unwrap();
+        let mut not_on_curve = [0u8; 32];
+        not_on_curve[31] = 5;
+        assert!(S256Point::from_xonly(&not_on_curve).is_err());
+        for bad_x in [not_on_curve, p_bytes] {
+            let mut bad_key = items.clone();
+            bad_key[2].0 = bad_x;
+            assert!(!verify_batch(&bad_key, &mut rng));
+            let mut bad_r = items.clone();
+            bad_r[2].2[..32].copy_from_slice(&bad_x);
+            assert!(!verify_batch(&bad_r, &mut rng));
+        }
+        // s = n
+        let mut bad_s = items.clone();
+        bad_s[1].2[32..].copy_from_slice(&order().to_digits::<u8>(Order::Msf));
+        assert!(!verify_batch(&bad_s, &mut rng));
+    }
+
+    #[test]
+    fn test_verify_batch_bip340_vectors() {
+        use rand_core::SeedableRng;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(206);
+        let base = batch_items(3);
+        for line in VECTORS.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            // the batch form only takes 32 byte messages
+            let Ok(msg) = <[u8; 32]>::try_from(decode_hex(fields[4])) else {
+                continue;
+            };
+            let item = (
+                decode_hex(fields[2]).try_into().unwrap(),
+                msg,
+                decode_hex(fields[5]).try_into().unwrap(),
+            );
+            let mut items = base.clone();
+            items.insert(1, item);
+            let expected = fields[6] == "TRUE";
+            assert_eq!(
+                verify_batch(&items, &mut rng),
+                expected,
+                "vector {}",
+                fields[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_verify_round_trip() {
+        let key = PrivateKey::new(Integer::from(0xdeadbeefu32));
+        let pubkey = key.public_key().point().xonly();
+        let msg = [0x42u8; 32];
+        let sig = sign(&key, &msg, &[7u8; 32]);
+        assert!(verify(&pubkey, &msg, &sig));
+
+        let mut tampered = sig;
+        tampered[63] ^= 1;
+        assert!(!verify(&pubkey, &msg, &tampered));
+        assert!(!verify(&pubkey, &[0x43u8; 32], &sig));
+
+        // different aux randomness gives a different but equally valid signature
+        let other = sign(&key, &msg, &[8u8; 32]);
+        assert_ne!(other, sig);
+        assert!(verify(&pubkey, &msg, &other));
+    }
+}
@@ -0,0 +1,154 @@
+// serde support, everything as a lowercase hex string: points as compressed SEC, signatures as
+// DER and private keys as 32 bytes. Deserializing goes through the same validating parsers as
+// the rest of the API, so corrupt data is an error rather than an invalid value.
+// Private keys only (de)serialize with the serde_secrets feature, so a key can't end up in a
+// log or config file just because a struct holding it derives Serialize
+use super::{ParsePolicy, S256Point, Signature};
+use crate::encoding::hex;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+fn to_hex(bytes: &[u8]) -> String {
+    hex::encode(bytes)
+}
+
+fn from_hex<E: Error>(value: &str) -> Result<Vec<u8>, E> {
+    hex::decode(value).map_err(E::custom)
+}
+
+impl Serialize for S256Point {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(&self.sec(true)))
+    }
+}
+
+// compressed or uncompressed SEC, anything else is rejected by S256Point::parse
+impl<'de> Deserialize<'de> for S256Point {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = from_hex(&String::deserialize(deserializer)?)?;
+        S256Point::parse(&bytes, ParsePolicy::Strict).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(&self.der()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = from_hex(&String::deserialize(deserializer)?)?;
+        Signature::parse_der(&bytes).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde_secrets")]
+mod secrets {
+    use super::{from_hex, to_hex};
+    use crate::ecc::secp256k1::PrivateKey;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use zeroize::Zeroize;
+
+    impl Serialize for PrivateKey {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut bytes = self.secret.to_bytes32();
+            let mut hex = to_hex(&bytes);
+            let result = serializer.serialize_str(&hex);
+            bytes.zeroize();
+            hex.zeroize();
+            result
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PrivateKey {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut hex = String::deserialize(deserializer)?;
+            let mut bytes = from_hex::<D::Error>(&hex)?;
+            hex.zeroize();
+            let key = <[u8; 32]>::try_from(bytes.as_slice())
+                .map_err(|_| D::Error::custom("expected a 32-byte private key"))
+                .and_then(|secret| PrivateKey::from_bytes(&secret).map_err(D::Error::custom));
+            bytes.zeroize();
+            key
+        }
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::secp256k1::PrivateKey;
+    use rug::Integer;
+
+    fn test_key() -> PrivateKey {
+        PrivateKey::new(Integer::from(0xdead_beefu32))
+    }
+
+    #[test]
+    fn test_point_round_trip() {
+        let point = S256Point::from(test_key().public_key());
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(json, format!("\"{}\"", to_hex(&point.sec(true))));
+        assert_eq!(serde_json::from_str::<S256Point>(&json).unwrap(), point);
+
+        // uncompressed SEC is accepted too
+        let uncompressed = format!("\"{}\"", to_hex(&point.sec(false)));
+        assert_eq!(
+            serde_json::from_str::<S256Point>(&uncompressed).unwrap(),
+            point
+        );
+    }
+
+    #[test]
+    fn test_point_rejects_invalid() {
+        let mut off_curve = S256Point::get_generator().sec(false);
+        off_curve[64] ^= 1;
+        let json = format!("\"{}\"", to_hex(&off_curve));
+        let err = serde_json::from_str::<S256Point>(&json).unwrap_err();
+        assert!(
+            err.to_string().contains("not on the secp256k1 curve"),
+            "{err}"
+        );
+
+        for bad in ["\"02\"", "\"zz\"", "\"0\"", "\"\""] {
+            assert!(serde_json::from_str::<S256Point>(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let sig = test_key().sign(&Integer::from(1234));
+        let json = serde_json::to_string(&sig).unwrap();
+        assert_eq!(json, format!("\"{}\"", to_hex(&sig.der())));
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), sig);
+
+        // DER with a trailing byte
+        let mut der = sig.der();
+        der.push(0);
+        let json = format!("\"{}\"", to_hex(&der));
+        assert!(serde_json::from_str::<Signature>(&json).is_err());
+    }
+
+    #[cfg(feature = "serde_secrets")]
+    #[test]
+    fn test_private_key_round_trip() {
+        let key = test_key();
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, format!("\"{}\"", key.reveal_hex()));
+        assert_eq!(serde_json::from_str::<PrivateKey>(&json).unwrap(), key);
+
+        // zero, n and a short key are all rejected
+        let n = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+        for bad in [&"00".repeat(32), n, "01"] {
+            let json = format!("\"{bad}\"");
+            assert!(serde_json::from_str::<PrivateKey>(&json).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use super::{S256Point, Secp256k1Error};
+use crate::encoding::base64;
+
+// SubjectPublicKeyInfo (RFC 5480), the public key format OpenSSL reads and writes:
+//
+//     SEQUENCE {
+//         SEQUENCE { OID id-ecPublicKey, OID secp256k1 }
+//         BIT STRING { SEC point }
+//     }
+//
+// Everything here is shorter than 128 bytes, so only short-form lengths are accepted
+
+// 1.2.840.10045.2.1
+const ID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+// 1.3.132.0.10
+const SECP256K1_OID: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+const PEM_BEGIN: &str = "-----BEGIN PUBLIC KEY-----";
+const PEM_END: &str = "-----END PUBLIC KEY-----";
+
+fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut result = vec![tag, value.len() as u8];
+    result.extend_from_slice(value);
+    result
+}
+
+// the value of the element with this tag at the start of bytes, and what follows it
+fn read_tlv(bytes: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Secp256k1Error> {
+    match bytes {
+        [t, len, rest @ ..] if *t == tag && *len < 0x80 && rest.len() >= *len as usize => {
+            Ok(rest.split_at(*len as usize))
+        }
+        _ => Err(Secp256k1Error::InvalidSpki),
+    }
+}
+
+impl S256Point {
+    pub fn to_spki_der(&self, compressed: bool) -> Vec<u8> {
+        let algorithm = [tlv(0x06, ID_EC_PUBLIC_KEY), tlv(0x06, SECP256K1_OID)].concat();
+        // the leading 0 is the BIT STRING's count of unused bits
+        let key = [&[0x00][..], &self.sec(compressed)].concat();
+        tlv(0x30, &[tlv(0x30, &algorithm), tlv(0x03, &key)].concat())
+    }
+
+    // the SEC point inside is parsed strictly, compressed or uncompressed
+    pub fn from_spki_der(bytes: &[u8]) -> Result<Self, Secp256k1Error> {
+        let (spki, rest) = read_tlv(bytes, 0x30)?;
+        if !rest.is_empty() {
+            return Err(Secp256k1Error::InvalidSpki);
+        }
+        let (algorithm, rest) = read_tlv(spki, 0x30)?;
+        let (key, rest) = read_tlv(rest, 0x03)?;
+        if !rest.is_empty() {
+            return Err(Secp256k1Error::InvalidSpki);
+        }
+
+        let (oid, params) = read_tlv(algorithm, 0x06)?;
+        if oid != ID_EC_PUBLIC_KEY {
+            return Err(Secp256k1Error::UnsupportedKeyAlgorithm);
+        }
+        // explicit curve parameters (a SEQUENCE) are not supported, only the named curve
+        match read_tlv(params, 0x06) {
+            Ok((curve, [])) if curve == SECP256K1_OID => {}
+            _ => return Err(Secp256k1Error::UnsupportedCurve),
+        }
+
+        match key {
+            [0x00, sec @ ..] => Self::from_sec(sec),
+            _ => Err(Secp256k1Error::InvalidSpki),
+        }
+    }
+
+    // to_spki_der as a PEM PUBLIC KEY block, base64 in lines of 64
+    pub fn to_spki_pem(&self, compressed: bool) -> String {
+        pem_encode(&self.to_spki_der(compressed))
+    }
+
+    pub fn from_spki_pem(pem: &str) -> Result<Self, Secp256k1Error> {
+        Self::from_spki_der(&pem_decode(pem)?)
+    }
+}
+
+pub fn pem_encode(der: &[u8]) -> String {
+    let body = base64::encode(der);
+    let mut result = format!("{PEM_BEGIN}\n");
+    for line in body.as_bytes().chunks(64) {
+        result.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        result.push('\n');
+    }
+    result.push_str(PEM_END);
+    result.push('\n');
+    result
+}
+
+// the DER inside a PUBLIC KEY block. Whitespace around the markers and inside the base64 is
+// ignored, other PEM labels and text outside the block are not
+pub fn pem_decode(pem: &str) -> Result<Vec<u8>, Secp256k1Error> {
+    let body = pem
+        .trim()
+        .strip_prefix(PEM_BEGIN)
+        .and_then(|rest| rest.strip_suffix(PEM_END))
+        .ok_or(Secp256k1Error::InvalidPem)?;
+    let body: String = body.split_whitespace().collect();
+    base64::decode(&body).map_err(|_| Secp256k1Error::InvalidPem)
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::secp256k1::PrivateKey;
+    use rug::Integer;
+
+    // made with
+    //     openssl ecparam -name secp256k1 -genkey -noout -out key.pem
+    //     openssl ec -in key.pem -pubout -outform DER -out secp256k1_spki.der
+    //     openssl ec -in key.pem -pubout -out secp256k1_spki.pem
+    const OPENSSL_DER: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/vectors/secp256k1_spki.der"
+    ));
+    const OPENSSL_PEM: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/vectors/secp256k1_spki.pem"
+    ));
+    const OPENSSL_SECRET: &str = "394649a7f5f8702b44ac9069f28dce45c4bea2d7b854fbc726fe418c9198c05e";
+
+    fn openssl_key() -> S256Point {
+        PrivateKey::new(Integer::from_str_radix(OPENSSL_SECRET, 16).unwrap())
+            .public_key()
+            .into()
+    }
+
+    #[test]
+    fn test_openssl_fixture() {
+        let expected = openssl_key();
+        assert_eq!(S256Point::from_spki_der(OPENSSL_DER), Ok(expected.clone()));
+        assert_eq!(S256Point::from_spki_pem(OPENSSL_PEM), Ok(expected.clone()));
+        // OpenSSL writes the uncompressed form by default
+        assert_eq!(expected.to_spki_der(false), OPENSSL_DER);
+        assert_eq!(expected.to_spki_pem(false), OPENSSL_PEM);
+    }
+
+    #[test]
+    fn test_spki_round_trip() {
+        let point = openssl_key();
+        for compressed in [true, false] {
+            let der = point.to_spki_der(compressed);
+            assert_eq!(der.len(), if compressed { 56 } else { 88 });
+            assert_eq!(S256Point::from_spki_der(&der), Ok(point.clone()));
+            let pem = point.to_spki_pem(compressed);
+            assert_eq!(S256Point::from_spki_pem(&pem), Ok(point.clone()));
+        }
+        let g = S256Point::get_generator();
+        assert_eq!(S256Point::from_spki_der(&g.to_spki_der(true)), Ok(g));
+    }
+
+    #[test]
+    fn test_spki_rejects_other_curves_and_algorithms() {
+        let der = openssl_key().to_spki_der(true);
+        let curve_at = der.len() - 36 - SECP256K1_OID.len();
+        assert_eq!(&der[curve_at..curve_at + 5], SECP256K1_OID);
+
+        // secp256r1 is 1.2.840.10045.3.1.7, 8 bytes instead of 5
+        let p256_oid = [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+        let algorithm = [tlv(0x06, ID_EC_PUBLIC_KEY), tlv(0x06, &p256_oid)].concat();
+        let p256 = tlv(
+            0x30,
+            &[tlv(0x30, &algorithm), tlv(0x03, &der[der.len() - 34..])].concat(),
+        );
+        assert_eq!(
+            S256Point::from_spki_der(&p256),
+            Err(Secp256k1Error::UnsupportedCurve)
+        );
+
+        // the secp384r1 OID 1.3.132.0.34 differs from secp256k1 in the last byte only
+        let mut p384 = der.clone();
+        p384[curve_at + 4] = 0x22;
+        assert_eq!(
+            S256Point::from_spki_der(&p384),
+            Err(Secp256k1Error::UnsupportedCurve)
+        );
+
+        // 1.2.840.10045.2.2 in place of id-ecPublicKey
+        let mut not_ec = der.clone();
+        not_ec[12] = 0x02;
+        assert_eq!(
+            S256Point::from_spki_der(&not_ec),
+            Err(Secp256k1Error::UnsupportedKeyAlgorithm)
+        );
+    }
+
+    #[test]
+    fn test_spki_rejects_malformed() {
+        let der = openssl_key().to_spki_der(false);
+        assert_eq!(
+            S256Point::from_spki_der(&der[..der.len() - 1]),
+            Err(Secp256k1Error::InvalidSpki)
+        );
+        assert_eq!(
+            S256Point::from_spki_der(&[der.as_slice(), &[0]].concat()),
+            Err(Secp256k1Error::InvalidSpki)
+        );
+        assert_eq!(
+            S256Point::from_spki_der(&[]),
+            Err(Secp256k1Error::InvalidSpki)
+        );
+
+        // nonzero unused bits in the BIT STRING
+        let mut unused_bits = der.clone();
+        unused_bits[22] = 0x01;
+        assert_eq!(
+            S256Point::from_spki_der(&unused_bits),
+            Err(Secp256k1Error::InvalidSpki)
+        );
+
+        // the SEC point inside still goes through from_sec
+        let mut off_curve = der.clone();
+        *off_curve.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            S256Point::from_spki_der(&off_curve),
+            Err(Secp256k1Error::NotOnCurve)
+        );
+    }
+
+    #[test]
+    fn test_pem_armor() {
+        assert_eq!(pem_decode(OPENSSL_PEM), Ok(OPENSSL_DER.to_vec()));
+        assert!(OPENSSL_PEM.lines().all(|line| line.len() <= 64));
+        assert_eq!(
+            pem_decode(&OPENSSL_PEM.replace("PUBLIC KEY", "EC PRIVATE KEY")),
+            Err(Secp256k1Error::InvalidPem)
+        );
+        assert_eq!(
+            pem_decode(&OPENSSL_PEM.replace('M', "*")),
+            Err(Secp256k1Error::InvalidPem)
+        );
+        // CRLF line endings and surrounding blank lines are fine
+        let crlf = format!("\r\n{}\r\n", OPENSSL_PEM.replace('\n', "\r\n"));
+        assert_eq!(pem_decode(&crlf), Ok(OPENSSL_DER.to_vec()));
+    }
+}
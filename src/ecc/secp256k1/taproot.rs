@@ -0,0 +1,242 @@
+use super::S256Point;
+use super::hash::tagged_hash;
+use crate::encoding::varint;
+
+// BIP341 script path commitments: leaves and branches of the script tree, and the checks a
+// verifier makes to tie a revealed script to an output key
+
+// leaf version of BIP342 tapscript
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+// a control block is the version/parity byte and the internal key, then up to 128 path hashes
+const CONTROL_BLOCK_BASE_SIZE: usize = 33;
+const CONTROL_BLOCK_NODE_SIZE: usize = 32;
+const CONTROL_BLOCK_MAX_NODES: usize = 128;
+
+// hash_TapLeaf(leaf_version || compact_size(script) || script)
+pub fn tap_leaf_hash(leaf_version: u8, script: &[u8]) -> [u8; 32] {
+    let mut data = vec![leaf_version];
+    data.extend(varint::encode(script.len() as u64));
+    data.extend_from_slice(script);
+    tagged_hash("TapLeaf", &data)
+}
+
+// hash_TapBranch of the two children in byte order, so the result doesn't depend on which side
+// of the tree each one is on
+pub fn tap_branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    tagged_hash("TapBranch", &[&first[..], &second[..]].concat())
+}
+
+// the merkle root reached by hashing leaf_hash up through the sibling hashes of a control block
+pub fn merkle_root(leaf_hash: &[u8; 32], path: &[[u8; 32]]) -> [u8; 32] {
+    path.iter()
+        .fold(*leaf_hash, |node, sibling| tap_branch_hash(&node, sibling))
+}
+
+// true when output = internal + hash_TapTweak(internal || merkle_root)*G, and the odd-y flag
+// of the tweaked point equals parity. Keys that don't lift to the curve fail the check
+pub fn verify_commitment(
+    output: &[u8; 32],
+    parity: bool,
+    internal: &[u8; 32],
+    merkle_root: Option<&[u8; 32]>,
+) -> bool {
+    let Ok(internal) = S256Point::from_xonly(internal) else {
+        return false;
+    };
+    match internal.tap_tweak(merkle_root) {
+        Ok((tweaked, odd)) => odd == parity && tweaked.xonly() == *output,
+        Err(_) => false,
+    }
+}
+
+// the script path check for spending output by revealing script: the control block's leaf
+// version (its first byte without the parity bit), internal key and path must commit to it
+pub fn verify_control_block(output: &[u8; 32], script: &[u8], control_block: &[u8]) -> bool {
+    let nodes = control_block.len().wrapping_sub(CONTROL_BLOCK_BASE_SIZE);
+    if control_block.len() < CONTROL_BLOCK_BASE_SIZE
+        || !nodes.is_multiple_of(CONTROL_BLOCK_NODE_SIZE)
+        || nodes / CONTROL_BLOCK_NODE_SIZE > CONTROL_BLOCK_MAX_NODES
+    {
+        return false;
+    }
+    let leaf_version = control_block[0] & 0xfe;
+    let parity = control_block[0] & 1 == 1;
+    let internal: &[u8; 32] = control_block[1..33]
+        .try_into()
+        .expect("length checked above");
+    let path: Vec<[u8; 32]> = control_block[CONTROL_BLOCK_BASE_SIZE..]
+        .chunks_exact(CONTROL_BLOCK_NODE_SIZE)
+        .map(|node| node.try_into().expect("chunks are 32 bytes"))
+        .collect();
+
+    let root = merkle_root(&tap_leaf_hash(leaf_version, script), &path);
+    verify_commitment(output, parity, internal, Some(&root))
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    fn bytes32(value: &str) -> [u8; 32] {
+        hex::decode(value).unwrap().try_into().unwrap()
+    }
+
+    // BIP341 wallet test vectors, scriptPubKey section: (internal key, [(leaf version, script)],
+    // merkle root, output key, control block for each leaf)
+    struct ScriptPathVector {
+        internal: &'static str,
+        leaves: &'static [(u8, &'static str)],
+        merkle_root: &'static str,
+        output: &'static str,
+        control_blocks: &'static [&'static str],
+    }
+
+    const VECTORS: [ScriptPathVector; 3] = [
+        ScriptPathVector {
+            internal: "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+            leaves: &[(
+                0xc0,
+                "20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac",
+            )],
+            merkle_root: "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+            output: "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+            control_blocks: &["c1187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27"],
+        },
+        ScriptPathVector {
+            internal: "93478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820",
+            leaves: &[(
+                0xc0,
+                "20b617298552a72ade070667e86ca63b8f5789a9fe8731ef91202a91c9f3459007ac",
+            )],
+            merkle_root: "c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b",
+            output: "e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e",
+            control_blocks: &["c093478e9488f956df2396be2ce6c5cced75f900dfa18e7dabd2428aae78451820"],
+        },
+        ScriptPathVector {
+            internal: "ee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf3786592",
+            leaves: &[
+                (
+                    0xc0,
+                    "20387671353e273264c495656e27e39ba899ea8fee3bb69fb2a680e22093447d48ac",
+                ),
+                (0xfa, "06424950333431"),
+            ],
+            merkle_root: "6c2dc106ab816b73f9d07e3cd1ef2c8c1256f519748e0813e4edd2405d277bef",
+            output: "712447206d7a5238acc7ff53fbe94a3b64539ad291c7cdbc490b7577e4b17df5",
+            control_blocks: &[
+                "c0ee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf3786592\
+                 f224a923cd0021ab202ab139cc56802ddb92dcfc172b9212261a539df79a112a",
+                "faee4fe085983462a184015d1f782d6a5f8b9c2b60130aff050ce221ecf3786592\
+                 8ad69ec7cf41c2a4001fd1f738bf1e505ce2277acdcaa63fe4765192497f47a7",
+            ],
+        },
+    ];
+
+    #[test]
+    fn test_bip341_script_path_vectors() {
+        for vector in &VECTORS {
+            let internal = bytes32(vector.internal);
+            let output = bytes32(vector.output);
+            let root = bytes32(vector.merkle_root);
+            let leaf_hashes: Vec<[u8; 32]> = vector
+                .leaves
+                .iter()
+                .map(|(version, script)| tap_leaf_hash(*version, &hex::decode(script).unwrap()))
+                .collect();
+            let tree_root = match leaf_hashes[..] {
+                [leaf] => leaf,
+                [a, b] => tap_branch_hash(&a, &b),
+                _ => unreachable!(),
+            };
+            assert_eq!(tree_root, root, "{}", vector.output);
+
+            for ((version, script), control_block) in
+                vector.leaves.iter().zip(vector.control_blocks)
+            {
+                let control_block = hex::decode(control_block).unwrap();
+                let script = hex::decode(script).unwrap();
+                assert_eq!(control_block[0] & 0xfe, *version);
+                let parity = control_block[0] & 1 == 1;
+                assert!(verify_commitment(&output, parity, &internal, Some(&root)));
+                assert!(!verify_commitment(&output, !parity, &internal, Some(&root)));
+                assert!(verify_control_block(&output, &script, &control_block));
+
+                // another script, or the same one under another leaf version
+                let mut other_script = script.clone();
+                other_script.push(0x51);
+                assert!(!verify_control_block(
+                    &output,
+                    &other_script,
+                    &control_block
+                ));
+                let mut other_version = control_block.clone();
+                other_version[0] ^= 0x02;
+                assert!(!verify_control_block(&output, &script, &other_version));
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_commitment_key_path() {
+        // the BIP341 key path only vector, with no script tree at all
+        let internal = bytes32("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+        let output = bytes32("53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343");
+        assert!(verify_commitment(&output, true, &internal, None));
+        assert!(!verify_commitment(&output, false, &internal, None));
+        assert!(!verify_commitment(
+            &output,
+            true,
+            &internal,
+            Some(&[0u8; 32])
+        ));
+        assert!(!verify_commitment(&internal, true, &internal, None));
+
+        // x = 5 is not on the curve
+        let mut off_curve = [0u8; 32];
+        off_curve[31] = 5;
+        assert!(!verify_commitment(&output, true, &off_curve, None));
+    }
+
+    #[test]
+    fn test_control_block_shape() {
+        let vector = &VECTORS[2];
+        let output = bytes32(vector.output);
+        let script = hex::decode(vector.leaves[0].1).unwrap();
+        let control_block = hex::decode(vector.control_blocks[0]).unwrap();
+        assert!(verify_control_block(&output, &script, &control_block));
+
+        for len in [0, 32, 34, control_block.len() - 1] {
+            assert!(!verify_control_block(
+                &output,
+                &script,
+                &control_block[..len]
+            ));
+        }
+        // a path longer than 128 nodes is rejected before any hashing
+        let long = [&control_block[..33], &[0u8; 32 * 129]].concat();
+        assert!(!verify_control_block(&output, &script, &long));
+
+        // the path commits to the sibling
+        let mut wrong_sibling = control_block.clone();
+        wrong_sibling[40] ^= 1;
+        assert!(!verify_control_block(&output, &script, &wrong_sibling));
+    }
+
+    #[test]
+    fn test_tap_branch_is_symmetric() {
+        let a = tap_leaf_hash(TAPSCRIPT_LEAF_VERSION, b"a");
+        let b = tap_leaf_hash(TAPSCRIPT_LEAF_VERSION, b"b");
+        assert_eq!(tap_branch_hash(&a, &b), tap_branch_hash(&b, &a));
+        assert_eq!(merkle_root(&a, &[]), a);
+        assert_eq!(merkle_root(&a, &[b]), tap_branch_hash(&a, &b));
+        assert_ne!(tap_leaf_hash(0xc2, b"a"), a);
+    }
+}
@@ -0,0 +1,295 @@
+use super::{Network, PrivateKey, S256Point, Scalar, g_mul};
+use crate::ecc::curve::Bounded::Infinity;
+use crate::encoding::base58;
+use crate::encoding::bech32::{self, Bech32Error};
+use rand_core::{CryptoRng, RngCore};
+use rug::Integer;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Vanity address search: keys whose address starts with a chosen prefix. Candidates are
+// consecutive keys k, k+1, k+2, ... from a random k, so each one costs a point addition rather
+// than a scalar multiplication. With the parallel feature every rayon thread walks its own
+// stride of the same sequence
+
+// a P2PKH address has 25 bytes behind it, at most 34 Base58 characters
+const MAX_P2PKH_LEN: usize = 34;
+// after the hrp and "1": the witness version, 32 characters of program and 6 of checksum
+const P2WPKH_DATA_LEN: usize = 39;
+
+// Which address the prefix is matched against, both for the compressed key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddressKind {
+    #[default]
+    P2pkh,
+    P2wpkh,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VanityError {
+    // not in the Base58 alphabet, or not a bech32 data character
+    InvalidCharacter(char),
+    // bech32 prefixes may be upper or lower case, but not both
+    MixedCase,
+    // no address of this kind on this network can start with the prefix
+    UnreachablePrefix,
+    // the custom network's hrp can't make a bech32 address
+    Bech32(Bech32Error),
+}
+
+impl Display for VanityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VanityError::InvalidCharacter(c) => write!(f, "{c:?} cannot appear in the address"),
+            VanityError::MixedCase => write!(f, "bech32 prefix mixes upper and lower case"),
+            VanityError::UnreachablePrefix => {
+                write!(
+                    f,
+                    "no address of this kind and network starts with the prefix"
+                )
+            }
+            VanityError::Bech32(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for VanityError {}
+
+impl AddressKind {
+    fn address(&self, point: &S256Point, network: Network) -> String {
+        match self {
+            AddressKind::P2pkh => point.address(true, network),
+            AddressKind::P2wpkh => point
+                .p2wpkh_address(network)
+                .expect("the hrp is checked before searching"),
+        }
+    }
+}
+
+// Tries at most max_tries keys and returns the first whose address starts with prefix, with
+// that address. Base58 prefixes are case-sensitive, bech32 ones are not. A prefix that can
+// never match is an error up front rather than max_tries wasted attempts, while Ok(None) means
+// the tries ran out. Each extra character multiplies the expected work by about 58 (Base58)
+// or 32 (bech32)
+pub fn search<R: CryptoRng + RngCore>(
+    prefix: &str,
+    network: Network,
+    kind: AddressKind,
+    rng: &mut R,
+    max_tries: u64,
+) -> Result<Option<(PrivateKey, String)>, VanityError> {
+    let prefix = check_prefix(prefix, network, kind)?;
+    let base = PrivateKey::generate(rng);
+    let found = AtomicBool::new(false);
+    let lane = |lane: u64, lanes: u64| {
+        search_lane(
+            &base,
+            lane,
+            lanes,
+            max_tries,
+            |point| {
+                let address = kind.address(point, network);
+                address.starts_with(&prefix).then_some(address)
+            },
+            &found,
+        )
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        let lanes = rayon::current_num_threads() as u64;
+        Ok((0..lanes)
+            .into_par_iter()
+            .find_map_any(|index| lane(index, lanes)))
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        Ok(lane(0, 1))
+    }
+}
+
+// candidates base + lane, base + lane + lanes, ... below base + max_tries, stopping early once
+// any lane has found a match
+fn search_lane(
+    base: &PrivateKey,
+    lane: u64,
+    lanes: u64,
+    max_tries: u64,
+    matches: impl Fn(&S256Point) -> Option<String>,
+    found: &AtomicBool,
+) -> Option<(PrivateKey, String)> {
+    let step = g_mul(&Integer::from(lanes));
+    let mut point = &base.point + &g_mul(&Integer::from(lane));
+    let mut offset = lane;
+    while offset < max_tries && !found.load(Ordering::Relaxed) {
+        // base + offset = 0 mod n has no address, the next candidate does
+        if point.point.x != Infinity
+            && let Some(address) = matches(&point)
+        {
+            found.store(true, Ordering::Relaxed);
+            let secret = &base.secret + &Scalar::new(Integer::from(offset));
+            return Some((PrivateKey::new(secret.as_integer().clone()), address));
+        }
+        point = &point + &step;
+        offset = offset.checked_add(lanes)?;
+    }
+    None
+}
+
+// the prefix to compare addresses with, lowercased for bech32
+fn check_prefix(prefix: &str, network: Network, kind: AddressKind) -> Result<String, VanityError> {
+    match kind {
+        AddressKind::P2pkh => {
+            if let Some(c) = prefix
+                .chars()
+                .find(|c| !c.is_ascii() || !base58::ALPHABET.contains(&(*c as u8)))
+            {
+                return Err(VanityError::InvalidCharacter(c));
+            }
+            // every address lies between the encodings of the smallest and largest payloads
+            // with this version byte, so those bound its first character
+            let version = network.params().p2pkh_prefix;
+            let first = |fill: u8| {
+                let payload = [&[version][..], &[fill; 24]].concat();
+                base58::encode(&payload).as_bytes()[0]
+            };
+            let position = |c: u8| base58::ALPHABET.iter().position(|&a| a == c);
+            let firsts = position(first(0x00))..=position(first(0xff));
+            let reachable = prefix
+                .bytes()
+                .next()
+                .is_none_or(|c| firsts.contains(&position(c)));
+            if !reachable || prefix.len() > MAX_P2PKH_LEN {
+                return Err(VanityError::UnreachablePrefix);
+            }
+            Ok(prefix.to_string())
+        }
+        AddressKind::P2wpkh => {
+            S256Point::get_generator()
+                .p2wpkh_address(network)
+                .map_err(VanityError::Bech32)?;
+            if prefix.chars().any(|c| c.is_ascii_lowercase())
+                && prefix.chars().any(|c| c.is_ascii_uppercase())
+            {
+                return Err(VanityError::MixedCase);
+            }
+            let prefix = prefix.to_ascii_lowercase();
+            // hrp, separator and witness version 0 are the same for every P2WPKH address
+            let hrp = network.params().hrp;
+            let fixed = format!("{hrp}1q");
+            if !(fixed.starts_with(&prefix) || prefix.starts_with(&fixed))
+                || prefix.len() > hrp.len() + 1 + P2WPKH_DATA_LEN
+            {
+                return Err(VanityError::UnreachablePrefix);
+            }
+            let data = prefix.get(fixed.len()..).unwrap_or("");
+            if let Some(c) = data
+                .chars()
+                .find(|c| !c.is_ascii() || !bech32::CHARSET.contains(&(*c as u8)))
+            {
+                return Err(VanityError::InvalidCharacter(c));
+            }
+            Ok(prefix)
+        }
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::SeedableRng;
+
+    fn rng() -> rand_chacha::ChaCha20Rng {
+        rand_chacha::ChaCha20Rng::seed_from_u64(208)
+    }
+
+    #[test]
+    fn test_search_p2pkh() {
+        for (prefix, network) in [("1A", Network::Mainnet), ("n", Network::Testnet)] {
+            let (key, address) = search(prefix, network, AddressKind::P2pkh, &mut rng(), 10_000)
+                .unwrap()
+                .unwrap();
+            assert!(address.starts_with(prefix), "{address}");
+            assert_eq!(key.public_key().point().address(true, network), address);
+        }
+    }
+
+    #[test]
+    fn test_search_p2wpkh() {
+        for (prefix, network) in [("bc1qq", Network::Mainnet), ("TB1QQ", Network::Testnet)] {
+            let (key, address) = search(prefix, network, AddressKind::P2wpkh, &mut rng(), 10_000)
+                .unwrap()
+                .unwrap();
+            assert!(address.starts_with(&prefix.to_lowercase()), "{address}");
+            assert_eq!(
+                key.public_key().point().p2wpkh_address(network).unwrap(),
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_runs_out_of_tries() {
+        let found = search(
+            "1zzzzzzzzz",
+            Network::Mainnet,
+            AddressKind::P2pkh,
+            &mut rng(),
+            5,
+        );
+        assert_eq!(found, Ok(None));
+        // an empty prefix takes the first candidate
+        let found = search("", Network::Mainnet, AddressKind::P2pkh, &mut rng(), 1);
+        assert!(found.unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prefix_checks() {
+        let check = |prefix: &str, network: Network, kind: AddressKind| {
+            search(prefix, network, kind, &mut rng(), 0)
+        };
+        use AddressKind::*;
+        use Network::*;
+        use VanityError::*;
+        // 0, O, I and l are left out of Base58, and Base58 is case-sensitive
+        assert_eq!(check("1O", Mainnet, P2pkh), Err(InvalidCharacter('O')));
+        assert_eq!(check("1l", Mainnet, P2pkh), Err(InvalidCharacter('l')));
+        assert_eq!(check("1é", Mainnet, P2pkh), Err(InvalidCharacter('é')));
+        assert_eq!(check("1L", Mainnet, P2pkh), Ok(None));
+        // mainnet P2PKH addresses all start with 1, testnet ones with m or n
+        assert_eq!(check("3", Mainnet, P2pkh), Err(UnreachablePrefix));
+        assert_eq!(check("1", Testnet, P2pkh), Err(UnreachablePrefix));
+        assert_eq!(check("m", Testnet, P2pkh), Ok(None));
+        assert_eq!(
+            check(&"1".repeat(35), Mainnet, P2pkh),
+            Err(UnreachablePrefix)
+        );
+
+        // b is not a bech32 character
+        assert_eq!(check("bc1qb", Mainnet, P2wpkh), Err(InvalidCharacter('b')));
+        assert_eq!(check("Bc1q", Mainnet, P2wpkh), Err(MixedCase));
+        assert_eq!(check("tb1q", Mainnet, P2wpkh), Err(UnreachablePrefix));
+        // witness version 0 is always q
+        assert_eq!(check("bc1p", Mainnet, P2wpkh), Err(UnreachablePrefix));
+        assert_eq!(check("bc", Mainnet, P2wpkh), Ok(None));
+        assert_eq!(check("bcrt1q", Regtest, P2wpkh), Ok(None));
+        assert_eq!(
+            check(&format!("bc1q{}", "q".repeat(39)), Mainnet, P2wpkh),
+            Err(UnreachablePrefix)
+        );
+        let no_hrp = Network::Custom(crate::ecc::secp256k1::NetworkParams {
+            hrp: "",
+            ..Mainnet.params()
+        });
+        assert_eq!(
+            check("1q", no_hrp, P2wpkh),
+            Err(Bech32(Bech32Error::InvalidHrp))
+        );
+    }
+}
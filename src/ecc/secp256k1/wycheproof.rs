@@ -0,0 +1,343 @@
+// Runs Wycheproof style ECDSA vectors (the ecdsa_secp256k1_sha256*.json files under
+// tests/vectors) through Signature::parse_der and S256Point::verify, and again through
+// verify_der with the lax pre-BIP66 parser. The upstream Wycheproof files can be dropped in next
+// to the local one and are picked up by name.
+// No JSON crate, so a small parser covering what those files use lives here too
+use super::VerifyPolicy::{self, Consensus};
+use super::{ParsePolicy, S256Point, Signature};
+use crate::ecc::hash::sha256;
+use rug::Integer;
+use rug::integer::Order;
+
+const VECTORS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors");
+
+// (file, tcId, policy, why) for cases whose outcome intentionally differs from the file's
+// "result" under that policy. Every entry is asserted to still diverge, so a stale entry fails
+// the test instead of hiding. Standard has none, Consensus reads BER like Bitcoin Core did
+// before BIP66
+const LOCAL: &str = "ecdsa_secp256k1_sha256_local.json";
+const DIVERGENT: &[(&str, u64, VerifyPolicy, &str)] = &[
+    (LOCAL, 13, Consensus, "long form length read"),
+    (LOCAL, 14, Consensus, "SEQUENCE length ignored"),
+    (LOCAL, 15, Consensus, "long form length read"),
+    (LOCAL, 16, Consensus, "bytes after s ignored"),
+    (LOCAL, 17, Consensus, "bytes after s ignored"),
+    (LOCAL, 18, Consensus, "SEQUENCE length ignored"),
+    (LOCAL, 19, Consensus, "SEQUENCE length ignored"),
+    (LOCAL, 25, Consensus, "leading zeros skipped"),
+    (LOCAL, 27, Consensus, "integers read unsigned"),
+    (LOCAL, 29, Consensus, "bytes after s ignored"),
+];
+
+// "acceptable" cases are legacy encodings Wycheproof lets libraries go either way on.
+// parse_der is strict BIP66 DER, so all of them are expected to be rejected. The ones the lax
+// parser reads belong in DIVERGENT under Consensus
+
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn str(&self, key: &str) -> &str {
+        match self.get(key) {
+            Some(Json::String(s)) => s,
+            other => panic!("Error: expected string at {key}, found {other:?}"),
+        }
+    }
+
+    fn array(&self, key: &str) -> &[Json] {
+        match self.get(key) {
+            Some(Json::Array(items)) => items,
+            other => panic!("Error: expected array at {key}, found {other:?}"),
+        }
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parse(text: &str) -> Json {
+        let mut parser = Parser {
+            bytes: text.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.value();
+        parser.skip_whitespace();
+        assert_eq!(parser.pos, parser.bytes.len(), "Error: trailing JSON");
+        value
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> u8 {
+        self.skip_whitespace();
+        *self.bytes.get(self.pos).expect("Error: JSON ends early")
+    }
+
+    fn expect(&mut self, byte: u8) {
+        assert_eq!(self.peek(), byte, "Error: unexpected JSON at {}", self.pos);
+        self.pos += 1;
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Json {
+        assert!(self.bytes[self.pos..].starts_with(word.as_bytes()));
+        self.pos += word.len();
+        value
+    }
+
+    fn value(&mut self) -> Json {
+        match self.peek() {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => Json::String(self.string()),
+            b't' => self.literal("true", Json::Bool(true)),
+            b'f' => self.literal("false", Json::Bool(false)),
+            b'n' => self.literal("null", Json::Null),
+            _ => self.number(),
+        }
+    }
+
+    fn object(&mut self) -> Json {
+        self.expect(b'{');
+        let mut fields = Vec::new();
+        if self.peek() == b'}' {
+            self.pos += 1;
+            return Json::Object(fields);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string();
+            self.expect(b':');
+            fields.push((key, self.value()));
+            match self.peek() {
+                b',' => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.expect(b'}');
+        Json::Object(fields)
+    }
+
+    fn array(&mut self) -> Json {
+        self.expect(b'[');
+        let mut items = Vec::new();
+        if self.peek() == b']' {
+            self.pos += 1;
+            return Json::Array(items);
+        }
+        loop {
+            items.push(self.value());
+            match self.peek() {
+                b',' => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.expect(b']');
+        Json::Array(items)
+    }
+
+    fn string(&mut self) -> String {
+        self.expect(b'"');
+        let mut result = String::new();
+        loop {
+            let c = self.bytes[self.pos];
+            self.pos += 1;
+            match c {
+                b'"' => return result,
+                b'\\' => {
+                    let escaped = self.bytes[self.pos];
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => result.push('\n'),
+                        b't' => result.push('\t'),
+                        b'r' => result.push('\r'),
+                        b'b' => result.push('\u{8}'),
+                        b'f' => result.push('\u{c}'),
+                        b'u' => {
+                            let hex = std::str::from_utf8(&self.bytes[self.pos..self.pos + 4])
+                                .expect("Error: bad \\u escape");
+                            let code = u32::from_str_radix(hex, 16).expect("Error: bad \\u escape");
+                            result.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                            self.pos += 4;
+                        }
+                        other => result.push(other as char),
+                    }
+                }
+                _ => {
+                    // copy a whole UTF-8 sequence at once
+                    let start = self.pos - 1;
+                    while self.pos < self.bytes.len() && self.bytes[self.pos] & 0xc0 == 0x80 {
+                        self.pos += 1;
+                    }
+                    result.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+                }
+            }
+        }
+    }
+
+    fn number(&mut self) -> Json {
+        let start = self.pos;
+        while self.pos < self.bytes.len()
+            && (self.bytes[self.pos] == b'-' || self.bytes[self.pos].is_ascii_digit())
+        {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+        Json::Number(
+            text.parse()
+                .expect("Error: only integer JSON numbers are supported"),
+        )
+    }
+}
+
+fn decode_hex(value: &str) -> Vec<u8> {
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// false when the signature doesn't parse or the key is unusable, same as a failed verify.
+// Standard is parse_der and verify (verify_strict for the Bitcoin groups), Consensus is
+// verify_der's lax parser, which also lets high-S through
+fn verify_case(
+    key: Option<&S256Point>,
+    msg: &[u8],
+    der: &[u8],
+    strict: bool,
+    policy: VerifyPolicy,
+) -> bool {
+    let Some(key) = key else {
+        return false;
+    };
+    let z = Integer::from_digits(&sha256(msg), Order::Msf);
+    match policy {
+        VerifyPolicy::Standard => match Signature::parse_der(der) {
+            Ok(sig) if strict => key.verify_strict(&z, &sig),
+            Ok(sig) => key.verify(&z, &sig),
+            Err(_) => false,
+        },
+        VerifyPolicy::Consensus => key.verify_der(&z, der, policy).unwrap_or(false),
+    }
+}
+
+// returns (cases run, failure descriptions). Every case runs under both policies
+fn run_file(name: &str, text: &str) -> (usize, Vec<String>) {
+    let root = Parser::parse(text);
+    let mut run = 0;
+    let mut failures = Vec::new();
+    for group in root.array("testGroups") {
+        assert_eq!(group.str("sha"), "SHA-256", "{name}");
+        // the Bitcoin flavoured files also reject high-S signatures
+        let strict = match group.str("type") {
+            "EcdsaVerify" => false,
+            "EcdsaBitcoinVerify" => true,
+            other => panic!("Error: unsupported test group type {other} in {name}"),
+        };
+        let key = group.get("publicKey").or(group.get("key")).expect("key");
+        let key = S256Point::parse(&decode_hex(key.str("uncompressed")), ParsePolicy::Strict).ok();
+
+        for case in group.array("tests") {
+            let id = match case.get("tcId") {
+                Some(Json::Number(id)) => *id as u64,
+                other => panic!("Error: bad tcId {other:?}"),
+            };
+            let valid = match case.str("result") {
+                "valid" => true,
+                "invalid" | "acceptable" => false,
+                other => panic!("Error: unknown result {other}"),
+            };
+            for policy in [VerifyPolicy::Standard, VerifyPolicy::Consensus] {
+                let outcome = verify_case(
+                    key.as_ref(),
+                    &decode_hex(case.str("msg")),
+                    &decode_hex(case.str("sig")),
+                    strict,
+                    policy,
+                );
+                let divergent = DIVERGENT
+                    .iter()
+                    .any(|(file, tc, p, _)| *file == name && *tc == id && *p == policy);
+                let expected = valid != divergent;
+                if outcome != expected {
+                    failures.push(format!(
+                        "{name} tcId {id} ({}) {policy:?}: expected {expected}, got {outcome}",
+                        case.str("comment")
+                    ));
+                }
+            }
+            run += 1;
+        }
+    }
+    if let Some(Json::Number(total)) = root.get("numberOfTests") {
+        assert_eq!(run as i64, *total, "{name} numberOfTests");
+    }
+    (run, failures)
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_parser() {
+        let json = Parser::parse(r#" {"a": [1, -2, true, null], "b": {"c": "x\"éy"}, "d": []} "#);
+        assert_eq!(
+            json.get("a"),
+            Some(&Json::Array(vec![
+                Json::Number(1),
+                Json::Number(-2),
+                Json::Bool(true),
+                Json::Null
+            ]))
+        );
+        assert_eq!(json.get("b").unwrap().str("c"), "x\"\u{e9}y");
+        assert_eq!(json.array("d"), &[]);
+        assert_eq!(json.get("missing"), None);
+    }
+
+    #[test]
+    fn test_wycheproof_vectors() {
+        let mut files: Vec<_> = std::fs::read_dir(VECTORS_DIR)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.starts_with("ecdsa_secp256k1_sha256") && name.ends_with(".json"))
+            .collect();
+        files.sort();
+        assert!(!files.is_empty(), "Error: no ECDSA vector files found");
+
+        let mut failures = Vec::new();
+        for name in &files {
+            let text = std::fs::read_to_string(format!("{VECTORS_DIR}/{name}")).unwrap();
+            let (run, file_failures) = run_file(name, &text);
+            assert!(run > 0, "{name} has no test cases");
+            failures.extend(file_failures);
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
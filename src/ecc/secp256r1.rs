@@ -0,0 +1,399 @@
+use super::curve::{
+    Bounded::{self, Finite, Infinity},
+    EcPoint,
+};
+use super::curves::SECP256R1;
+use super::field::{FieldElement, Pow};
+use super::secp256k1::Signature;
+use rug::integer::Order;
+use rug::ops::RemRoundingAssign;
+use rug::{Complete, Integer};
+use std::fmt::Display;
+use std::ops::{Add, Mul};
+use std::sync::OnceLock;
+
+const PRIME_STR: &str = SECP256R1.p;
+const A_STR: &str = SECP256R1.a;
+const B_STR: &str = SECP256R1.b;
+const GX_STR: &str = SECP256R1.gx;
+const GY_STR: &str = SECP256R1.gy;
+const N_STR: &str = SECP256R1.n;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Secp256r1Error {
+    XOutOfRange,
+    YOutOfRange,
+    NotOnCurve,
+    InvalidSecLength,
+    InvalidSecPrefix,
+    SecPointAtInfinity,
+    SecTrailingBytes,
+}
+
+impl Display for Secp256r1Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Secp256r1Error::XOutOfRange => {
+                write!(f, "x coordinate is not less than the field prime")
+            }
+            Secp256r1Error::YOutOfRange => {
+                write!(f, "y coordinate is not less than the field prime")
+            }
+            Secp256r1Error::NotOnCurve => write!(f, "point is not on the P-256 curve"),
+            Secp256r1Error::InvalidSecLength => write!(f, "SEC public key has the wrong length"),
+            Secp256r1Error::InvalidSecPrefix => write!(f, "SEC public key has an unknown prefix"),
+            Secp256r1Error::SecPointAtInfinity => {
+                write!(f, "SEC public key encodes the point at infinity")
+            }
+            Secp256r1Error::SecTrailingBytes => write!(f, "SEC public key has trailing bytes"),
+        }
+    }
+}
+
+impl std::error::Error for Secp256r1Error {}
+
+fn prime() -> Integer {
+    static PRIME: OnceLock<Integer> = OnceLock::new();
+    PRIME
+        .get_or_init(|| Integer::from_str_radix(PRIME_STR, 16).unwrap())
+        .clone()
+}
+
+fn order() -> Integer {
+    static ORDER: OnceLock<Integer> = OnceLock::new();
+    ORDER
+        .get_or_init(|| Integer::from_str_radix(N_STR, 16).unwrap())
+        .clone()
+}
+
+fn to_bytes32(value: &Integer) -> [u8; 32] {
+    let digits = value.to_digits::<u8>(Order::Msf);
+    let mut bytes = [0u8; 32];
+    bytes[32 - digits.len()..].copy_from_slice(&digits);
+    bytes
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct P256Field {
+    element: FieldElement,
+}
+
+impl P256Field {
+    pub fn new(element: &str) -> Self {
+        Self {
+            element: FieldElement::from_str(element, PRIME_STR),
+        }
+    }
+
+    // p % 4 == 3 here too, so a square root (if one exists) is v^((p + 1) / 4)
+    pub fn sqrt(&self) -> Self {
+        let exp = (&self.element.order + 1u8).complete() >> 2u32;
+        Self {
+            element: self.element.pow(exp),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct P256Point {
+    point: EcPoint,
+}
+
+impl P256Point {
+    pub fn new(x: Bounded<P256Field>, y: Bounded<P256Field>) -> Self {
+        let a = P256Field::new(A_STR);
+        let b = P256Field::new(B_STR);
+        let point = match (x, y) {
+            (Finite(x), Finite(y)) => {
+                EcPoint::new(Finite(x.element), Finite(y.element), a.element, b.element)
+            }
+            (Infinity, Infinity) => EcPoint::new(Infinity, Infinity, a.element, b.element),
+            _ => panic!("Error: only one coordinate is infinity, cannot create P256Point"),
+        };
+        Self { point }
+    }
+
+    pub fn get_generator() -> Self {
+        static GENERATOR: OnceLock<P256Point> = OnceLock::new();
+        GENERATOR
+            .get_or_init(|| {
+                Self::new(
+                    Finite(P256Field::new(GX_STR)),
+                    Finite(P256Field::new(GY_STR)),
+                )
+            })
+            .clone()
+    }
+
+    pub fn has_even_y(&self) -> bool {
+        match &self.point.y {
+            Finite(y) => y.value.is_even(),
+            Infinity => panic!("Error: the point at infinity has no y coordinate"),
+        }
+    }
+
+    pub fn sec(&self, compressed: bool) -> Vec<u8> {
+        let (x, y) = match (&self.point.x, &self.point.y) {
+            (Finite(x), Finite(y)) => (to_bytes32(&x.value), to_bytes32(&y.value)),
+            _ => panic!("Error: the point at infinity has no SEC encoding"),
+        };
+        if compressed {
+            let prefix = if self.has_even_y() { 0x02 } else { 0x03 };
+            [&[prefix][..], &x].concat()
+        } else {
+            [&[0x04][..], &x, &y].concat()
+        }
+    }
+
+    // 33-byte compressed or 65-byte uncompressed, validated the same way as S256Point::parse
+    // with the strict policy
+    pub fn from_sec(bytes: &[u8]) -> Result<Self, Secp256r1Error> {
+        let expected = match bytes.first() {
+            None => return Err(Secp256r1Error::InvalidSecLength),
+            Some(0x00) => return Err(Secp256r1Error::SecPointAtInfinity),
+            Some(0x02 | 0x03) => 33,
+            Some(0x04) => 65,
+            Some(_) => return Err(Secp256r1Error::InvalidSecPrefix),
+        };
+        if bytes.len() < expected {
+            return Err(Secp256r1Error::InvalidSecLength);
+        }
+        if bytes.len() > expected {
+            return Err(Secp256r1Error::SecTrailingBytes);
+        }
+
+        let prime = prime();
+        let x = Integer::from_digits(&bytes[1..33], Order::Msf);
+        if x >= prime {
+            return Err(Secp256r1Error::XOutOfRange);
+        }
+        let x = FieldElement::new(x, prime.clone());
+        let a = P256Field::new(A_STR);
+        let b = P256Field::new(B_STR);
+        let alpha = &(&x.pow(3) + &(&a.element * &x)) + &b.element;
+
+        let y = if expected == 33 {
+            let beta = P256Field {
+                element: alpha.clone(),
+            }
+            .sqrt()
+            .element;
+            if beta.pow(2) != alpha {
+                return Err(Secp256r1Error::NotOnCurve);
+            }
+            if beta.value.is_even() == (bytes[0] == 0x02) {
+                beta
+            } else {
+                -&beta
+            }
+        } else {
+            let y = Integer::from_digits(&bytes[33..], Order::Msf);
+            if y >= prime {
+                return Err(Secp256r1Error::YOutOfRange);
+            }
+            let y = FieldElement::new(y, prime);
+            if y.pow(2) != alpha {
+                return Err(Secp256r1Error::NotOnCurve);
+            }
+            y
+        };
+        Ok(Self {
+            point: EcPoint::new(Finite(x), Finite(y), a.element, b.element),
+        })
+    }
+
+    // ECDSA over P-256 with the shared Signature type. Its Scalars are reduced mod the
+    // secp256k1 order, which is larger than P-256's, so r and s arrive unreduced and are
+    // range checked against P-256's n here
+    pub fn verify(&self, z: &Integer, sig: &Signature) -> bool {
+        let n = order();
+        let (r, s) = (sig.r.as_integer(), sig.s.as_integer());
+        if *r <= 0 || *r >= n || *s <= 0 || *s >= n {
+            return false;
+        }
+        if self.point.x == Infinity {
+            return false;
+        }
+
+        let exp = (&n - 2u8).complete();
+        let s_inv = s.pow_mod_ref(&exp, &n).unwrap().complete();
+        let mut u = (z * &s_inv).complete();
+        u.rem_euc_assign(&n);
+        let mut v = (r * &s_inv).complete();
+        v.rem_euc_assign(&n);
+
+        let g = Self::get_generator();
+        let total = &(&g * u) + &(self * v);
+        match total.point.x {
+            Finite(x) => {
+                let mut x = x.value;
+                x.rem_euc_assign(&n);
+                x == *r
+            }
+            Infinity => false,
+        }
+    }
+}
+
+impl Add for &P256Point {
+    type Output = P256Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        P256Point {
+            point: &self.point + &rhs.point,
+        }
+    }
+}
+
+impl Mul<Integer> for &P256Point {
+    type Output = P256Point;
+
+    fn mul(self, rhs: Integer) -> Self::Output {
+        let mut coef = rhs;
+        coef.rem_euc_assign(&order());
+        P256Point {
+            point: &self.point * coef,
+        }
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::hash::sha256;
+    use crate::ecc::secp256k1::Scalar;
+
+    fn hex(value: &str) -> Integer {
+        Integer::from_str_radix(value, 16).unwrap()
+    }
+
+    // RFC 6979 A.2.5: the P-256 key used for every deterministic signature in that section
+    fn rfc6979_key() -> (Integer, P256Point) {
+        let secret = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let point = P256Point::new(
+            Finite(P256Field::new(
+                "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6",
+            )),
+            Finite(P256Field::new(
+                "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299",
+            )),
+        );
+        (secret, point)
+    }
+
+    // (message, r, s) with SHA-256 from RFC 6979 A.2.5
+    fn rfc6979_vectors() -> [(&'static [u8], Signature); 2] {
+        let sig = |r: &str, s: &str| Signature::new(Scalar::new(hex(r)), Scalar::new(hex(s)));
+        [
+            (
+                b"sample",
+                sig(
+                    "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+                    "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+                ),
+            ),
+            (
+                b"test",
+                sig(
+                    "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+                    "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+                ),
+            ),
+        ]
+    }
+
+    fn z(msg: &[u8]) -> Integer {
+        Integer::from_digits(&sha256(msg), Order::Msf)
+    }
+
+    #[test]
+    fn test_generator() {
+        let g = P256Point::get_generator();
+        assert_eq!(&g * order(), P256Point::new(Infinity, Infinity));
+        assert_ne!(&g * Integer::from(2), g);
+    }
+
+    #[test]
+    fn test_public_key() {
+        let (secret, point) = rfc6979_key();
+        assert_eq!(&P256Point::get_generator() * secret, point);
+    }
+
+    #[test]
+    fn test_verify_rfc6979_vectors() {
+        let (_, point) = rfc6979_key();
+        for (msg, sig) in rfc6979_vectors() {
+            assert!(point.verify(&z(msg), &sig));
+            assert!(!point.verify(&z(b"other"), &sig));
+            assert!(!P256Point::get_generator().verify(&z(msg), &sig));
+
+            let bad_s = Signature::new(sig.r.clone(), &sig.s + &Scalar::one());
+            assert!(!point.verify(&z(msg), &bad_s));
+        }
+    }
+
+    #[test]
+    fn test_verify_out_of_range() {
+        let (_, point) = rfc6979_key();
+        let (msg, sig) = rfc6979_vectors()[0].clone();
+        let n = order();
+        // P-256's n fits in a secp256k1 Scalar unreduced, so r + n is representable
+        let cases = [
+            Signature::new(Scalar::zero(), sig.s.clone()),
+            Signature::new(sig.r.clone(), Scalar::zero()),
+            Signature::new(Scalar::new(n.clone()), sig.s.clone()),
+            Signature::new(sig.r.clone(), Scalar::new(n)),
+        ];
+        for bad in cases {
+            assert!(!point.verify(&z(msg), &bad));
+        }
+    }
+
+    #[test]
+    fn test_sec_round_trip() {
+        let (_, point) = rfc6979_key();
+        let g = P256Point::get_generator();
+        for p in [point, g.clone(), &g * Integer::from(3)] {
+            assert_eq!(P256Point::from_sec(&p.sec(true)), Ok(p.clone()));
+            assert_eq!(P256Point::from_sec(&p.sec(false)), Ok(p));
+        }
+        assert_eq!(
+            P256Point::get_generator().sec(true)[..5],
+            [0x03, 0x6b, 0x17, 0xd1, 0xf2]
+        );
+    }
+
+    #[test]
+    fn test_from_sec_errors() {
+        let g = P256Point::get_generator();
+        let mut off_curve = g.sec(false);
+        off_curve[64] ^= 1;
+        let field_prime = to_bytes32(&prime());
+        let cases: Vec<(Vec<u8>, Secp256r1Error)> = vec![
+            (vec![], Secp256r1Error::InvalidSecLength),
+            (vec![0x00], Secp256r1Error::SecPointAtInfinity),
+            (g.sec(true)[..32].to_vec(), Secp256r1Error::InvalidSecLength),
+            (
+                [&g.sec(true)[..], &[0]].concat(),
+                Secp256r1Error::SecTrailingBytes,
+            ),
+            (
+                [&[0x06], &g.sec(false)[1..]].concat(),
+                Secp256r1Error::InvalidSecPrefix,
+            ),
+            (
+                [&[0x02], &field_prime[..]].concat(),
+                Secp256r1Error::XOutOfRange,
+            ),
+            (off_curve, Secp256r1Error::NotOnCurve),
+        ];
+        for (bytes, err) in cases {
+            assert_eq!(P256Point::from_sec(&bytes), Err(err));
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::field::FieldElement;
+use super::poly::lagrange_eval_at;
+use rand_core::{CryptoRng, RngCore};
+use rug::Integer;
+use std::fmt::Display;
+
+// Shamir secret sharing over F_order: the secret is the constant term of a random polynomial of
+// degree t - 1 and share i is the polynomial at x = i. Any t shares pin the polynomial down, any
+// t - 1 of them are consistent with every possible secret
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SssError {
+    NoShares,
+    DuplicateX(u32),
+    ZeroX,
+    OrderMismatch,
+}
+
+impl Display for SssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SssError::NoShares => write!(f, "no shares to combine"),
+            SssError::DuplicateX(x) => write!(f, "more than one share at x = {x}"),
+            SssError::ZeroX => write!(f, "a share at x = 0 would be the secret itself"),
+            SssError::OrderMismatch => write!(f, "shares are not all over the same field"),
+        }
+    }
+}
+
+impl std::error::Error for SssError {}
+
+// n shares of secret, any t of which recover it. Panics unless 1 <= t <= n < order and
+// secret < order, since every one of those is a caller bug rather than bad data
+pub fn split<R: CryptoRng + RngCore>(
+    secret: &Integer,
+    t: usize,
+    n: usize,
+    order: &Integer,
+    rng: &mut R,
+) -> Vec<(u32, FieldElement)> {
+    assert!(1 <= t && t <= n, "Error: threshold must be in [1, n]");
+    assert!(
+        n <= u32::MAX as usize && *order > n,
+        "Error: too many shares for the field"
+    );
+    let secret = FieldElement::new(secret.clone(), order.clone());
+    let coefficients: Vec<FieldElement> = std::iter::once(secret)
+        .chain((1..t).map(|_| FieldElement::random_in_range(rng, order.clone())))
+        .collect();
+
+    (1..=n as u32)
+        .map(|x| {
+            let x_element = FieldElement::new(x, order.clone());
+            // Horner, from the highest coefficient down
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(FieldElement::new(0, order.clone()), |acc, c| {
+                    &(&acc * &x_element) + c
+                });
+            (x, y)
+        })
+        .collect()
+}
+
+// The polynomial through the shares, evaluated at 0 by Lagrange interpolation. With fewer than
+// t shares this is still some value, just not the secret
+pub fn combine(shares: &[(u32, FieldElement)]) -> Result<Integer, SssError> {
+    let (_, first) = shares.first().ok_or(SssError::NoShares)?;
+    let order = &first.order;
+    // x is compared as a field element, so x = order + 1 collides with x = 1
+    let mut xs: Vec<FieldElement> = Vec::with_capacity(shares.len());
+    for (x, y) in shares {
+        if y.order != *order {
+            return Err(SssError::OrderMismatch);
+        }
+        let x_element = FieldElement::new(Integer::from(*x) % order, order.clone());
+        if x_element.is_zero() {
+            return Err(SssError::ZeroX);
+        }
+        if xs.contains(&x_element) {
+            return Err(SssError::DuplicateX(*x));
+        }
+        xs.push(x_element);
+    }
+
+    let points: Vec<(FieldElement, FieldElement)> = xs
+        .into_iter()
+        .zip(shares.iter().map(|(_, y)| y.clone()))
+        .collect();
+    let secret = lagrange_eval_at(&points, &FieldElement::new(0, order.clone()))
+        .expect("Error: shares were already checked");
+    Ok(secret.value)
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::curves::SECP256K1;
+    use rand_core::SeedableRng;
+
+    fn rng(seed: u64) -> rand_chacha::ChaCha20Rng {
+        rand_chacha::ChaCha20Rng::seed_from_u64(seed)
+    }
+
+    #[test]
+    fn test_split_combine_subsets() {
+        let n_order = SECP256K1.order();
+        let secret = Integer::from_str_radix(
+            "e9873d79c6d87dc0fb6a5778633389f4453213303da61f20bd67fc233aa33262",
+            16,
+        )
+        .unwrap();
+        let shares = split(&secret, 3, 5, &n_order, &mut rng(1));
+        assert_eq!(shares.len(), 5);
+        assert_eq!(
+            shares.iter().map(|(x, _)| *x).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5]
+        );
+
+        // every 3-subset, plus all five at once
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(combine(&subset), Ok(secret.clone()));
+                }
+            }
+        }
+        assert_eq!(combine(&shares), Ok(secret.clone()));
+        assert_ne!(combine(&shares[..2]), Ok(secret));
+    }
+
+    #[test]
+    fn test_threshold_one_and_n() {
+        let order = Integer::from(223);
+        let secret = Integer::from(42);
+        // t = 1 is a constant polynomial, every share is the secret
+        for (_, y) in split(&secret, 1, 4, &order, &mut rng(2)) {
+            assert_eq!(y.value, 42);
+        }
+        let shares = split(&secret, 4, 4, &order, &mut rng(3));
+        assert_eq!(combine(&shares), Ok(secret.clone()));
+        assert_ne!(combine(&shares[1..]), Ok(secret));
+    }
+
+    #[test]
+    fn test_too_few_shares_reveal_nothing() {
+        // Over F_19, two shares of a 3-of-5 split interpolate to a line whose value at 0 should
+        // be uniform over the field no matter the secret
+        let order = Integer::from(19);
+        let secret = Integer::from(5);
+        let mut rng = rng(4);
+        let mut counts = [0u32; 19];
+        for _ in 0..19 * 200 {
+            let shares = split(&secret, 3, 5, &order, &mut rng);
+            let guess = combine(&shares[..2]).unwrap();
+            counts[guess.to_usize().unwrap()] += 1;
+        }
+        // 200 expected per value, about 6 standard deviations of slack either way
+        assert!(
+            counts.iter().all(|&c| (120..=280).contains(&c)),
+            "{counts:?}"
+        );
+    }
+
+    #[test]
+    fn test_combine_errors() {
+        let order = Integer::from(223);
+        let shares = split(&Integer::from(7), 2, 3, &order, &mut rng(5));
+        assert_eq!(combine(&[]), Err(SssError::NoShares));
+        assert_eq!(
+            combine(&[shares[0].clone(), shares[1].clone(), shares[0].clone()]),
+            Err(SssError::DuplicateX(1))
+        );
+        assert_eq!(
+            combine(&[shares[0].clone(), (224, shares[1].1.clone())]),
+            Err(SssError::DuplicateX(224))
+        );
+        assert_eq!(
+            combine(&[(0, FieldElement::new(7, 223)), shares[1].clone()]),
+            Err(SssError::ZeroX)
+        );
+        assert_eq!(
+            combine(&[shares[0].clone(), (2, FieldElement::new(1, 227))]),
+            Err(SssError::OrderMismatch)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold")]
+    fn test_threshold_above_n() {
+        split(&Integer::from(1), 4, 3, &Integer::from(223), &mut rng(6));
+    }
+}
@@ -0,0 +1,177 @@
+use super::curve::{
+    Bounded::{Finite, Infinity},
+    EcPoint,
+};
+use super::curves::CurveParams;
+use super::field::{FieldElement, Pow};
+use proptest::prelude::*;
+use rug::integer::Order;
+use rug::{Complete, Integer};
+
+// proptest strategies for the field and curve types, behind the proptest feature so other
+// crates can reuse them
+
+// Elements of F_order for orders up to 2^256. 0 and order - 1 are each drawn about one time in
+// eight since those are the edges that keep being missed, and everything shrinks toward 0
+pub fn field_element(order: Integer) -> impl Strategy<Value = FieldElement> {
+    let zero = FieldElement::new(0, order.clone());
+    let max = FieldElement::new((&order - 1u8).complete(), order.clone());
+    let random = any::<[u8; 32]>().prop_map(move |bytes| {
+        let value = Integer::from_digits(&bytes, Order::Msf) % &order;
+        FieldElement::new(value, order.clone())
+    });
+    prop_oneof![
+        1 => Just(zero),
+        1 => Just(max),
+        6 => random,
+    ]
+}
+
+// Points on the curve, Infinity included. x comes from field_element and is lifted with a coin
+// flip for the parity of y, rejecting x with no square root, so shrinking heads to Infinity
+// first and then toward small x. The lift is a^((p + 1) / 4), which needs p = 3 mod 4
+pub fn ec_point(curve: &CurveParams) -> impl Strategy<Value = EcPoint> {
+    let p = curve.prime();
+    assert!(p.mod_u(4) == 3, "Error: ec_point needs a prime p = 3 mod 4");
+    let exp = (&p + 1u8).complete() / 4u8;
+    let (a, b) = curve.coefficients();
+    let infinity = EcPoint::new(Infinity, Infinity, a.clone(), b.clone());
+
+    let finite = (field_element(p), any::<bool>()).prop_filter_map(
+        "x is not the x coordinate of a point",
+        move |(x, odd)| {
+            let rhs = &(&x.pow(3) + &(&a * &x)) + &b;
+            let y = rhs.pow(exp.clone());
+            if y.pow(2) != rhs {
+                return None;
+            }
+            let y = if y.value.is_odd() == odd { y } else { -&y };
+            Some(EcPoint::new(Finite(x), Finite(y), a.clone(), b.clone()))
+        },
+    );
+    prop_oneof![
+        1 => Just(infinity),
+        7 => finite,
+    ]
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::curves::{SECP256K1, TOY223};
+    use crate::ecc::ring::jacobi;
+    use crate::ecc::secp256k1::{PrivateKey, Scalar};
+
+    fn toy_order() -> Integer {
+        TOY223.prime()
+    }
+
+    fn negate(point: &EcPoint) -> EcPoint {
+        match &point.y {
+            Finite(y) => EcPoint::new(
+                point.x.clone(),
+                Finite(-y),
+                point.a.clone(),
+                point.b.clone(),
+            ),
+            Infinity => point.clone(),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_field_add_is_associative_and_commutative(
+            a in field_element(toy_order()),
+            b in field_element(toy_order()),
+            c in field_element(toy_order()),
+        ) {
+            prop_assert_eq!(&(&a + &b) + &c, &a + &(&b + &c));
+            prop_assert_eq!(&a + &b, &b + &a);
+        }
+
+        #[test]
+        fn test_field_mul_is_associative_and_distributive(
+            a in field_element(SECP256K1.prime()),
+            b in field_element(SECP256K1.prime()),
+            c in field_element(SECP256K1.prime()),
+        ) {
+            prop_assert_eq!(&(&a * &b) * &c, &a * &(&b * &c));
+            prop_assert_eq!(&a * &(&b + &c), &(&a * &b) + &(&a * &c));
+        }
+
+        #[test]
+        fn test_field_inverses(a in field_element(SECP256K1.prime())) {
+            let zero = FieldElement::new(0, SECP256K1.prime());
+            let one = FieldElement::new(1, SECP256K1.prime());
+            prop_assert_eq!(&a + &(-&a), zero.clone());
+            prop_assert_eq!(&a - &a, zero.clone());
+            if a.is_zero() {
+                prop_assert!(one.try_div(&a).is_err());
+            } else {
+                prop_assert_eq!(&a * &(&one / &a), one);
+            }
+        }
+
+        #[test]
+        fn test_group_add_is_associative_and_commutative(
+            p in ec_point(&TOY223),
+            q in ec_point(&TOY223),
+            r in ec_point(&TOY223),
+        ) {
+            prop_assert_eq!(&(&p + &q) + &r, &p + &(&q + &r));
+            prop_assert_eq!(&p + &q, &q + &p);
+        }
+
+        #[test]
+        fn test_group_identity_and_inverse(p in ec_point(&TOY223)) {
+            let (a, b) = TOY223.coefficients();
+            let infinity = EcPoint::new(Infinity, Infinity, a, b);
+            prop_assert_eq!(&p + &infinity, p.clone());
+            prop_assert_eq!(&infinity + &p, p.clone());
+            prop_assert_eq!(&p + &negate(&p), infinity);
+        }
+
+        #[test]
+        fn test_secp256k1_doubling_matches_scalar_mul(p in ec_point(&SECP256K1)) {
+            prop_assert_eq!(&p + &p, &p * Integer::from(2));
+        }
+
+        #[test]
+        fn test_jacobi_is_multiplicative(a in any::<i64>(), b in any::<i64>(), n in any::<u64>()) {
+            let n = Integer::from(n | 1);
+            let (a, b) = (Integer::from(a), Integer::from(b));
+            let ab = (&a * &b).complete();
+            let symbol = |v: &Integer| jacobi(v, &n).unwrap();
+            prop_assert_eq!(symbol(&ab), symbol(&a) * symbol(&b));
+        }
+
+        #[test]
+        fn test_private_and_public_tweaks_agree(
+            secret in field_element(SECP256K1.order()),
+            t in field_element(SECP256K1.order()),
+        ) {
+            prop_assume!(secret.value != 0);
+            let key = PrivateKey::new(secret.value);
+            let t = Scalar::new(t.value);
+            match key.add_tweak(&t) {
+                Ok(tweaked) => prop_assert_eq!(
+                    Ok(tweaked.public_key()),
+                    key.public_key().add_tweak(&t)
+                ),
+                Err(e) => prop_assert_eq!(Err(e), key.public_key().add_tweak(&t)),
+            }
+            match key.mul_tweak(&t) {
+                Ok(tweaked) => prop_assert_eq!(
+                    Ok(tweaked.public_key()),
+                    key.public_key().mul_tweak(&t)
+                ),
+                Err(e) => prop_assert_eq!(Err(e), key.public_key().mul_tweak(&t)),
+            }
+        }
+    }
+}
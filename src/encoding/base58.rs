@@ -0,0 +1,173 @@
+use crate::ecc::hash::hash256;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+pub(crate) const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Base58Error {
+    InvalidCharacter(char),
+    TooShort,
+    BadChecksum,
+}
+
+impl Display for Base58Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Base58Error::InvalidCharacter(c) => write!(f, "invalid base58 character {c:?}"),
+            Base58Error::TooShort => write!(f, "base58check data is shorter than its checksum"),
+            Base58Error::BadChecksum => write!(f, "base58 checksum does not match"),
+        }
+    }
+}
+
+impl core::error::Error for Base58Error {}
+
+fn checksum(bytes: &[u8]) -> [u8; 4] {
+    let mut result = [0u8; 4];
+    result.copy_from_slice(&hash256(bytes)[..4]);
+    result
+}
+
+// each leading zero byte becomes a '1', the rest is the number written in base 58. The
+// conversion works on little-endian digit vectors so it needs no bignum type
+pub fn encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut result: Vec<u8> = digits.iter().map(|&d| ALPHABET[d as usize]).collect();
+    result.extend(core::iter::repeat_n(b'1', zeros));
+    result.reverse();
+    String::from_utf8(result).unwrap()
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in s.chars().skip(zeros) {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or(Base58Error::InvalidCharacter(c))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(core::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Ok(bytes)
+}
+
+// appends the first 4 bytes of hash256(bytes)
+pub fn encode_check(bytes: &[u8]) -> String {
+    encode(&[bytes, &checksum(bytes)].concat())
+}
+
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let bytes = decode(s)?;
+    if bytes.len() < 4 {
+        return Err(Base58Error::TooShort);
+    }
+    let (payload, check) = bytes.split_at(bytes.len() - 4);
+    if checksum(payload) != check {
+        return Err(Base58Error::BadChecksum);
+    }
+    Ok(payload.to_vec())
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_vectors() {
+        let cases = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            (
+                "73696d706c792061206c6f6e6720737472696e67",
+                "2cFupjhnEsSn59qHXstmK2ffpLv2",
+            ),
+            (
+                "00eb15231dfceb60925886b67d065299925915aeb172c06647",
+                "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+            ),
+            ("000001", "112"),
+            ("0000287fb4cd", "11233QC4"),
+            ("00000000000000000000", "1111111111"),
+        ];
+        for (hex, expected) in cases {
+            let bytes = decode_hex(hex);
+            assert_eq!(encode(&bytes), expected);
+            assert_eq!(decode(expected).unwrap(), bytes);
+        }
+        assert_eq!(encode(b"hello world"), "StV1DL6CwTryKyV");
+    }
+
+    #[test]
+    fn test_check_vectors() {
+        assert_eq!(encode_check(b"hello world"), "3vQB7B6MrGQZaxCuFg4oh");
+        assert_eq!(
+            decode_check("3vQB7B6MrGQZaxCuFg4oh").unwrap(),
+            b"hello world"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // deterministic pseudo-random inputs, with and without leading zeros
+        let mut seed = hash256(b"base58").to_vec();
+        for len in 0..40 {
+            seed = hash256(&seed).to_vec();
+            let mut bytes: Vec<u8> = seed.iter().cycle().take(len).copied().collect();
+            if len % 3 == 0 {
+                bytes.splice(0..0, [0, 0]);
+            }
+            assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+            assert_eq!(decode_check(&encode_check(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode("0OIl"), Err(Base58Error::InvalidCharacter('0')));
+        assert_eq!(decode("abc!"), Err(Base58Error::InvalidCharacter('!')));
+        assert_eq!(decode_check("2g"), Err(Base58Error::TooShort));
+        assert_eq!(
+            decode_check("3vQB7B6MrGQZaxCuFg4oi"),
+            Err(Base58Error::BadChecksum)
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Base64Error {
+    InvalidCharacter(char),
+    InvalidLength,
+    InvalidPadding,
+}
+
+impl Display for Base64Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Base64Error::InvalidCharacter(c) => write!(f, "invalid base64 character {c:?}"),
+            Base64Error::InvalidLength => write!(f, "base64 length is not a multiple of 4"),
+            Base64Error::InvalidPadding => write!(f, "invalid base64 padding"),
+        }
+    }
+}
+
+impl core::error::Error for Base64Error {}
+
+// standard alphabet with '=' padding (RFC 4648 section 4)
+pub fn encode(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base64Error> {
+    if !s.len().is_multiple_of(4) {
+        return Err(Base64Error::InvalidLength);
+    }
+    let padding = s.bytes().rev().take_while(|&c| c == b'=').count();
+    if padding > 2 {
+        return Err(Base64Error::InvalidPadding);
+    }
+
+    let mut result = Vec::with_capacity(s.len() / 4 * 3);
+    for (i, chunk) in s.as_bytes().chunks(4).enumerate() {
+        let last = (i + 1) * 4 == s.len();
+        let mut n: u32 = 0;
+        for (j, &c) in chunk.iter().enumerate() {
+            let value = if c == b'=' && last && j >= 4 - padding {
+                0
+            } else {
+                ALPHABET
+                    .iter()
+                    .position(|&a| a == c)
+                    .ok_or(Base64Error::InvalidCharacter(c as char))? as u32
+            };
+            n = (n << 6) | value;
+        }
+        let bytes = n.to_be_bytes();
+        let keep = if last { 3 - padding } else { 3 };
+        // bits dropped by the padding must be zero for a canonical encoding
+        if bytes[1 + keep..].iter().any(|&b| b != 0) {
+            return Err(Base64Error::InvalidPadding);
+        }
+        result.extend_from_slice(&bytes[1..1 + keep]);
+    }
+    Ok(result)
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        let cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in cases {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 2, 3, 64, 65, 256] {
+            assert_eq!(decode(&encode(&bytes[..len])).unwrap(), &bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode("Zm9"), Err(Base64Error::InvalidLength));
+        assert_eq!(decode("Zm9*"), Err(Base64Error::InvalidCharacter('*')));
+        assert_eq!(decode("Z==="), Err(Base64Error::InvalidPadding));
+        assert_eq!(decode("Zg=a"), Err(Base64Error::InvalidCharacter('=')));
+        assert_eq!(decode("Zh=="), Err(Base64Error::InvalidPadding));
+        assert_eq!(decode("Zg==Zg=="), Err(Base64Error::InvalidCharacter('=')));
+    }
+}
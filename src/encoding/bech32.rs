@@ -0,0 +1,335 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::Display;
+
+pub(crate) const CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+// BIP173 (bech32) is used for witness v0, BIP350 (bech32m) for v1 and up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bech32Error {
+    InvalidHrp,
+    InvalidCharacter,
+    MixedCase,
+    MissingSeparator,
+    InvalidLength,
+    BadChecksum,
+    InvalidPadding,
+    InvalidWitnessVersion,
+    InvalidProgramLength,
+    WrongVariant,
+}
+
+impl Display for Bech32Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Bech32Error::InvalidHrp => write!(f, "human-readable part is empty or invalid"),
+            Bech32Error::InvalidCharacter => write!(f, "invalid bech32 character"),
+            Bech32Error::MixedCase => write!(f, "bech32 string mixes upper and lower case"),
+            Bech32Error::MissingSeparator => write!(f, "bech32 string has no '1' separator"),
+            Bech32Error::InvalidLength => write!(f, "bech32 string has an invalid length"),
+            Bech32Error::BadChecksum => write!(f, "bech32 checksum does not match"),
+            Bech32Error::InvalidPadding => write!(f, "invalid padding in 5-bit data"),
+            Bech32Error::InvalidWitnessVersion => write!(f, "witness version must be 0..=16"),
+            Bech32Error::InvalidProgramLength => write!(f, "invalid witness program length"),
+            Bech32Error::WrongVariant => {
+                write!(f, "checksum variant does not match the witness version")
+            }
+        }
+    }
+}
+
+impl core::error::Error for Bech32Error {}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk: u32 = 1;
+    for &v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ v as u32;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut values: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|c| c & 31));
+    values
+}
+
+fn checksum(hrp: &str, data: &[u8], variant: Variant) -> [u8; 6] {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0; 6]);
+    let pm = polymod(&values) ^ variant.constant();
+    let mut result = [0u8; 6];
+    for (i, c) in result.iter_mut().enumerate() {
+        *c = ((pm >> (5 * (5 - i))) & 31) as u8;
+    }
+    result
+}
+
+// data is a sequence of 5-bit values
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Bech32Error> {
+    if hrp.is_empty() || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+    if data.iter().any(|&d| d > 31) {
+        return Err(Bech32Error::InvalidCharacter);
+    }
+    if hrp.len() + data.len() + 7 > 90 {
+        return Err(Bech32Error::InvalidLength);
+    }
+    let hrp = hrp.to_ascii_lowercase();
+    let mut result = format!("{hrp}1");
+    for &d in data.iter().chain(&checksum(&hrp, data, variant)) {
+        result.push(CHARSET[d as usize] as char);
+    }
+    Ok(result)
+}
+
+// returns the lowercase hrp, the 5-bit data without the checksum, and which variant matched
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    if s.len() > 90 {
+        return Err(Bech32Error::InvalidLength);
+    }
+    if s.bytes().any(|c| c.is_ascii_lowercase()) && s.bytes().any(|c| c.is_ascii_uppercase()) {
+        return Err(Bech32Error::MixedCase);
+    }
+    let s = s.to_ascii_lowercase();
+    let sep = s.rfind('1').ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, rest) = (&s[..sep], &s[sep + 1..]);
+    if hrp.is_empty() || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+    if rest.len() < 6 {
+        return Err(Bech32Error::InvalidLength);
+    }
+
+    let mut data = Vec::with_capacity(rest.len());
+    for c in rest.bytes() {
+        let d = CHARSET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(Bech32Error::InvalidCharacter)?;
+        data.push(d as u8);
+    }
+
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    let variant = match polymod(&values) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err(Bech32Error::BadChecksum),
+    };
+    data.truncate(data.len() - 6);
+    Ok((hrp.to_string(), data, variant))
+}
+
+// regroups bits, e.g. bytes (8) into bech32 characters (5) and back
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let max = (1u32 << to) - 1;
+    let mut result = Vec::new();
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return Err(Bech32Error::InvalidCharacter);
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            result.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+    Ok(result)
+}
+
+fn check_program(version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion);
+    }
+    if program.len() < 2 || program.len() > 40 {
+        return Err(Bech32Error::InvalidProgramLength);
+    }
+    // v0 programs are either a key hash or a script hash
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(Bech32Error::InvalidProgramLength);
+    }
+    Ok(())
+}
+
+// segwit address: witness version followed by the program regrouped into 5-bit values
+pub fn encode_segwit(hrp: &str, version: u8, program: &[u8]) -> Result<String, Bech32Error> {
+    check_program(version, program)?;
+    let variant = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    let mut data = vec![version];
+    data.extend(convert_bits(program, 8, 5, true)?);
+    encode(hrp, &data, variant)
+}
+
+// returns the witness version and program, checking the hrp matches the expected network
+pub fn decode_segwit(hrp: &str, address: &str) -> Result<(u8, Vec<u8>), Bech32Error> {
+    let (found_hrp, data, variant) = decode(address)?;
+    if found_hrp != hrp.to_ascii_lowercase() {
+        return Err(Bech32Error::InvalidHrp);
+    }
+    let (&version, rest) = data.split_first().ok_or(Bech32Error::InvalidLength)?;
+    let program = convert_bits(rest, 5, 8, false)?;
+    check_program(version, &program)?;
+    let expected = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+    if variant != expected {
+        return Err(Bech32Error::WrongVariant);
+    }
+    Ok((version, program))
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_checksums() {
+        // BIP173 and BIP350 valid strings
+        for (s, variant) in [
+            ("A12UEL5L", Variant::Bech32),
+            ("a12uel5l", Variant::Bech32),
+            (
+                "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+                Variant::Bech32,
+            ),
+            (
+                "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+                Variant::Bech32,
+            ),
+            ("A1LQFN3A", Variant::Bech32m),
+            (
+                "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+                Variant::Bech32m,
+            ),
+        ] {
+            let (hrp, data, found) = decode(s).unwrap();
+            assert_eq!(found, variant);
+            assert_eq!(
+                encode(&hrp, &data, variant).unwrap(),
+                s.to_ascii_lowercase()
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_strings() {
+        assert_eq!(decode("pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+        assert_eq!(decode("1pzry9x0s0muk"), Err(Bech32Error::InvalidHrp));
+        assert_eq!(decode("x1b4n0q5v"), Err(Bech32Error::InvalidCharacter));
+        assert_eq!(decode("li1dgmt3"), Err(Bech32Error::InvalidLength));
+        assert_eq!(decode("A1G7SGD8"), Err(Bech32Error::BadChecksum));
+        assert_eq!(decode("A12uEL5L"), Err(Bech32Error::MixedCase));
+    }
+
+    #[test]
+    fn test_segwit_addresses() {
+        let cases = [
+            (
+                "bc",
+                0,
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                "bc",
+                0,
+                "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+                "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+            ),
+            (
+                "bc",
+                1,
+                "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+            ),
+        ];
+        for (hrp, version, program, address) in cases {
+            let program = decode_hex(program);
+            assert_eq!(encode_segwit(hrp, version, &program).unwrap(), address);
+            assert_eq!(decode_segwit(hrp, address).unwrap(), (version, program));
+        }
+    }
+
+    #[test]
+    fn test_invalid_segwit() {
+        assert_eq!(
+            encode_segwit("bc", 0, &[0u8; 21]),
+            Err(Bech32Error::InvalidProgramLength)
+        );
+        assert_eq!(
+            encode_segwit("bc", 1, &[0u8; 41]),
+            Err(Bech32Error::InvalidProgramLength)
+        );
+        assert_eq!(
+            encode_segwit("bc", 17, &[0u8; 20]),
+            Err(Bech32Error::InvalidWitnessVersion)
+        );
+        // a mainnet address checked against the testnet hrp
+        assert_eq!(
+            decode_segwit("tb", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+            Err(Bech32Error::InvalidHrp)
+        );
+        // v0 program with a bech32m checksum
+        let data = [&[0u8][..], &convert_bits(&[0u8; 20], 8, 5, true).unwrap()].concat();
+        let address = encode("bc", &data, Variant::Bech32m).unwrap();
+        assert_eq!(
+            decode_segwit("bc", &address),
+            Err(Bech32Error::WrongVariant)
+        );
+    }
+}
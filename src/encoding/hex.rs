@@ -0,0 +1,68 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HexError {
+    InvalidCharacter(char),
+    OddLength,
+}
+
+impl Display for HexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HexError::InvalidCharacter(c) => write!(f, "invalid hex character {c:?}"),
+            HexError::OddLength => write!(f, "hex string has an odd number of digits"),
+        }
+    }
+}
+
+impl core::error::Error for HexError {}
+
+// lowercase, two digits per byte
+pub fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// either case, no prefix, separators or whitespace
+pub fn decode(s: &str) -> Result<Vec<u8>, HexError> {
+    if let Some(c) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(HexError::InvalidCharacter(c));
+    }
+    if !s.len().is_multiple_of(2) {
+        return Err(HexError::OddLength);
+    }
+    Ok((0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).expect("checked to be hex digits"))
+        .collect())
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(encode(&bytes[..4]), "00010203");
+        assert_eq!(encode(&bytes[250..]), "fafbfcfdfeff");
+        assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+        assert_eq!(decode("DEADbeef").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode("abc"), Err(HexError::OddLength));
+        assert_eq!(decode("0x00"), Err(HexError::InvalidCharacter('x')));
+        assert_eq!(decode("00 11"), Err(HexError::InvalidCharacter(' ')));
+        assert_eq!(decode("é0"), Err(HexError::InvalidCharacter('é')));
+    }
+}
@@ -0,0 +1,134 @@
+use core::fmt::{Display, LowerHex};
+#[cfg(feature = "gmp")]
+use rug::{Integer, integer::Order};
+
+// Byte-order helpers. Bitcoin puts integers on the wire little-endian, and shows hashes such as
+// txids and block hashes with their bytes reversed
+
+#[cfg(any(feature = "std", test))]
+pub fn read_u32_le(reader: &mut impl std::io::Read) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+#[cfg(any(feature = "std", test))]
+pub fn read_u64_le(reader: &mut impl std::io::Read) -> std::io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(any(feature = "std", test))]
+pub fn write_u32_le(writer: &mut impl std::io::Write, n: u32) -> std::io::Result<()> {
+    writer.write_all(&n.to_le_bytes())
+}
+
+#[cfg(any(feature = "std", test))]
+pub fn write_u64_le(writer: &mut impl std::io::Write, n: u64) -> std::io::Result<()> {
+    writer.write_all(&n.to_le_bytes())
+}
+
+// any number of bytes, least significant first
+#[cfg(feature = "gmp")]
+pub fn integer_from_le_bytes(bytes: &[u8]) -> Integer {
+    Integer::from_digits(bytes, Order::Lsf)
+}
+
+// n zero-padded to 32 bytes, least significant first. Panics if n is negative or does not fit
+#[cfg(feature = "gmp")]
+pub fn integer_to_le_bytes32(n: &Integer) -> [u8; 32] {
+    assert!(
+        n.cmp0().is_ge() && n.significant_bits() <= 256,
+        "Error: integer does not fit in 32 little-endian bytes"
+    );
+    let mut bytes = [0u8; 32];
+    let digits = n.to_digits::<u8>(Order::Lsf);
+    bytes[..digits.len()].copy_from_slice(&digits);
+    bytes
+}
+
+// Displays a 32-byte hash the way explorers and RPCs do, last byte first, so internal byte order
+// can be kept everywhere else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReversedHex<'a>(pub &'a [u8; 32]);
+
+impl Display for ReversedHex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        LowerHex::fmt(self, f)
+    }
+}
+
+impl LowerHex for ReversedHex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for byte in self.0.iter().rev() {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    #[test]
+    fn test_read_write() {
+        let mut buf = Vec::new();
+        write_u32_le(&mut buf, 1).unwrap();
+        write_u64_le(&mut buf, 0x0102_0304_0506_0708).unwrap();
+        write_u32_le(&mut buf, 0xffff_fffe).unwrap();
+        assert_eq!(hex::encode(&buf), "010000000807060504030201feffffff");
+
+        let mut reader = &buf[..];
+        assert_eq!(read_u32_le(&mut reader).unwrap(), 1);
+        assert_eq!(read_u64_le(&mut reader).unwrap(), 0x0102_0304_0506_0708);
+        assert_eq!(read_u32_le(&mut reader).unwrap(), 0xffff_fffe);
+        let err = read_u32_le(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "gmp")]
+    #[test]
+    fn test_integer_round_trip() {
+        let n = Integer::from(0x0102_0304u32);
+        let bytes = integer_to_le_bytes32(&n);
+        assert_eq!(bytes[..5], [0x04, 0x03, 0x02, 0x01, 0x00]);
+        assert_eq!(integer_from_le_bytes(&bytes), n);
+        assert_eq!(integer_from_le_bytes(&[0xe8, 0x03]), 1000);
+
+        let max = (Integer::from(1) << 256u32) - 1u8;
+        assert_eq!(integer_to_le_bytes32(&max), [0xff; 32]);
+        assert_eq!(integer_from_le_bytes(&[0xff; 32]), max);
+    }
+
+    #[cfg(feature = "gmp")]
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_integer_too_large() {
+        integer_to_le_bytes32(&(Integer::from(1) << 256u32));
+    }
+
+    #[test]
+    fn test_reversed_hex() {
+        // the genesis block hash as stored, and as every explorer shows it
+        let stored =
+            hex::decode("6fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000")
+                .unwrap();
+        let hash: [u8; 32] = stored.try_into().unwrap();
+        assert_eq!(
+            ReversedHex(&hash).to_string(),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert_eq!(
+            format!("{:x}", ReversedHex(&hash)),
+            ReversedHex(&hash).to_string()
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod base58;
+pub mod base64;
+pub mod bech32;
+pub mod hex;
+pub mod le;
+pub mod varint;
@@ -0,0 +1,185 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+// Bitcoin's CompactSize: values below 0xfd are a single byte, larger ones are a 0xfd, 0xfe or
+// 0xff prefix followed by 2, 4 or 8 little-endian bytes
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarIntError {
+    UnexpectedEnd,
+    // the value would have fit in a shorter encoding
+    NonCanonical(u64),
+}
+
+impl Display for VarIntError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VarIntError::UnexpectedEnd => write!(f, "varint is cut short"),
+            VarIntError::NonCanonical(n) => write!(f, "varint {n} is not minimally encoded"),
+        }
+    }
+}
+
+impl core::error::Error for VarIntError {}
+
+#[cfg(any(feature = "std", test))]
+impl From<VarIntError> for std::io::Error {
+    fn from(err: VarIntError) -> Self {
+        let kind = match err {
+            VarIntError::UnexpectedEnd => std::io::ErrorKind::UnexpectedEof,
+            VarIntError::NonCanonical(_) => std::io::ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+// always the shortest encoding
+pub fn encode(n: u64) -> Vec<u8> {
+    match n {
+        0..0xfd => vec![n as u8],
+        0xfd..=0xffff => [&[0xfd][..], &(n as u16).to_le_bytes()].concat(),
+        0x10000..=0xffff_ffff => [&[0xfe][..], &(n as u32).to_le_bytes()].concat(),
+        _ => [&[0xff][..], &n.to_le_bytes()].concat(),
+    }
+}
+
+// number of bytes after the first one, and the smallest value allowed for that width
+fn width(prefix: u8) -> (usize, u64) {
+    match prefix {
+        0xfd => (2, 0xfd),
+        0xfe => (4, 0x10000),
+        0xff => (8, 0x1_0000_0000),
+        _ => (0, 0),
+    }
+}
+
+fn check(n: u64, min: u64, strict: bool) -> Result<u64, VarIntError> {
+    if strict && n < min {
+        return Err(VarIntError::NonCanonical(n));
+    }
+    Ok(n)
+}
+
+// (value, bytes consumed) from the start of bytes, anything after the varint is left alone.
+// strict rejects values that have a shorter encoding, as consensus code does
+pub fn decode_from_slice(bytes: &[u8], strict: bool) -> Result<(u64, usize), VarIntError> {
+    let (&prefix, rest) = bytes.split_first().ok_or(VarIntError::UnexpectedEnd)?;
+    let (len, min) = width(prefix);
+    if len == 0 {
+        return Ok((prefix as u64, 1));
+    }
+    let body = rest.get(..len).ok_or(VarIntError::UnexpectedEnd)?;
+    let mut buf = [0u8; 8];
+    buf[..len].copy_from_slice(body);
+    let n = check(u64::from_le_bytes(buf), min, strict)?;
+    Ok((n, 1 + len))
+}
+
+// reads exactly the bytes of one varint, accepting non-minimal encodings
+#[cfg(any(feature = "std", test))]
+pub fn decode(reader: &mut impl std::io::Read) -> std::io::Result<u64> {
+    decode_with(reader, false)
+}
+
+// as decode, but non-minimal encodings are an InvalidData error
+#[cfg(any(feature = "std", test))]
+pub fn decode_strict(reader: &mut impl std::io::Read) -> std::io::Result<u64> {
+    decode_with(reader, true)
+}
+
+#[cfg(any(feature = "std", test))]
+fn decode_with(reader: &mut impl std::io::Read, strict: bool) -> std::io::Result<u64> {
+    let mut prefix = [0u8; 1];
+    reader.read_exact(&mut prefix)?;
+    let (len, min) = width(prefix[0]);
+    if len == 0 {
+        return Ok(prefix[0] as u64);
+    }
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf[..len])?;
+    Ok(check(u64::from_le_bytes(buf), min, strict)?)
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::{RngCore, SeedableRng};
+
+    #[test]
+    fn test_boundaries() {
+        let cases: [(u64, &[u8]); 8] = [
+            (0, &[0x00]),
+            (252, &[0xfc]),
+            (253, &[0xfd, 0xfd, 0x00]),
+            (0xffff, &[0xfd, 0xff, 0xff]),
+            (0x10000, &[0xfe, 0x00, 0x00, 0x01, 0x00]),
+            (0xffff_ffff, &[0xfe, 0xff, 0xff, 0xff, 0xff]),
+            (0x1_0000_0000, &[0xff, 0, 0, 0, 0, 1, 0, 0, 0]),
+            (u64::MAX, &[0xff; 9]),
+        ];
+        for (n, bytes) in cases {
+            assert_eq!(encode(n), bytes);
+            assert_eq!(decode_from_slice(bytes, true), Ok((n, bytes.len())));
+            assert_eq!(decode(&mut &bytes[..]).unwrap(), n);
+            assert_eq!(decode_strict(&mut &bytes[..]).unwrap(), n);
+        }
+    }
+
+    #[test]
+    fn test_non_canonical() {
+        let cases: [(u64, &[u8]); 3] = [
+            (252, &[0xfd, 0xfc, 0x00]),
+            (0xffff, &[0xfe, 0xff, 0xff, 0x00, 0x00]),
+            (0xffff_ffff, &[0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]),
+        ];
+        for (n, bytes) in cases {
+            assert_eq!(decode_from_slice(bytes, false), Ok((n, bytes.len())));
+            assert_eq!(
+                decode_from_slice(bytes, true),
+                Err(VarIntError::NonCanonical(n))
+            );
+            assert_eq!(decode(&mut &bytes[..]).unwrap(), n);
+            let err = decode_strict(&mut &bytes[..]).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_truncated_and_trailing() {
+        assert_eq!(
+            decode_from_slice(&[], false),
+            Err(VarIntError::UnexpectedEnd)
+        );
+        assert_eq!(
+            decode_from_slice(&[0xfe, 0x00, 0x00, 0x01], false),
+            Err(VarIntError::UnexpectedEnd)
+        );
+        let err = decode(&mut &[0xfd, 0x00][..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // the reader is left right after the varint
+        let bytes = [0xfd, 0x00, 0x01, 0xaa, 0xbb];
+        assert_eq!(decode_from_slice(&bytes, true), Ok((0x100, 3)));
+        let mut reader = &bytes[..];
+        assert_eq!(decode(&mut reader).unwrap(), 0x100);
+        assert_eq!(reader, [0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn test_random_round_trips() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(163);
+        for _ in 0..1000 {
+            // a random bit length so every width gets exercised
+            let n = rng.next_u64() >> (rng.next_u32() % 64);
+            let bytes = encode(n);
+            assert_eq!(decode_from_slice(&bytes, true), Ok((n, bytes.len())));
+            assert_eq!(decode_strict(&mut &bytes[..]).unwrap(), n);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ecc::curve::CurveError;
+use crate::ecc::field::FieldError;
+use crate::ecc::secp256k1::Secp256k1Error;
+use crate::script::ScriptError;
+use crate::tx::TxError;
+use std::fmt::Display;
+
+// One error for callers that use more than one layer of the crate. Each layer keeps its own
+// error type, this just wraps whichever one failed so ? works across them
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Field(FieldError),
+    Curve(CurveError),
+    Secp256k1(Secp256k1Error),
+    Tx(TxError),
+    Script(ScriptError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Field(err) => write!(f, "field error: {err}"),
+            Error::Curve(err) => write!(f, "curve error: {err}"),
+            Error::Secp256k1(err) => write!(f, "secp256k1 error: {err}"),
+            Error::Tx(err) => write!(f, "transaction error: {err}"),
+            Error::Script(err) => write!(f, "script error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Field(err) => Some(err),
+            Error::Curve(err) => Some(err),
+            Error::Secp256k1(err) => Some(err),
+            Error::Tx(err) => Some(err),
+            Error::Script(err) => Some(err),
+        }
+    }
+}
+
+impl From<FieldError> for Error {
+    fn from(err: FieldError) -> Self {
+        Error::Field(err)
+    }
+}
+
+impl From<CurveError> for Error {
+    fn from(err: CurveError) -> Self {
+        Error::Curve(err)
+    }
+}
+
+impl From<Secp256k1Error> for Error {
+    fn from(err: Secp256k1Error) -> Self {
+        Error::Secp256k1(err)
+    }
+}
+
+impl From<TxError> for Error {
+    fn from(err: TxError) -> Self {
+        Error::Tx(err)
+    }
+}
+
+impl From<ScriptError> for Error {
+    fn from(err: ScriptError) -> Self {
+        Error::Script(err)
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::curve::{Bounded::Finite, EcPoint};
+    use crate::ecc::field::FieldElement;
+    use crate::ecc::secp256k1::S256Point;
+    use std::error::Error as _;
+
+    fn point_223(x: i32, y: i32) -> Result<EcPoint, Error> {
+        let a = FieldElement::try_new(0, 223)?;
+        let b = FieldElement::try_new(7, 223)?;
+        let x = FieldElement::try_new(x, 223)?;
+        let y = FieldElement::try_new(y, 223)?;
+        Ok(EcPoint::try_new(Finite(x), Finite(y), a, b)?)
+    }
+
+    #[test]
+    fn test_question_mark_across_layers() {
+        assert!(point_223(192, 105).is_ok());
+        assert_eq!(
+            point_223(300, 105),
+            Err(Error::Field(FieldError::ValueOutOfRange))
+        );
+        assert_eq!(
+            point_223(200, 119),
+            Err(Error::Curve(CurveError::NotOnCurve))
+        );
+
+        let parsed: Result<S256Point, Error> = "02zz".parse::<S256Point>().map_err(Error::from);
+        let err = parsed.unwrap_err();
+        assert!(matches!(err, Error::Secp256k1(Secp256k1Error::BadHex)));
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            Secp256k1Error::BadHex.to_string()
+        );
+        assert!(err.to_string().starts_with("secp256k1 error: "));
+    }
+}
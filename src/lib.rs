@@ -0,0 +1,23 @@
+// Without std only the hashes and encoders are built, on top of alloc
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
+
+#[cfg(feature = "gmp")]
+pub mod cli;
+pub mod ecc;
+pub mod encoding;
+#[cfg(feature = "gmp")]
+pub mod error;
+#[cfg(feature = "gmp")]
+pub mod script;
+#[cfg(feature = "gmp")]
+pub mod tx;
+
+#[cfg(feature = "gmp")]
+pub use ecc::curve::{Bounded, EcPoint};
+#[cfg(feature = "gmp")]
+pub use ecc::field::FieldElement;
+#[cfg(feature = "gmp")]
+pub use ecc::secp256k1::S256Point;
+#[cfg(feature = "gmp")]
+pub use error::Error;
@@ -1,10 +1,23 @@
-#![allow(unused)]
-pub mod ecc;
-use Bounded::{Finite, Infinity};
-use ecc::curve::{Bounded, EcPoint};
-use ecc::field::FieldElement;
-use ecc::secp256k1;
+use galactic_credit::cli;
+use std::process::ExitCode;
 
-fn main() {
-    println!("Welcome to the Galactic Empire");
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        println!("Welcome to the Galactic Empire");
+        println!("{}", cli::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    // errors are reported as a message and exit code, never a panic
+    match cli::parse_args(&args).and_then(|command| cli::run(&command)) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(err.exit_code())
+        }
+    }
 }
@@ -0,0 +1,962 @@
+use crate::ecc::hash::hash160;
+use crate::ecc::secp256k1::{Network, S256Point, Signature};
+use crate::encoding::base58::{self, Base58Error};
+use crate::encoding::hex;
+use rug::Integer;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::ops::Add;
+
+// Bitcoin Script, parsed into opcodes and data pushes. Only the opcodes needed for P2PKH, P2SH
+// and bare multisig are evaluated, anything else makes evaluate return false
+
+pub const OP_0: u8 = 0x00;
+pub const OP_PUSHDATA1: u8 = 0x4c;
+pub const OP_PUSHDATA2: u8 = 0x4d;
+pub const OP_PUSHDATA4: u8 = 0x4e;
+pub const OP_1NEGATE: u8 = 0x4f;
+pub const OP_1: u8 = 0x51;
+pub const OP_2: u8 = 0x52;
+pub const OP_16: u8 = 0x60;
+pub const OP_VERIFY: u8 = 0x69;
+pub const OP_DUP: u8 = 0x76;
+pub const OP_EQUAL: u8 = 0x87;
+pub const OP_EQUALVERIFY: u8 = 0x88;
+pub const OP_HASH160: u8 = 0xa9;
+pub const OP_CHECKSIG: u8 = 0xac;
+pub const OP_CHECKMULTISIG: u8 = 0xae;
+
+// OP_CHECKMULTISIG takes at most this many keys
+const MAX_MULTISIG_KEYS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptError {
+    // a push runs past the end of the script
+    Truncated,
+    Base58(Base58Error),
+    UnknownAddressVersion(u8),
+    BadAddressLength(usize),
+    // a token of script text that is neither an opcode name nor hex data
+    BadAssemblyToken(String),
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::Truncated => write!(f, "script push runs past the end of the script"),
+            ScriptError::Base58(err) => write!(f, "address is not valid base58check: {err}"),
+            ScriptError::UnknownAddressVersion(v) => write!(f, "unknown address version {v:#04x}"),
+            ScriptError::BadAddressLength(n) => {
+                write!(f, "address payload is {n} bytes, expected 21")
+            }
+            ScriptError::BadAssemblyToken(token) => {
+                write!(f, "{token:?} is not an opcode or hex data")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<Base58Error> for ScriptError {
+    fn from(err: Base58Error) -> Self {
+        ScriptError::Base58(err)
+    }
+}
+
+// Every opcode with a name in Bitcoin Core, as an enum with the opcode byte as discriminant.
+// The direct pushes 0x01-0x4b have no names, and 0xbb-0xfe are undefined
+macro_rules! opcodes {
+    ($($variant:ident = $byte:literal, $name:literal;)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(u8)]
+        pub enum OpCode {
+            $($variant = $byte,)*
+        }
+
+        impl OpCode {
+            pub const ALL: &[OpCode] = &[$(OpCode::$variant,)*];
+
+            pub fn from_u8(byte: u8) -> Option<OpCode> {
+                match byte {
+                    $($byte => Some(OpCode::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(OpCode::$variant => $name,)*
+                }
+            }
+        }
+    };
+}
+
+opcodes! {
+    Op0 = 0x00, "OP_0";
+    PushData1 = 0x4c, "OP_PUSHDATA1";
+    PushData2 = 0x4d, "OP_PUSHDATA2";
+    PushData4 = 0x4e, "OP_PUSHDATA4";
+    Op1Negate = 0x4f, "OP_1NEGATE";
+    Reserved = 0x50, "OP_RESERVED";
+    Op1 = 0x51, "OP_1";
+    Op2 = 0x52, "OP_2";
+    Op3 = 0x53, "OP_3";
+    Op4 = 0x54, "OP_4";
+    Op5 = 0x55, "OP_5";
+    Op6 = 0x56, "OP_6";
+    Op7 = 0x57, "OP_7";
+    Op8 = 0x58, "OP_8";
+    Op9 = 0x59, "OP_9";
+    Op10 = 0x5a, "OP_10";
+    Op11 = 0x5b, "OP_11";
+    Op12 = 0x5c, "OP_12";
+    Op13 = 0x5d, "OP_13";
+    Op14 = 0x5e, "OP_14";
+    Op15 = 0x5f, "OP_15";
+    Op16 = 0x60, "OP_16";
+    Nop = 0x61, "OP_NOP";
+    Ver = 0x62, "OP_VER";
+    If = 0x63, "OP_IF";
+    NotIf = 0x64, "OP_NOTIF";
+    VerIf = 0x65, "OP_VERIF";
+    VerNotIf = 0x66, "OP_VERNOTIF";
+    Else = 0x67, "OP_ELSE";
+    EndIf = 0x68, "OP_ENDIF";
+    Verify = 0x69, "OP_VERIFY";
+    Return = 0x6a, "OP_RETURN";
+    ToAltStack = 0x6b, "OP_TOALTSTACK";
+    FromAltStack = 0x6c, "OP_FROMALTSTACK";
+    Drop2 = 0x6d, "OP_2DROP";
+    Dup2 = 0x6e, "OP_2DUP";
+    Dup3 = 0x6f, "OP_3DUP";
+    Over2 = 0x70, "OP_2OVER";
+    Rot2 = 0x71, "OP_2ROT";
+    Swap2 = 0x72, "OP_2SWAP";
+    IfDup = 0x73, "OP_IFDUP";
+    Depth = 0x74, "OP_DEPTH";
+    Drop = 0x75, "OP_DROP";
+    Dup = 0x76, "OP_DUP";
+    Nip = 0x77, "OP_NIP";
+    Over = 0x78, "OP_OVER";
+    Pick = 0x79, "OP_PICK";
+    Roll = 0x7a, "OP_ROLL";
+    Rot = 0x7b, "OP_ROT";
+    Swap = 0x7c, "OP_SWAP";
+    Tuck = 0x7d, "OP_TUCK";
+    Cat = 0x7e, "OP_CAT";
+    Substr = 0x7f, "OP_SUBSTR";
+    Left = 0x80, "OP_LEFT";
+    Right = 0x81, "OP_RIGHT";
+    Size = 0x82, "OP_SIZE";
+    Invert = 0x83, "OP_INVERT";
+    And = 0x84, "OP_AND";
+    Or = 0x85, "OP_OR";
+    Xor = 0x86, "OP_XOR";
+    Equal = 0x87, "OP_EQUAL";
+    EqualVerify = 0x88, "OP_EQUALVERIFY";
+    Reserved1 = 0x89, "OP_RESERVED1";
+    Reserved2 = 0x8a, "OP_RESERVED2";
+    Add1 = 0x8b, "OP_1ADD";
+    Sub1 = 0x8c, "OP_1SUB";
+    Mul2 = 0x8d, "OP_2MUL";
+    Div2 = 0x8e, "OP_2DIV";
+    Negate = 0x8f, "OP_NEGATE";
+    Abs = 0x90, "OP_ABS";
+    Not = 0x91, "OP_NOT";
+    NotEqual0 = 0x92, "OP_0NOTEQUAL";
+    Add = 0x93, "OP_ADD";
+    Sub = 0x94, "OP_SUB";
+    Mul = 0x95, "OP_MUL";
+    Div = 0x96, "OP_DIV";
+    Mod = 0x97, "OP_MOD";
+    LShift = 0x98, "OP_LSHIFT";
+    RShift = 0x99, "OP_RSHIFT";
+    BoolAnd = 0x9a, "OP_BOOLAND";
+    BoolOr = 0x9b, "OP_BOOLOR";
+    NumEqual = 0x9c, "OP_NUMEQUAL";
+    NumEqualVerify = 0x9d, "OP_NUMEQUALVERIFY";
+    NumNotEqual = 0x9e, "OP_NUMNOTEQUAL";
+    LessThan = 0x9f, "OP_LESSTHAN";
+    GreaterThan = 0xa0, "OP_GREATERTHAN";
+    LessThanOrEqual = 0xa1, "OP_LESSTHANOREQUAL";
+    GreaterThanOrEqual = 0xa2, "OP_GREATERTHANOREQUAL";
+    Min = 0xa3, "OP_MIN";
+    Max = 0xa4, "OP_MAX";
+    Within = 0xa5, "OP_WITHIN";
+    Ripemd160 = 0xa6, "OP_RIPEMD160";
+    Sha1 = 0xa7, "OP_SHA1";
+    Sha256 = 0xa8, "OP_SHA256";
+    Hash160 = 0xa9, "OP_HASH160";
+    Hash256 = 0xaa, "OP_HASH256";
+    CodeSeparator = 0xab, "OP_CODESEPARATOR";
+    CheckSig = 0xac, "OP_CHECKSIG";
+    CheckSigVerify = 0xad, "OP_CHECKSIGVERIFY";
+    CheckMultiSig = 0xae, "OP_CHECKMULTISIG";
+    CheckMultiSigVerify = 0xaf, "OP_CHECKMULTISIGVERIFY";
+    Nop1 = 0xb0, "OP_NOP1";
+    CheckLockTimeVerify = 0xb1, "OP_CHECKLOCKTIMEVERIFY";
+    CheckSequenceVerify = 0xb2, "OP_CHECKSEQUENCEVERIFY";
+    Nop4 = 0xb3, "OP_NOP4";
+    Nop5 = 0xb4, "OP_NOP5";
+    Nop6 = 0xb5, "OP_NOP6";
+    Nop7 = 0xb6, "OP_NOP7";
+    Nop8 = 0xb7, "OP_NOP8";
+    Nop9 = 0xb8, "OP_NOP9";
+    Nop10 = 0xb9, "OP_NOP10";
+    CheckSigAdd = 0xba, "OP_CHECKSIGADD";
+    InvalidOpCode = 0xff, "OP_INVALIDOPCODE";
+}
+
+impl OpCode {
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    // the inverse of name, which is always the "OP_" form Bitcoin Core prints
+    pub fn from_name(name: &str) -> Option<OpCode> {
+        Self::ALL.iter().copied().find(|op| op.name() == name)
+    }
+}
+
+impl Display for OpCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cmd {
+    Op(u8),
+    // any of the push forms, 0x01-0x4b and OP_PUSHDATA1/2/4. OP_0 stays an Op
+    Push(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Script {
+    pub cmds: Vec<Cmd>,
+}
+
+impl Script {
+    pub fn new(cmds: Vec<Cmd>) -> Self {
+        Self { cmds }
+    }
+
+    // OP_DUP OP_HASH160 <hash160> OP_EQUALVERIFY OP_CHECKSIG
+    pub fn p2pkh_script_pubkey(hash160: &[u8; 20]) -> Script {
+        Script::new(vec![
+            Cmd::Op(OP_DUP),
+            Cmd::Op(OP_HASH160),
+            Cmd::Push(hash160.to_vec()),
+            Cmd::Op(OP_EQUALVERIFY),
+            Cmd::Op(OP_CHECKSIG),
+        ])
+    }
+
+    // <DER signature + hash type> <SEC pubkey>
+    pub fn p2pkh_script_sig(sig_der_with_type: &[u8], sec_pubkey: &[u8]) -> Script {
+        Script::new(vec![
+            Cmd::Push(sig_der_with_type.to_vec()),
+            Cmd::Push(sec_pubkey.to_vec()),
+        ])
+    }
+
+    pub fn is_p2pkh(&self) -> bool {
+        self.pubkey_hash().is_some()
+    }
+
+    // the hash160 a P2PKH script_pubkey pays to, None for any other script
+    pub fn pubkey_hash(&self) -> Option<[u8; 20]> {
+        match &self.cmds[..] {
+            [
+                Cmd::Op(OP_DUP),
+                Cmd::Op(OP_HASH160),
+                Cmd::Push(hash),
+                Cmd::Op(OP_EQUALVERIFY),
+                Cmd::Op(OP_CHECKSIG),
+            ] => hash.as_slice().try_into().ok(),
+            _ => None,
+        }
+    }
+
+    // OP_HASH160 <hash160 of the redeem script> OP_EQUAL
+    pub fn p2sh_script_pubkey(hash160: &[u8; 20]) -> Script {
+        Script::new(vec![
+            Cmd::Op(OP_HASH160),
+            Cmd::Push(hash160.to_vec()),
+            Cmd::Op(OP_EQUAL),
+        ])
+    }
+
+    pub fn is_p2sh(&self) -> bool {
+        self.script_hash().is_some()
+    }
+
+    // the redeem script hash a P2SH script_pubkey pays to, None for any other script
+    pub fn script_hash(&self) -> Option<[u8; 20]> {
+        script_hash(&self.cmds)
+    }
+
+    // hash160 of the serialized script, what a P2SH output commits to when this is its redeem
+    // script
+    pub fn hash160(&self) -> [u8; 20] {
+        hash160(&self.serialize())
+    }
+
+    // raw script bytes, without the varint length a transaction puts in front
+    pub fn parse(bytes: &[u8]) -> Result<Script, ScriptError> {
+        let mut cmds = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let op = bytes[i];
+            i += 1;
+            let (len_bytes, len) = match op {
+                0x01..=0x4b => (0, op as usize),
+                OP_PUSHDATA1 => (1, 0),
+                OP_PUSHDATA2 => (2, 0),
+                OP_PUSHDATA4 => (4, 0),
+                _ => {
+                    cmds.push(Cmd::Op(op));
+                    continue;
+                }
+            };
+            let len = match len_bytes {
+                0 => len,
+                n => {
+                    let field = bytes.get(i..i + n).ok_or(ScriptError::Truncated)?;
+                    i += n;
+                    let mut buf = [0u8; 8];
+                    buf[..n].copy_from_slice(field);
+                    usize::try_from(u64::from_le_bytes(buf)).map_err(|_| ScriptError::Truncated)?
+                }
+            };
+            let end = i.checked_add(len).ok_or(ScriptError::Truncated)?;
+            let data = bytes.get(i..end).ok_or(ScriptError::Truncated)?;
+            cmds.push(Cmd::Push(data.to_vec()));
+            i = end;
+        }
+        Ok(Script { cmds })
+    }
+
+    // pushes use the shortest form that fits their length
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for cmd in &self.cmds {
+            match cmd {
+                Cmd::Op(op) => out.push(*op),
+                Cmd::Push(data) => {
+                    let len = data.len();
+                    match len {
+                        0..=0x4b => out.push(len as u8),
+                        0x4c..=0xff => out.extend([OP_PUSHDATA1, len as u8]),
+                        0x100..=0xffff => {
+                            out.push(OP_PUSHDATA2);
+                            out.extend((len as u16).to_le_bytes());
+                        }
+                        _ => {
+                            out.push(OP_PUSHDATA4);
+                            out.extend((len as u32).to_le_bytes());
+                        }
+                    }
+                    out.extend(data);
+                }
+            }
+        }
+        out
+    }
+
+    // Opcodes by name and pushes as hex, separated by spaces, e.g. "OP_DUP OP_HASH160 ab12...
+    // OP_EQUALVERIFY OP_CHECKSIG". Unlike Bitcoin Core, short pushes are not shown as numbers,
+    // so text and script map one to one. Bytes with no opcode show as OP_UNKNOWN_xx
+    pub fn disassemble(&self) -> String {
+        let tokens: Vec<String> = self
+            .cmds
+            .iter()
+            .map(|cmd| match cmd {
+                // an empty push serializes as OP_0
+                Cmd::Push(data) if data.is_empty() => OpCode::Op0.name().to_string(),
+                Cmd::Push(data) => hex::encode(data),
+                Cmd::Op(op) => match OpCode::from_u8(*op) {
+                    Some(op) => op.name().to_string(),
+                    None => format!("OP_UNKNOWN_{op:02x}"),
+                },
+            })
+            .collect();
+        tokens.join(" ")
+    }
+
+    // the inverse of disassemble, for writing scripts in tests. Any whitespace separates tokens
+    pub fn assemble(text: &str) -> Result<Script, ScriptError> {
+        let cmds = text
+            .split_whitespace()
+            .map(|token| {
+                let unknown = token
+                    .strip_prefix("OP_UNKNOWN_")
+                    .filter(|byte| byte.len() == 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .filter(|&byte| OpCode::from_u8(byte).is_none());
+                if let Some(byte) = unknown {
+                    Ok(Cmd::Op(byte))
+                } else if let Some(op) = OpCode::from_name(token) {
+                    Ok(Cmd::Op(op.to_u8()))
+                } else {
+                    hex::decode(token)
+                        .map(Cmd::Push)
+                        .map_err(|_| ScriptError::BadAssemblyToken(token.to_string()))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Script { cmds })
+    }
+
+    // Runs the combined script (script_sig + script_pubkey) with z as the hash OP_CHECKSIG
+    // checks against. True when nothing failed and the top of the stack is true.
+    // When the script ends in a push followed by the P2SH template, that push is the redeem
+    // script (BIP16): it must hash to the template's hash, and then runs in place of the
+    // template on the stack the rest of script_sig left. Only one level is unwrapped
+    pub fn evaluate(&self, z: &Integer) -> bool {
+        let mut cmds: VecDeque<Cmd> = self.cmds.iter().cloned().collect();
+        let mut stack: Vec<Vec<u8>> = Vec::new();
+        let mut redeemed = false;
+        while let Some(cmd) = cmds.pop_front() {
+            let op = match cmd {
+                Cmd::Push(data) => {
+                    match script_hash(cmds.make_contiguous()) {
+                        Some(hash) if !redeemed => {
+                            if hash160(&data) != hash {
+                                return false;
+                            }
+                            let Ok(redeem_script) = Script::parse(&data) else {
+                                return false;
+                            };
+                            cmds = redeem_script.cmds.into();
+                            redeemed = true;
+                        }
+                        _ => stack.push(data),
+                    }
+                    continue;
+                }
+                Cmd::Op(op) => op,
+            };
+            let ok = match op {
+                OP_0 => push_bool(&mut stack, false),
+                OP_1NEGATE => {
+                    stack.push(vec![0x81]);
+                    true
+                }
+                OP_1..=OP_16 => {
+                    stack.push(vec![op - OP_1 + 1]);
+                    true
+                }
+                OP_VERIFY => pop_true(&mut stack),
+                OP_DUP => match stack.last() {
+                    Some(top) => {
+                        stack.push(top.clone());
+                        true
+                    }
+                    None => false,
+                },
+                OP_EQUAL => match (stack.pop(), stack.pop()) {
+                    (Some(a), Some(b)) => push_bool(&mut stack, a == b),
+                    _ => false,
+                },
+                OP_EQUALVERIFY => match (stack.pop(), stack.pop()) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => false,
+                },
+                OP_HASH160 => match stack.pop() {
+                    Some(top) => {
+                        stack.push(hash160(&top).to_vec());
+                        true
+                    }
+                    None => false,
+                },
+                OP_CHECKSIG => match (stack.pop(), stack.pop()) {
+                    (Some(sec), Some(sig)) => push_bool(&mut stack, check_sig(&sec, &sig, z)),
+                    _ => false,
+                },
+                OP_CHECKMULTISIG => check_multisig(&mut stack, z),
+                _ => false,
+            };
+            if !ok {
+                return false;
+            }
+        }
+        pop_true(&mut stack)
+    }
+}
+
+// the script_pubkey a Base58Check P2PKH or P2SH address pays to, and the network its version
+// byte belongs to (Testnet for the versions Testnet, Regtest and Signet share)
+pub fn address_to_script_pubkey(addr: &str) -> Result<(Script, Network), ScriptError> {
+    let payload = base58::decode_check(addr)?;
+    let (&version, hash) = payload
+        .split_first()
+        .ok_or(ScriptError::BadAddressLength(0))?;
+    let hash: [u8; 20] = hash
+        .try_into()
+        .map_err(|_| ScriptError::BadAddressLength(payload.len()))?;
+    if let Some(network) = Network::from_p2pkh_prefix(version) {
+        Ok((Script::p2pkh_script_pubkey(&hash), network))
+    } else if let Some(network) = Network::from_p2sh_prefix(version) {
+        Ok((Script::p2sh_script_pubkey(&hash), network))
+    } else {
+        Err(ScriptError::UnknownAddressVersion(version))
+    }
+}
+
+// the inverse of address_to_script_pubkey, None for scripts that have no address form
+pub fn address_from_script(script: &Script, network: Network) -> Option<String> {
+    let params = network.params();
+    let (version, hash) = match (script.pubkey_hash(), script.script_hash()) {
+        (Some(hash), _) => (params.p2pkh_prefix, hash),
+        (None, Some(hash)) => (params.p2sh_prefix, hash),
+        (None, None) => return None,
+    };
+    Some(base58::encode_check(&[&[version][..], &hash].concat()))
+}
+
+// the hash in OP_HASH160 <20 bytes> OP_EQUAL
+fn script_hash(cmds: &[Cmd]) -> Option<[u8; 20]> {
+    match cmds {
+        [Cmd::Op(OP_HASH160), Cmd::Push(hash), Cmd::Op(OP_EQUAL)] => {
+            hash.as_slice().try_into().ok()
+        }
+        _ => None,
+    }
+}
+
+// script_sig + script_pubkey, the order they run in
+impl Add<&Script> for &Script {
+    type Output = Script;
+
+    fn add(self, other: &Script) -> Self::Output {
+        Script {
+            cmds: [&self.cmds[..], &other.cmds[..]].concat(),
+        }
+    }
+}
+
+// 1 for true and the empty vector for false, as Script encodes numbers
+fn push_bool(stack: &mut Vec<Vec<u8>>, value: bool) -> bool {
+    stack.push(if value { vec![1] } else { Vec::new() });
+    true
+}
+
+// false for an empty stack, and for zero in any of its encodings, including negative zero
+fn pop_true(stack: &mut Vec<Vec<u8>>) -> bool {
+    let Some(top) = stack.pop() else {
+        return false;
+    };
+    match top.split_last() {
+        None => false,
+        Some((&last, rest)) => rest.iter().any(|&b| b != 0) || (last & 0x7f) != 0,
+    }
+}
+
+// <dummy> <sig>... m <key>... n with m <= n <= 20. Each signature has to match one of the keys,
+// in the order the keys are listed. The dummy is popped too, a bug in the original
+// implementation that is now consensus
+fn check_multisig(stack: &mut Vec<Vec<u8>>, z: &Integer) -> bool {
+    let Some(n) = pop_count(stack).filter(|&n| n <= MAX_MULTISIG_KEYS) else {
+        return false;
+    };
+    let Some(keys) = stack.len().checked_sub(n).map(|at| stack.split_off(at)) else {
+        return false;
+    };
+    let Some(m) = pop_count(stack).filter(|&m| m <= n) else {
+        return false;
+    };
+    let Some(sigs) = stack.len().checked_sub(m).map(|at| stack.split_off(at)) else {
+        return false;
+    };
+    if stack.pop().is_none() {
+        return false;
+    }
+    let mut keys = keys.iter();
+    let valid = sigs
+        .iter()
+        .all(|sig| keys.any(|key| check_sig(key, sig, z)));
+    push_bool(stack, valid)
+}
+
+// a non-negative count from the top of the stack, as a little-endian sign-magnitude number of
+// at most 4 bytes
+fn pop_count(stack: &mut Vec<Vec<u8>>) -> Option<usize> {
+    let top = stack.pop()?;
+    if top.len() > 4 || top.last().is_some_and(|b| b & 0x80 != 0) {
+        return None;
+    }
+    Some(top.iter().rev().fold(0, |n, &b| (n << 8) | b as usize))
+}
+
+// sig is DER followed by the one-byte hash type. Anything that doesn't parse just fails the check
+fn check_sig(sec: &[u8], sig: &[u8], z: &Integer) -> bool {
+    let Some((_hash_type, der)) = sig.split_last() else {
+        return false;
+    };
+    match (S256Point::from_sec(sec), Signature::parse_der(der)) {
+        (Ok(point), Ok(sig)) => point.verify(z, &sig),
+        _ => false,
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::secp256k1::PrivateKey;
+    use crate::tx::Tx;
+
+    // chapter 5's transaction, whose only input spends this P2PKH output
+    const CH5_TX: &str = "0100000001813f79011acb80925dfe69b3def355fe914bd1d96a3f5f71bf8303c6a989c7d1000000006b483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278afeffffff02a135ef01000000001976a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac99c39800000000001976a9141c4bc762dd5423e332166702cb75f40df79fea1288ac19430600";
+    const PREV_SCRIPT_PUBKEY: &str = "76a914a802fc56c704ce87c42d7c92eb75e7896bdc41ae88ac";
+
+    // the 2-of-2 multisig redeem script from Programming Bitcoin chapter 8
+    const CH8_REDEEM_SCRIPT: &str = "5221022626e955ea6ea6d98850c994f9107b036b1334f18ca8830bfff1295d21cfdb702103b287eaf122eea69030a0e9feed096bed8045c8b98bec453e1ffac7fbdbd4bb7152ae";
+
+    fn script(script_hex: &str) -> Script {
+        Script::parse(&hex::decode(script_hex).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_serialize() {
+        let script_pubkey = script(PREV_SCRIPT_PUBKEY);
+        assert_eq!(
+            script_pubkey.cmds,
+            [
+                Cmd::Op(OP_DUP),
+                Cmd::Op(OP_HASH160),
+                Cmd::Push(hex::decode("a802fc56c704ce87c42d7c92eb75e7896bdc41ae").unwrap()),
+                Cmd::Op(OP_EQUALVERIFY),
+                Cmd::Op(OP_CHECKSIG),
+            ]
+        );
+        assert_eq!(hex::encode(&script_pubkey.serialize()), PREV_SCRIPT_PUBKEY);
+
+        // PUSHDATA forms in, shortest form back out
+        let long = Script::parse(&[&[OP_PUSHDATA2, 0x00, 0x01][..], &[7; 256]].concat()).unwrap();
+        assert_eq!(long.cmds, [Cmd::Push(vec![7; 256])]);
+        assert_eq!(long.serialize()[..3], [OP_PUSHDATA2, 0x00, 0x01]);
+        assert_eq!(script("4c0199").cmds, [Cmd::Push(vec![0x99])]);
+        assert_eq!(script("4c0199").serialize(), [0x01, 0x99]);
+
+        for truncated in ["02aa", "4c", "4c02aa", "4d01", "4e0000"] {
+            assert_eq!(
+                Script::parse(&hex::decode(truncated).unwrap()),
+                Err(ScriptError::Truncated)
+            );
+        }
+    }
+
+    #[test]
+    fn test_p2pkh_templates() {
+        // the first output of the chapter 5 transaction
+        let expected = "76a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac";
+        let (script_pubkey, network) =
+            address_to_script_pubkey("1JAHBxA51vwp5C2zpSB15VbxSZK3hVJs2H").unwrap();
+        assert_eq!(network, Network::Mainnet);
+        assert_eq!(hex::encode(&script_pubkey.serialize()), expected);
+        assert_eq!(script_pubkey, script(expected));
+        assert!(script_pubkey.is_p2pkh());
+        assert_eq!(
+            hex::encode(&script_pubkey.pubkey_hash().unwrap()),
+            "bc3b654dca7e56b04dca18f2566cdaf02e8d9ada"
+        );
+        assert_eq!(
+            Script::p2pkh_script_pubkey(&script_pubkey.pubkey_hash().unwrap()),
+            script_pubkey
+        );
+
+        for (addr, expected) in [
+            ("1JAHBxA51vwp5C2zpSB15VbxSZK3hVJs2H", Network::Mainnet),
+            ("mi6Zzdd6Wegi1PgcDkyakq1L7CvhBECeLa", Network::Testnet),
+            ("mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA", Network::Testnet),
+        ] {
+            let (script_pubkey, network) = address_to_script_pubkey(addr).unwrap();
+            assert_eq!(network, expected);
+            assert_eq!(
+                address_from_script(&script_pubkey, network).as_deref(),
+                Some(addr)
+            );
+        }
+
+        // the chapter 5 script_sig rebuilt from its two pushes
+        let tx = Tx::parse(&mut &hex::decode(CH5_TX).unwrap()[..]).unwrap();
+        let script_sig = Script::parse(&tx.inputs[0].script_sig).unwrap();
+        let [Cmd::Push(sig), Cmd::Push(sec)] = &script_sig.cmds[..] else {
+            panic!("expected two pushes");
+        };
+        assert_eq!(
+            Script::p2pkh_script_sig(sig, sec).serialize(),
+            tx.inputs[0].script_sig
+        );
+        assert!(!script_sig.is_p2pkh());
+        assert_eq!(address_from_script(&script_sig, Network::Mainnet), None);
+    }
+
+    #[test]
+    fn test_p2sh_templates() {
+        let redeem_script = script(CH8_REDEEM_SCRIPT);
+        let hash = redeem_script.hash160();
+        assert_eq!(
+            hex::encode(&hash),
+            "74d691da1574e6b3c192ecfb52cc8984ee7b6c56"
+        );
+        let script_pubkey = Script::p2sh_script_pubkey(&hash);
+        assert_eq!(
+            hex::encode(&script_pubkey.serialize()),
+            "a91474d691da1574e6b3c192ecfb52cc8984ee7b6c5687"
+        );
+        assert!(script_pubkey.is_p2sh() && !script_pubkey.is_p2pkh());
+        assert_eq!(script_pubkey.script_hash(), Some(hash));
+        assert!(!redeem_script.is_p2sh());
+        assert!(!script(PREV_SCRIPT_PUBKEY).is_p2sh());
+
+        for (addr, expected) in [
+            ("3CLoMMyuoDQTPRD3XYZtCvgvkadrAdvdXh", Network::Mainnet),
+            ("2N3u1R6uwQfuobCqbCgBkpsgBxvr1tZpe7B", Network::Testnet),
+        ] {
+            assert_eq!(
+                address_from_script(&script_pubkey, expected).as_deref(),
+                Some(addr)
+            );
+            assert_eq!(
+                address_to_script_pubkey(addr),
+                Ok((script_pubkey.clone(), expected))
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_p2sh_multisig() {
+        let keys = [
+            PrivateKey::new(Integer::from(8_675_309)),
+            PrivateKey::new(Integer::from(8_675_310)),
+        ];
+        let [sec1, sec2] = keys.each_ref().map(|key| key.public_key().to_sec(true));
+        let redeem_script = Script::new(vec![
+            Cmd::Op(OP_2),
+            Cmd::Push(sec1),
+            Cmd::Push(sec2),
+            Cmd::Op(OP_2),
+            Cmd::Op(OP_CHECKMULTISIG),
+        ]);
+        let script_pubkey = Script::p2sh_script_pubkey(&redeem_script.hash160());
+        let z = Integer::from(0x5ca1ab1e_u32);
+        let [sig1, sig2] = keys.each_ref().map(|key| {
+            let mut sig = key.sign(&z).der();
+            sig.push(0x01);
+            sig
+        });
+        let script_sig = |sigs: &[&Vec<u8>], redeem_script: &Script| {
+            let mut cmds = vec![Cmd::Op(OP_0)];
+            cmds.extend(sigs.iter().map(|sig| Cmd::Push(sig.to_vec())));
+            cmds.push(Cmd::Push(redeem_script.serialize()));
+            Script::new(cmds)
+        };
+
+        let spend = script_sig(&[&sig1, &sig2], &redeem_script);
+        assert!((&spend + &script_pubkey).evaluate(&z));
+        assert!(!(&spend + &script_pubkey).evaluate(&(z.clone() + 1u8)));
+        // signatures out of key order, or one missing
+        let swapped = script_sig(&[&sig2, &sig1], &redeem_script);
+        assert!(!(&swapped + &script_pubkey).evaluate(&z));
+        let one = script_sig(&[&sig1], &redeem_script);
+        assert!(!(&one + &script_pubkey).evaluate(&z));
+        // a redeem script that doesn't hash to the output's hash
+        let mut other_redeem = redeem_script.clone();
+        other_redeem.cmds[0] = Cmd::Op(OP_1);
+        let wrong = script_sig(&[&sig1, &sig2], &other_redeem);
+        assert!(!(&wrong + &script_pubkey).evaluate(&z));
+
+        // the redeem script works as a bare multisig script_pubkey too
+        let bare = Script::new(spend.cmds[..3].to_vec());
+        assert!((&bare + &redeem_script).evaluate(&z));
+    }
+
+    #[test]
+    fn test_address_errors() {
+        assert!(matches!(
+            address_to_script_pubkey("1JAHBxA51vwp5C2zpSB15VbxSZK3hVJs2J"),
+            Err(ScriptError::Base58(Base58Error::BadChecksum))
+        ));
+        // a WIF key is valid base58check, but not an address
+        assert_eq!(
+            address_to_script_pubkey("cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN8rFTv2sfUK"),
+            Err(ScriptError::BadAddressLength(34))
+        );
+        // Litecoin's P2PKH version
+        let other_version = base58::encode_check(&[&[0x30][..], &[0u8; 20]].concat());
+        assert_eq!(
+            address_to_script_pubkey(&other_version),
+            Err(ScriptError::UnknownAddressVersion(0x30))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_p2pkh() {
+        let tx = Tx::parse(&mut &hex::decode(CH5_TX).unwrap()[..]).unwrap();
+        let prev = hex::decode(PREV_SCRIPT_PUBKEY).unwrap();
+        let z = tx.sig_hash(0, &prev).unwrap();
+        let script_sig = Script::parse(&tx.inputs[0].script_sig).unwrap();
+        let script_pubkey = Script::parse(&prev).unwrap();
+        assert!((&script_sig + &script_pubkey).evaluate(&z));
+
+        // flip a byte inside s
+        let mut bad_sig = script_sig.clone();
+        if let Cmd::Push(sig) = &mut bad_sig.cmds[0] {
+            sig[60] ^= 1;
+        }
+        assert!(!(&bad_sig + &script_pubkey).evaluate(&z));
+        // right signature, wrong message
+        assert!(!(&script_sig + &script_pubkey).evaluate(&(z + 1u8)));
+    }
+
+    #[test]
+    fn test_evaluate_small_ops() {
+        let cases = [
+            ("515187", true),
+            ("515287", false),
+            ("51518851", true),
+            ("51528851", false),
+            ("606087", true),
+            ("516951", true),
+            ("006951", false),
+            // 0x80 is negative zero
+            ("0180", false),
+            ("4f", true),
+            ("", false),
+            ("76", false),
+            ("a9", false),
+            // 0-of-0 OP_CHECKMULTISIG with its dummy, then without
+            ("000000ae", true),
+            ("0000ae", false),
+            // OP_ADD is not implemented, so it fails rather than being skipped
+            ("515193", false),
+        ];
+        for (script_hex, expected) in cases {
+            assert_eq!(
+                script(script_hex).evaluate(&Integer::new()),
+                expected,
+                "{script_hex}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_opcode_table() {
+        assert_eq!(OpCode::ALL.len(), 113);
+        for byte in 0..=255u8 {
+            match OpCode::from_u8(byte) {
+                Some(op) => {
+                    assert_eq!(op.to_u8(), byte);
+                    assert_eq!(OpCode::from_name(op.name()), Some(op));
+                }
+                None => assert!(matches!(byte, 0x01..=0x4b | 0xbb..=0xfe), "{byte:#04x}"),
+            }
+        }
+        assert_eq!(OpCode::from_u8(OP_CHECKSIG), Some(OpCode::CheckSig));
+        assert_eq!(OpCode::Hash160.to_u8(), OP_HASH160);
+        assert_eq!(
+            OpCode::from_u8(0xb1).unwrap().name(),
+            "OP_CHECKLOCKTIMEVERIFY"
+        );
+        assert_eq!(OpCode::NotEqual0.to_string(), "OP_0NOTEQUAL");
+        // only the names Bitcoin Core prints, not aliases like OP_TRUE
+        assert_eq!(OpCode::from_name("OP_TRUE"), None);
+        assert_eq!(OpCode::from_name("DUP"), None);
+    }
+
+    #[test]
+    fn test_disassemble_p2pkh() {
+        assert_eq!(
+            script(PREV_SCRIPT_PUBKEY).disassemble(),
+            "OP_DUP OP_HASH160 a802fc56c704ce87c42d7c92eb75e7896bdc41ae OP_EQUALVERIFY OP_CHECKSIG"
+        );
+        assert_eq!(
+            Script::assemble(
+                "OP_DUP OP_HASH160 a802fc56c704ce87c42d7c92eb75e7896bdc41ae OP_EQUALVERIFY OP_CHECKSIG"
+            ),
+            Ok(script(PREV_SCRIPT_PUBKEY))
+        );
+        assert_eq!(Script::default().disassemble(), "");
+        assert_eq!(
+            script("0051bbff").disassemble(),
+            "OP_0 OP_1 OP_UNKNOWN_bb OP_INVALIDOPCODE"
+        );
+    }
+
+    #[test]
+    fn test_assemble_round_trip() {
+        let tx = Tx::parse(&mut &hex::decode(CH5_TX).unwrap()[..]).unwrap();
+        let g = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let g2 = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+        let g3 = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let corpus = [
+            PREV_SCRIPT_PUBKEY.to_string(),
+            hex::encode(&tx.inputs[0].script_sig),
+            hex::encode(&tx.outputs[1].script_pubkey),
+            // the genesis coinbase: a 4-byte push, a 1-byte push and the headline
+            "04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73".to_string(),
+            // the genesis output, pay to an uncompressed key
+            "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac".to_string(),
+            // P2SH, P2WPKH and P2TR outputs
+            "a914748284390f9e263a4b766a75d0633c50426eb87587".to_string(),
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6".to_string(),
+            "512053a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343".to_string(),
+            // 2-of-3 multisig
+            format!("5221{g}21{g2}21{g3}53ae"),
+            // a timelocked P2PKH: <locktime> OP_CHECKLOCKTIMEVERIFY OP_DROP ...
+            "03a08601b17576a914a802fc56c704ce87c42d7c92eb75e7896bdc41ae88ac".to_string(),
+            // OP_RETURN with 80 bytes, the first length that needs OP_PUSHDATA1
+            format!("6a4c50{}", "ab".repeat(80)),
+            // the longest direct push, and the PUSHDATA1 and PUSHDATA2 boundaries
+            format!("4b{}", "01".repeat(75)),
+            format!("4cff{}", "02".repeat(255)),
+            format!("4d0001{}", "03".repeat(256)),
+            // undefined opcodes and the reserved ones
+            "50bbfeff8a89".to_string(),
+        ];
+        for script_hex in &corpus {
+            let original = script(script_hex);
+            let text = original.disassemble();
+            assert_eq!(Script::assemble(&text), Ok(original.clone()), "{text}");
+            assert_eq!(hex::encode(&original.serialize()), *script_hex);
+        }
+
+        // non-minimal pushes read back as the same data, written in the shortest form
+        for non_minimal in ["4c0199", "4d010099", "4e0100000099"] {
+            let text = script(non_minimal).disassemble();
+            assert_eq!(text, "99");
+            assert_eq!(Script::assemble(&text).unwrap().serialize(), [0x01, 0x99]);
+        }
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        for bad in [
+            "OP_DUPP",
+            "OP_UNKNOWN_76",
+            "OP_UNKNOWN_bbb",
+            "abc",
+            "0xab",
+            "op_dup",
+        ] {
+            assert_eq!(
+                Script::assemble(&format!("OP_1 {bad}")),
+                Err(ScriptError::BadAssemblyToken(bad.to_string()))
+            );
+        }
+        // newlines and runs of spaces separate tokens too
+        assert_eq!(
+            Script::assemble("OP_1\n  OP_1   OP_EQUAL"),
+            Ok(script("515187"))
+        );
+    }
+}
@@ -0,0 +1,672 @@
+use crate::ecc::hash::hash256;
+use crate::ecc::secp256k1::PrivateKey;
+use crate::encoding::le::{ReversedHex, read_u32_le, read_u64_le, write_u32_le, write_u64_le};
+use crate::encoding::varint::{self, VarIntError};
+use crate::script::{Cmd, Script};
+use rug::Integer;
+use rug::integer::Order;
+use std::fmt::Display;
+use std::io::{ErrorKind, Read, Write};
+
+// Legacy (pre-segwit) transactions. Scripts are kept as raw bytes
+
+// No count or script length can be larger than a whole block, anything above this is a corrupt
+// or hostile stream rather than something worth trying to allocate for
+const MAX_LEN: u64 = 4_000_000;
+
+pub const SIGHASH_ALL: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxError {
+    Truncated,
+    NonCanonicalVarInt,
+    TooLarge(u64),
+    Io(ErrorKind),
+    InputIndexOutOfRange(usize),
+    // the input's script_sig does not satisfy the script_pubkey it spends
+    InvalidInput(usize),
+    // (inputs, previous outputs) when there isn't one previous output per input
+    PrevOutCount(usize, usize),
+    // (input total, output total) when the outputs spend more than the inputs hold
+    NegativeFee(u64, u64),
+    // a sum of amounts doesn't fit in a u64
+    AmountOverflow,
+}
+
+impl Display for TxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxError::Truncated => write!(f, "transaction data ends early"),
+            TxError::NonCanonicalVarInt => write!(f, "varint in transaction is not minimal"),
+            TxError::TooLarge(n) => write!(f, "count or length {n} is too large for a transaction"),
+            TxError::Io(kind) => write!(f, "error reading transaction: {kind}"),
+            TxError::InputIndexOutOfRange(i) => write!(f, "transaction has no input {i}"),
+            TxError::InvalidInput(i) => write!(f, "input {i} does not verify"),
+            TxError::PrevOutCount(inputs, prevouts) => {
+                write!(f, "{prevouts} previous outputs given for {inputs} inputs")
+            }
+            TxError::NegativeFee(input, output) => {
+                write!(
+                    f,
+                    "outputs total {output} sat, more than the {input} sat inputs"
+                )
+            }
+            TxError::AmountOverflow => write!(f, "amounts add up to more than a u64 holds"),
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+impl From<std::io::Error> for TxError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::UnexpectedEof => TxError::Truncated,
+            kind => TxError::Io(kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tx {
+    pub version: u32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub locktime: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxIn {
+    // in wire order, use encoding::le::ReversedHex to show it the way explorers do
+    pub prev_txid: [u8; 32],
+    pub prev_index: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxOut {
+    // satoshis
+    pub amount: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+// The output an input spends, with what the transaction itself doesn't say about it: the
+// amount and the script. Callers look these up in the previous transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrevOut {
+    // satoshis
+    pub amount: u64,
+    pub script_pubkey: Script,
+}
+
+// a varint that is about to be used as a count or a length
+fn read_len(reader: &mut impl Read) -> Result<u64, TxError> {
+    // the varint's own error is the only InvalidData that means a non-minimal encoding, the
+    // reader may return InvalidData for reasons of its own
+    let n = varint::decode_strict(reader).map_err(|err| {
+        match err.get_ref().and_then(|inner| inner.downcast_ref()) {
+            Some(VarIntError::NonCanonical(_)) => TxError::NonCanonicalVarInt,
+            _ => TxError::from(err),
+        }
+    })?;
+    if n > MAX_LEN {
+        return Err(TxError::TooLarge(n));
+    }
+    Ok(n)
+}
+
+// read_to_end on a Take grows the buffer as bytes arrive, so a lying length can't make us
+// allocate more than the stream actually holds
+fn read_script(reader: &mut impl Read) -> Result<Vec<u8>, TxError> {
+    let len = read_len(reader)?;
+    let mut script = Vec::new();
+    reader.take(len).read_to_end(&mut script)?;
+    if script.len() as u64 != len {
+        return Err(TxError::Truncated);
+    }
+    Ok(script)
+}
+
+fn write_script(writer: &mut impl Write, script: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&varint::encode(script.len() as u64))?;
+    writer.write_all(script)
+}
+
+fn total(mut amounts: impl Iterator<Item = u64>) -> Result<u64, TxError> {
+    amounts
+        .try_fold(0u64, |sum, amount| sum.checked_add(amount))
+        .ok_or(TxError::AmountOverflow)
+}
+
+fn read_vec<R: Read, T>(
+    reader: &mut R,
+    parse: fn(&mut R) -> Result<T, TxError>,
+) -> Result<Vec<T>, TxError> {
+    let count = read_len(reader)?;
+    (0..count).map(|_| parse(reader)).collect()
+}
+
+impl Tx {
+    // version, inputs, outputs, locktime. Reads exactly one transaction and leaves the rest
+    pub fn parse(reader: &mut impl Read) -> Result<Tx, TxError> {
+        Ok(Tx {
+            version: read_u32_le(reader)?,
+            inputs: read_vec(reader, TxIn::parse)?,
+            outputs: read_vec(reader, TxOut::parse)?,
+            locktime: read_u32_le(reader)?,
+        })
+    }
+
+    // the consensus encoding, byte for byte what parse reads
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write_u32_le(writer, self.version)?;
+        writer.write_all(&varint::encode(self.inputs.len() as u64))?;
+        for input in &self.inputs {
+            input.write_to(writer)?;
+        }
+        writer.write_all(&varint::encode(self.outputs.len() as u64))?;
+        for output in &self.outputs {
+            output.write_to(writer)?;
+        }
+        write_u32_le(writer, self.locktime)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_to(&mut out)
+            .expect("writing to a Vec never fails");
+        out
+    }
+
+    // hash256 of the serialization without witness data, in internal byte order. This is what
+    // prev_txid holds. A wtxid, once there is witness support, hashes the witness serialization
+    // instead and leaves this alone
+    pub fn hash(&self) -> [u8; 32] {
+        hash256(&self.serialize())
+    }
+
+    // sum of the output amounts in satoshis
+    pub fn total_output(&self) -> Result<u64, TxError> {
+        total(self.outputs.iter().map(|output| output.amount))
+    }
+
+    // input total minus output total, where prevouts[i] is the output inputs[i] spends
+    pub fn fee(&self, prevouts: &[PrevOut]) -> Result<u64, TxError> {
+        if prevouts.len() != self.inputs.len() {
+            return Err(TxError::PrevOutCount(self.inputs.len(), prevouts.len()));
+        }
+        let input = total(prevouts.iter().map(|prevout| prevout.amount))?;
+        let output = self.total_output()?;
+        input
+            .checked_sub(output)
+            .ok_or(TxError::NegativeFee(input, output))
+    }
+
+    // hash, byte-reversed to hex, the way block explorers and RPCs show it
+    pub fn txid(&self) -> String {
+        ReversedHex(&self.hash()).to_string()
+    }
+
+    // Legacy SIGHASH_ALL: the transaction with input_index's script_sig replaced by the
+    // script_pubkey it spends and every other script_sig emptied, then the 4-byte hash type,
+    // hash256'd and read big-endian
+    pub fn sig_hash(&self, input_index: usize, script_pubkey: &[u8]) -> Result<Integer, TxError> {
+        if input_index >= self.inputs.len() {
+            return Err(TxError::InputIndexOutOfRange(input_index));
+        }
+        let mut tx = self.clone();
+        for (i, input) in tx.inputs.iter_mut().enumerate() {
+            input.script_sig = if i == input_index {
+                script_pubkey.to_vec()
+            } else {
+                Vec::new()
+            };
+        }
+        let mut preimage = tx.serialize();
+        write_u32_le(&mut preimage, SIGHASH_ALL).expect("writing to a Vec never fails");
+        Ok(Integer::from_digits(&hash256(&preimage), Order::Msf))
+    }
+
+    // runs input_index's script_sig against the script_pubkey of the output it spends. A P2SH
+    // input's signatures cover its redeem script, the last push of script_sig, in place of the
+    // script_pubkey
+    pub fn verify_input(
+        &self,
+        input_index: usize,
+        prev_script_pubkey: &Script,
+    ) -> Result<bool, TxError> {
+        let input = self
+            .inputs
+            .get(input_index)
+            .ok_or(TxError::InputIndexOutOfRange(input_index))?;
+        let script_sig = match Script::parse(&input.script_sig) {
+            Ok(script_sig) => script_sig,
+            Err(_) => return Ok(false),
+        };
+        let signed_script = match (prev_script_pubkey.is_p2sh(), script_sig.cmds.last()) {
+            (true, Some(Cmd::Push(redeem_script))) => redeem_script.clone(),
+            _ => prev_script_pubkey.serialize(),
+        };
+        let z = self.sig_hash(input_index, &signed_script)?;
+        Ok((&script_sig + prev_script_pubkey).evaluate(&z))
+    }
+
+    // Signs a P2PKH input with SIGHASH_ALL and the compressed SEC key, then checks the result.
+    // If it doesn't verify (usually the wrong key for the output) the input is left as it was
+    pub fn sign_input(
+        &mut self,
+        input_index: usize,
+        key: &PrivateKey,
+        prev_script_pubkey: &Script,
+    ) -> Result<(), TxError> {
+        let z = self.sig_hash(input_index, &prev_script_pubkey.serialize())?;
+        let mut sig = key.sign(&z).der();
+        sig.push(SIGHASH_ALL as u8);
+        let sec = key.public_key().to_sec(true);
+        let script_sig = Script::p2pkh_script_sig(&sig, &sec).serialize();
+
+        let old = std::mem::replace(&mut self.inputs[input_index].script_sig, script_sig);
+        if !self.verify_input(input_index, prev_script_pubkey)? {
+            self.inputs[input_index].script_sig = old;
+            return Err(TxError::InvalidInput(input_index));
+        }
+        Ok(())
+    }
+}
+
+impl TxIn {
+    pub fn parse(reader: &mut impl Read) -> Result<TxIn, TxError> {
+        let mut prev_txid = [0u8; 32];
+        reader.read_exact(&mut prev_txid)?;
+        Ok(TxIn {
+            prev_txid,
+            prev_index: read_u32_le(reader)?,
+            script_sig: read_script(reader)?,
+            sequence: read_u32_le(reader)?,
+        })
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.prev_txid)?;
+        write_u32_le(writer, self.prev_index)?;
+        write_script(writer, &self.script_sig)?;
+        write_u32_le(writer, self.sequence)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_to(&mut out)
+            .expect("writing to a Vec never fails");
+        out
+    }
+}
+
+impl TxOut {
+    pub fn parse(reader: &mut impl Read) -> Result<TxOut, TxError> {
+        Ok(TxOut {
+            amount: read_u64_le(reader)?,
+            script_pubkey: read_script(reader)?,
+        })
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write_u64_le(writer, self.amount)?;
+        write_script(writer, &self.script_pubkey)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_to(&mut out)
+            .expect("writing to a Vec never fails");
+        out
+    }
+}
+
+/*
+*********
+* Tests *
+*********
+* */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecc::secp256k1::{S256Point, Signature};
+    use crate::encoding::hex;
+    use crate::script::{OP_0, OP_2, OP_CHECKMULTISIG, address_to_script_pubkey};
+
+    // the transaction parsed throughout chapter 5 of Programming Bitcoin
+    const CH5_TX: &str = "0100000001813f79011acb80925dfe69b3def355fe914bd1d96a3f5f71bf8303c6a989c7d1000000006b483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278afeffffff02a135ef01000000001976a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac99c39800000000001976a9141c4bc762dd5423e332166702cb75f40df79fea1288ac19430600";
+
+    // chapter 5 exercise 5: four inputs, two outputs
+    const FOUR_INPUTS_TX: &str = "010000000456919960ac691763688d3d3bcea9ad6ecaf875df5339e148a1fc61c6ed7a069e010000006a47304402204585bcdef85e6b1c6af5c2669d4830ff86e42dd205c0e089bc2a821657e951c002201024a10366077f87d6bce1f7100ad8cfa8a064b39d4e8fe4ea13a7b71aa8180f012102f0da57e85eec2934a82a585ea337ce2f4998b50ae699dd79f5880e253dafafb7feffffffeb8f51f4038dc17e6313cf831d4f02281c2a468bde0fafd37f1bf882729e7fd3000000006a47304402207899531a52d59a6de200179928ca900254a36b8dff8bb75f5f5d71b1cdc26125022008b422690b8461cb52c3cc30330b23d574351872b7c361e9aae3649071c1a7160121035d5c93d9ac96881f19ba1f686f15f009ded7c62efe85a872e6a19b43c15a2937feffffff567bf40595119d1bb8a3037c356efd56170b64cbcc160fb028fa10704b45d775000000006a47304402204c7c7818424c7f7911da6cddc59655a70af1cb5eaf17c69dadbfc74ffa0b662f02207599e08bc8023693ad4e9527dc42c34210f7a7d1d1ddfc8492b654a11e7620a0012102158b46fbdff65d0172b7989aec8850aa0dae49abfb84c81ae6e5b251a58ace5cfeffffffd63a5e6c16e620f86f375925b21cabaf736c779f88fd04dcad51d26690f7f345010000006a47304402200633ea0d3314bea0d95b3cd8dadb2ef79ea8331ffe1e61f762c0f6daea0fabde022029f23b3e9c30f080446150b23852028751635dcee2be669c2a1686a4b5edf304012103ffd6f4a67e94aba353a00882e563ff2722eb4cff0ad6006e86ee20dfe7520d55feffffff0251430f00000000001976a914ab0c0b2e98b1ab6dbf67d4750b0a56244948a87988ac005a6202000000001976a9143c82d7df364eb6c75be8c80df2b3eda8db57397088ac46430600";
+
+    // the output of test_sign_input, checked independently against RFC 6979 and the sighash
+    const SIGNED_TX: &str = "01000000011111111111111111111111111111111111111111111111111111111111111111000000006a47304402202c324e25ba73a9105a5082cb72da8fcb53bf286bbc114d359aad8845e2fdf60c02205cd5d1692891d54f89a4a3900931f2e09f2e08886bbea4d65e89bf76803d38670121030f85cb0c917647fadfd31e641231d3a01ac9d3d8a680aab2457e0036bf34d37effffffff01301b0f00000000001976a9147184c9c226be8b92253f539f0b5c0c4016ad9eb088ac00000000";
+
+    fn parse_hex(tx_hex: &str) -> Result<Tx, TxError> {
+        Tx::parse(&mut &hex::decode(tx_hex).unwrap()[..])
+    }
+
+    #[test]
+    fn test_parse_chapter5() {
+        let tx = parse_hex(CH5_TX).unwrap();
+        assert_eq!(tx.version, 1);
+        assert_eq!(tx.locktime, 410393);
+
+        assert_eq!(tx.inputs.len(), 1);
+        let input = &tx.inputs[0];
+        assert_eq!(
+            ReversedHex(&input.prev_txid).to_string(),
+            "d1c789a9c60383bf715f3f6ad9d14b91fe55f3deb369fe5d9280cb1a01793f81"
+        );
+        assert_eq!(input.prev_index, 0);
+        assert_eq!(
+            hex::encode(&input.script_sig),
+            "483045022100ed81ff192e75a3fd2304004dcadb746fa5e24c5031ccfcf21320b0277457c98f02207a986d955c6e0cb35d446a89d3f56100f4d7f67801c31967743a9c8e10615bed01210349fc4e631e3624a545de3f89f5d8684c7b8138bd94bdd531d2e213bf016b278a"
+        );
+        assert_eq!(input.sequence, 0xffff_fffe);
+
+        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.outputs[0].amount, 32454049);
+        assert_eq!(
+            hex::encode(&tx.outputs[0].script_pubkey),
+            "76a914bc3b654dca7e56b04dca18f2566cdaf02e8d9ada88ac"
+        );
+        assert_eq!(tx.outputs[1].amount, 10011545);
+        assert_eq!(
+            hex::encode(&tx.outputs[1].script_pubkey),
+            "76a9141c4bc762dd5423e332166702cb75f40df79fea1288ac"
+        );
+    }
+
+    #[test]
+    fn test_fee() {
+        // Programming Bitcoin's fee example: the chapter 5 transaction pays 40,000 sat, so its
+        // one input spends 42,505,594 sat
+        let tx = parse_hex(CH5_TX).unwrap();
+        let prevout = PrevOut {
+            amount: 42_505_594,
+            script_pubkey: Script::parse(
+                &hex::decode("76a914a802fc56c704ce87c42d7c92eb75e7896bdc41ae88ac").unwrap(),
+            )
+            .unwrap(),
+        };
+        assert_eq!(tx.total_output(), Ok(42_465_594));
+        assert_eq!(tx.fee(std::slice::from_ref(&prevout)), Ok(40_000));
+        assert_eq!(tx.verify_input(0, &prevout.script_pubkey), Ok(true));
+
+        assert_eq!(tx.fee(&[]), Err(TxError::PrevOutCount(1, 0)));
+        assert_eq!(
+            tx.fee(&[prevout.clone(), prevout.clone()]),
+            Err(TxError::PrevOutCount(1, 2))
+        );
+
+        // paying out exactly the inputs is a zero fee, one satoshi more is an error
+        let mut inflated = tx.clone();
+        inflated.outputs[0].amount += 40_000;
+        assert_eq!(inflated.fee(std::slice::from_ref(&prevout)), Ok(0));
+        inflated.outputs[0].amount += 1;
+        assert_eq!(
+            inflated.fee(std::slice::from_ref(&prevout)),
+            Err(TxError::NegativeFee(42_505_594, 42_505_595))
+        );
+    }
+
+    #[test]
+    fn test_fee_overflow() {
+        let mut tx = parse_hex(CH5_TX).unwrap();
+        let prevout = |amount| PrevOut {
+            amount,
+            script_pubkey: Script::default(),
+        };
+        tx.outputs[0].amount = u64::MAX - 10_011_545;
+        assert_eq!(tx.total_output(), Ok(u64::MAX));
+        assert_eq!(tx.fee(&[prevout(u64::MAX)]), Ok(0));
+
+        tx.outputs[0].amount += 1;
+        assert_eq!(tx.total_output(), Err(TxError::AmountOverflow));
+        assert_eq!(tx.fee(&[prevout(u64::MAX)]), Err(TxError::AmountOverflow));
+
+        // inputs that overflow are caught before the outputs are looked at
+        tx.inputs.push(tx.inputs[0].clone());
+        tx.outputs[0].amount = 1;
+        assert_eq!(
+            tx.fee(&[prevout(u64::MAX), prevout(1)]),
+            Err(TxError::AmountOverflow)
+        );
+        assert_eq!(
+            tx.fee(&[prevout(u64::MAX), prevout(0)]),
+            Ok(u64::MAX - 10_011_546)
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        for tx_hex in [CH5_TX, FOUR_INPUTS_TX] {
+            let tx = parse_hex(tx_hex).unwrap();
+            assert_eq!(hex::encode(&tx.serialize()), tx_hex);
+            assert_eq!(Tx::parse(&mut &tx.serialize()[..]).unwrap(), tx);
+        }
+
+        let tx = parse_hex(FOUR_INPUTS_TX).unwrap();
+        assert_eq!(tx.inputs.len(), 4);
+        assert_eq!(tx.inputs[3].prev_index, 1);
+        assert_eq!(tx.outputs[1].amount, 40000000);
+        assert_eq!(tx.locktime, 410438);
+        assert_eq!(
+            hex::encode(&tx.outputs[0].serialize()),
+            "51430f00000000001976a914ab0c0b2e98b1ab6dbf67d4750b0a56244948a87988ac"
+        );
+    }
+
+    #[test]
+    fn test_serialize_wide_varints() {
+        // 253 outputs and a 300 byte script need the 0xfd form for both counts and lengths
+        let tx = Tx {
+            version: 2,
+            inputs: vec![TxIn {
+                prev_txid: [0xab; 32],
+                prev_index: 0xffff_ffff,
+                script_sig: vec![0x51; 300],
+                sequence: 0xffff_ffff,
+            }],
+            outputs: vec![
+                TxOut {
+                    amount: 21_000_000 * 100_000_000,
+                    script_pubkey: vec![0x6a],
+                };
+                253
+            ],
+            locktime: 0,
+        };
+        let bytes = tx.serialize();
+        assert_eq!(hex::encode(&bytes[..5]), "0200000001");
+        assert_eq!(hex::encode(&bytes[41..44]), "fd2c01");
+        assert_eq!(hex::encode(&bytes[348..351]), "fdfd00");
+        assert_eq!(bytes.len(), 4 + 1 + 32 + 4 + 3 + 300 + 4 + 3 + 253 * 10 + 4);
+        assert_eq!(Tx::parse(&mut &bytes[..]).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_txid() {
+        let tx = parse_hex(CH5_TX).unwrap();
+        assert_eq!(
+            tx.txid(),
+            "452c629d67e41baec3ac6f04fe744b4b9617f8f859c63b3002f8684e7a4fee03"
+        );
+        assert_eq!(tx.hash()[0], 0x03);
+        assert_eq!(
+            Tx::parse(&mut &tx.serialize()[..]).unwrap().txid(),
+            tx.txid()
+        );
+
+        // an input spending it stores hash, which reads back as the txid
+        let input = TxIn {
+            prev_txid: tx.hash(),
+            prev_index: 0,
+            script_sig: Vec::new(),
+            sequence: 0xffff_ffff,
+        };
+        assert_eq!(ReversedHex(&input.prev_txid).to_string(), tx.txid());
+    }
+
+    #[test]
+    fn test_sig_hash_chapter7() {
+        let tx = parse_hex(CH5_TX).unwrap();
+        let prev_script =
+            hex::decode("76a914a802fc56c704ce87c42d7c92eb75e7896bdc41ae88ac").unwrap();
+        let z = tx.sig_hash(0, &prev_script).unwrap();
+        assert_eq!(
+            format!("{z:064x}"),
+            "27e0c5994dec7824e56dec6b2fcb342eb7cdb0d0957c2fce9882f715e85d81a6"
+        );
+
+        // script_sig is <DER signature + hash type> <SEC pubkey>
+        let script_sig = &tx.inputs[0].script_sig;
+        let sig_len = script_sig[0] as usize;
+        assert_eq!(script_sig[sig_len], SIGHASH_ALL as u8);
+        let sig = Signature::parse_der(&script_sig[1..sig_len]).unwrap();
+        let point = S256Point::from_sec(&script_sig[sig_len + 2..]).unwrap();
+        assert!(point.verify(&z, &sig));
+        assert!(!point.verify(&tx.sig_hash(0, &[]).unwrap(), &sig));
+
+        assert_eq!(
+            tx.sig_hash(1, &prev_script),
+            Err(TxError::InputIndexOutOfRange(1))
+        );
+    }
+
+    #[test]
+    fn test_sign_input() {
+        // secret 5002 pays itself back on testnet, minus a 10000 sat fee
+        let key = PrivateKey::new(Integer::from(5002));
+        let (prev_script_pubkey, _) =
+            address_to_script_pubkey("mqsBj1baxzgPTeeRJbE8cbgdYmtc3yess3").unwrap();
+        let mut tx = Tx {
+            version: 1,
+            inputs: vec![TxIn {
+                prev_txid: [0x11; 32],
+                prev_index: 0,
+                script_sig: Vec::new(),
+                sequence: 0xffff_ffff,
+            }],
+            outputs: vec![TxOut {
+                amount: 990_000,
+                script_pubkey: prev_script_pubkey.serialize(),
+            }],
+            locktime: 0,
+        };
+        assert_eq!(tx.verify_input(0, &prev_script_pubkey), Ok(false));
+        tx.sign_input(0, &key, &prev_script_pubkey).unwrap();
+        assert_eq!(tx.verify_input(0, &prev_script_pubkey), Ok(true));
+        assert_eq!(hex::encode(&tx.serialize()), SIGNED_TX);
+
+        // another key can't spend it, and the input is left untouched
+        let mut unsigned = tx.clone();
+        unsigned.inputs[0].script_sig.clear();
+        let mut attempt = unsigned.clone();
+        assert_eq!(
+            attempt.sign_input(
+                0,
+                &PrivateKey::new(Integer::from(5003)),
+                &prev_script_pubkey
+            ),
+            Err(TxError::InvalidInput(0))
+        );
+        assert_eq!(attempt, unsigned);
+        assert_eq!(
+            attempt.sign_input(1, &key, &prev_script_pubkey),
+            Err(TxError::InputIndexOutOfRange(1))
+        );
+    }
+
+    #[test]
+    fn test_verify_p2sh_input() {
+        // a 2-of-2 multisig behind a P2SH output, spent with both signatures
+        let keys = [
+            PrivateKey::new(Integer::from(5002)),
+            PrivateKey::new(Integer::from(5003)),
+        ];
+        let mut cmds = vec![Cmd::Op(OP_2)];
+        cmds.extend(
+            keys.iter()
+                .map(|key| Cmd::Push(key.public_key().to_sec(true))),
+        );
+        cmds.extend([Cmd::Op(OP_2), Cmd::Op(OP_CHECKMULTISIG)]);
+        let redeem_script = Script::new(cmds);
+        let prev_script_pubkey = Script::p2sh_script_pubkey(&redeem_script.hash160());
+        let mut tx = Tx {
+            version: 1,
+            inputs: vec![TxIn {
+                prev_txid: [0x22; 32],
+                prev_index: 0,
+                script_sig: Vec::new(),
+                sequence: 0xffff_ffff,
+            }],
+            outputs: vec![TxOut {
+                amount: 990_000,
+                script_pubkey: prev_script_pubkey.serialize(),
+            }],
+            locktime: 0,
+        };
+
+        // the signatures cover the redeem script, not the script_pubkey
+        let script_sig = |signed_script: &[u8]| {
+            let z = tx.sig_hash(0, signed_script).unwrap();
+            let mut cmds = vec![Cmd::Op(OP_0)];
+            cmds.extend(keys.iter().map(|key| {
+                let mut sig = key.sign(&z).der();
+                sig.push(SIGHASH_ALL as u8);
+                Cmd::Push(sig)
+            }));
+            cmds.push(Cmd::Push(redeem_script.serialize()));
+            Script::new(cmds).serialize()
+        };
+        let good = script_sig(&redeem_script.serialize());
+        let bad = script_sig(&prev_script_pubkey.serialize());
+        tx.inputs[0].script_sig = good;
+        assert_eq!(tx.verify_input(0, &prev_script_pubkey), Ok(true));
+        tx.inputs[0].script_sig = bad;
+        assert_eq!(tx.verify_input(0, &prev_script_pubkey), Ok(false));
+        assert_eq!(
+            tx.verify_input(1, &prev_script_pubkey),
+            Err(TxError::InputIndexOutOfRange(1))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // every proper prefix is truncated, never a panic
+        for end in (0..CH5_TX.len()).step_by(2) {
+            assert_eq!(parse_hex(&CH5_TX[..end]), Err(TxError::Truncated));
+        }
+
+        // an input count of 2^64 - 1
+        let absurd = format!("01000000ff{}", "ff".repeat(8));
+        assert_eq!(parse_hex(&absurd), Err(TxError::TooLarge(u64::MAX)));
+        // a script claiming 16 MB with a few bytes behind it
+        let long_script = format!("01000000{}{}fe00000001aabb", "01", "00".repeat(36));
+        assert_eq!(parse_hex(&long_script), Err(TxError::TooLarge(0x0100_0000)));
+        // one input, written as 0xfd 0x01 0x00
+        assert_eq!(
+            parse_hex("01000000fd0100"),
+            Err(TxError::NonCanonicalVarInt)
+        );
+
+        // InvalidData from the reader itself is passed on, not taken for a varint problem
+        struct BadReader;
+        impl Read for BadReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    "not a transaction",
+                ))
+            }
+        }
+        assert_eq!(
+            Tx::parse(&mut BadReader),
+            Err(TxError::Io(ErrorKind::InvalidData))
+        );
+    }
+}
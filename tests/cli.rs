@@ -0,0 +1,147 @@
+#![cfg(feature = "gmp")]
+// The command functions directly, plus a few runs of the real binary for exit codes
+use galactic_credit::Error;
+use galactic_credit::cli::{self, CliError, Command};
+use galactic_credit::ecc::secp256k1::{Network, PrivateKey, Secp256k1Error};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use std::process::Command as Process;
+
+// secret 5002 from Programming Bitcoin chapter 4
+const PRIV: &str = "000000000000000000000000000000000000000000000000000000000000138a";
+const PUB: &str = "040f85cb0c917647fadfd31e641231d3a01ac9d3d8a680aab2457e0036bf34d37e6949b302843a33cdf068589c8330bb5c09c1739420d65b7b33cfab6d10118d97";
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+fn binary(line: &str) -> std::process::Output {
+    Process::new(env!("CARGO_BIN_EXE_galactic_credit"))
+        .args(args(line))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_parse_args() {
+    assert_eq!(
+        cli::parse_args(&args("pubkey --compressed abcd")),
+        Ok(Command::Pubkey {
+            key: "abcd".to_string(),
+            compressed: true
+        })
+    );
+    assert_eq!(
+        cli::parse_args(&args("address abcd --testnet")),
+        Ok(Command::Address {
+            key: "abcd".to_string(),
+            network: Network::Testnet
+        })
+    );
+    for bad in [
+        "",
+        "mint 1",
+        "sign abcd",
+        "verify a b c d",
+        "pubkey abcd --testnet",
+    ] {
+        assert!(
+            matches!(cli::parse_args(&args(bad)), Err(CliError::Usage(_))),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn test_keygen() {
+    let mut rng = ChaCha20Rng::seed_from_u64(7);
+    let out = cli::keygen(&mut rng, Network::Mainnet);
+    let (hex, wif) = out.split_once('\n').unwrap();
+    let (key, compressed, network) = PrivateKey::from_wif(wif).unwrap();
+    assert_eq!(key.reveal_hex(), hex);
+    assert!(compressed);
+    assert_eq!(network, Network::Mainnet);
+}
+
+#[test]
+fn test_binary_keygen() {
+    let out = binary("keygen --testnet");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let (hex, wif) = stdout.trim().split_once('\n').unwrap();
+    let (key, compressed, network) = PrivateKey::from_wif(wif).unwrap();
+    assert_eq!(key.reveal_hex(), hex);
+    assert_eq!((compressed, network), (true, Network::Testnet));
+    assert_eq!(key.wif(compressed, network), wif);
+    // a fresh key from the OS rng each run
+    assert_ne!(binary("keygen").stdout, stdout.into_bytes());
+}
+
+#[test]
+fn test_pubkey_and_address() {
+    assert_eq!(cli::pubkey(PRIV, false).unwrap(), PUB);
+    assert_eq!(
+        cli::pubkey(PRIV, true).unwrap(),
+        "030f85cb0c917647fadfd31e641231d3a01ac9d3d8a680aab2457e0036bf34d37e"
+    );
+    assert_eq!(
+        cli::address(PUB, Network::Testnet).unwrap(),
+        "mmTPbXQFxboEtNRkwfh6K51jvdtHLxGeMA"
+    );
+    let compressed = cli::pubkey(PRIV, true).unwrap();
+    assert_eq!(
+        cli::address(&compressed, Network::Testnet).unwrap(),
+        "mqsBj1baxzgPTeeRJbE8cbgdYmtc3yess3"
+    );
+    assert_eq!(
+        cli::address(&format!("0x{compressed}"), Network::Testnet).unwrap(),
+        "mqsBj1baxzgPTeeRJbE8cbgdYmtc3yess3"
+    );
+}
+
+#[test]
+fn test_sign_verify() {
+    let sig = cli::sign(PRIV, "my message").unwrap();
+    assert_eq!(cli::verify(PUB, "my message", &sig), Ok("ok".to_string()));
+    assert_eq!(
+        cli::verify(PUB, "my massage", &sig),
+        Err(CliError::SignatureMismatch)
+    );
+}
+
+#[test]
+fn test_invalid_inputs() {
+    let invalid = |err| Err(CliError::Invalid(Error::Secp256k1(err)));
+    assert_eq!(cli::pubkey("zz", false), invalid(Secp256k1Error::BadHex));
+    assert_eq!(
+        cli::pubkey("abcd", false),
+        invalid(Secp256k1Error::BadHexLength)
+    );
+    assert_eq!(
+        cli::pubkey(&"0".repeat(64), false),
+        invalid(Secp256k1Error::SecretOutOfRange)
+    );
+    assert_eq!(
+        cli::address("02aa", Network::Mainnet),
+        invalid(Secp256k1Error::InvalidSecLength)
+    );
+    assert!(matches!(
+        cli::verify(PUB, "msg", "3000"),
+        Err(CliError::Invalid(Error::Secp256k1(_)))
+    ));
+}
+
+#[test]
+fn test_binary_exit_codes() {
+    let out = binary(&format!("pubkey {PRIV}"));
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), PUB);
+
+    let out = binary("pubkey nothex");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.starts_with("error: "), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+
+    assert_eq!(binary("frobnicate").status.code(), Some(2));
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "gmp")]
+// Uses galactic_credit the way a dependent crate would, through its public API only
+use galactic_credit::ecc::curve::CurveError;
+use galactic_credit::ecc::field::FieldError;
+use galactic_credit::ecc::secp256k1::{PrivateKey, Secp256k1Error, Signature};
+use galactic_credit::{Bounded, EcPoint, Error, FieldElement, S256Point, ec_point, field_element};
+use rug::Integer;
+
+#[test]
+fn test_macros_and_field_arithmetic() {
+    let a = field_element!(7, 13);
+    let b = field_element!(12, 13);
+    assert_eq!(&a + &b, field_element!(6, 13));
+    assert_eq!(&a * &b, FieldElement::new(6, 13));
+}
+
+#[test]
+fn test_small_curve_points() {
+    let a = field_element!(0, 223);
+    let b = field_element!(7, 223);
+    let p1 = ec_point!(
+        Bounded::Finite(field_element!(192, 223)),
+        Bounded::Finite(field_element!(105, 223)),
+        a.clone(),
+        b.clone()
+    );
+    let p2 = ec_point!(
+        Bounded::Finite(field_element!(17, 223)),
+        Bounded::Finite(field_element!(56, 223)),
+        a.clone(),
+        b.clone()
+    );
+    let sum = EcPoint::new(
+        Bounded::Finite(field_element!(170, 223)),
+        Bounded::Finite(field_element!(142, 223)),
+        a,
+        b,
+    );
+    assert_eq!(&p1 + &p2, sum);
+}
+
+#[test]
+fn test_secp256k1_sign_verify() {
+    let key = PrivateKey::new(Integer::from(12345));
+    let z = Integer::from_str_radix(
+        "969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48",
+        16,
+    )
+    .unwrap();
+    let sig = key.sign(&z);
+    let point: S256Point = key.public_key().to_string().parse().unwrap();
+    assert!(point.verify(&z, &sig));
+
+    let parsed = Signature::parse_der(&sig.der()).unwrap();
+    assert_eq!(parsed, sig);
+    assert!(!point.verify(&(z + 1u8), &parsed));
+}
+
+fn parse_point(x: i32, y: i32) -> Result<EcPoint, Error> {
+    let x = FieldElement::try_new(x, 223)?;
+    let y = FieldElement::try_new(y, 223)?;
+    let a = FieldElement::try_new(0, 223)?;
+    let b = FieldElement::try_new(7, 223)?;
+    Ok(EcPoint::try_new(
+        Bounded::Finite(x),
+        Bounded::Finite(y),
+        a,
+        b,
+    )?)
+}
+
+#[test]
+fn test_errors_match_on_variants() {
+    assert!(parse_point(192, 105).is_ok());
+    assert!(matches!(
+        parse_point(223, 105),
+        Err(Error::Field(FieldError::ValueOutOfRange))
+    ));
+    assert!(matches!(
+        parse_point(200, 119),
+        Err(Error::Curve(CurveError::NotOnCurve))
+    ));
+
+    let secret = Integer::from_str_radix(
+        "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16,
+    )
+    .unwrap();
+    let key = PrivateKey::try_new(secret).map_err(Error::from);
+    assert!(matches!(
+        key,
+        Err(Error::Secp256k1(Secp256k1Error::SecretOutOfRange))
+    ));
+}
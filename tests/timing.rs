@@ -0,0 +1,95 @@
+#![cfg(feature = "gmp")]
+// dudect-style smoke test for the constant-time claims in ecc::secp256k1::ct: time g_mul_ct
+// on two classes of scalars in random order, crop the slow outliers and compare the classes
+// with Welch's t-test. |t| above THRESHOLD means the timing depends on the class.
+// The same harness must flag plain double-and-add, or a pass would mean nothing.
+//
+// A statistical test on a shared machine, so the threshold is loose and it only catches gross
+// leaks. The default of 200 samples per run is a smoke check that stays quick under plain
+// cargo test. For a real measurement raise GC_TIMING_SAMPLES on a quiet machine, e.g.
+//
+//     GC_TIMING_SAMPLES=20000 cargo test --release --test timing
+use galactic_credit::ecc::curves::SECP256K1;
+use galactic_credit::ecc::secp256k1::ct::g_mul_ct;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+use rug::Integer;
+use std::hint::black_box;
+use std::time::Instant;
+
+// dudect's bound for "definitely not constant time"
+const THRESHOLD: f64 = 10.0;
+// measurements above this percentile of the run are dropped as interrupts and the like
+const CROP_PERCENTILE: f64 = 0.9;
+
+fn samples() -> usize {
+    std::env::var("GC_TIMING_SAMPLES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(200)
+}
+
+// class 0: sparse scalars, only the low byte random. Class 1: dense, n - 1 minus the same
+fn scalar(class: u8, rng: &mut ChaCha20Rng) -> Integer {
+    let low = Integer::from(rng.next_u32() & 0xff);
+    match class {
+        0 => low,
+        _ => SECP256K1.order() - 1u8 - low,
+    }
+}
+
+// Welch's t statistic between the two classes
+fn welch_t(times: &[(u8, u128)]) -> f64 {
+    let mut sorted: Vec<u128> = times.iter().map(|&(_, t)| t).collect();
+    sorted.sort_unstable();
+    let cutoff = sorted[(sorted.len() as f64 * CROP_PERCENTILE) as usize];
+
+    let stats = |class: u8| {
+        let values: Vec<f64> = times
+            .iter()
+            .filter(|&&(c, t)| c == class && t <= cutoff)
+            .map(|&(_, t)| t as f64)
+            .collect();
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var, n)
+    };
+    let (m0, v0, n0) = stats(0);
+    let (m1, v1, n1) = stats(1);
+    (m0 - m1) / (v0 / n0 + v1 / n1).sqrt()
+}
+
+fn measure(samples: usize, f: impl Fn(&Integer)) -> f64 {
+    let mut rng = ChaCha20Rng::seed_from_u64(189);
+    // warm up the lazily built tables before timing anything
+    f(&Integer::from(1));
+    let times: Vec<(u8, u128)> = (0..samples)
+        .map(|_| {
+            let class = (rng.next_u32() & 1) as u8;
+            let k = scalar(class, &mut rng);
+            let start = Instant::now();
+            f(black_box(&k));
+            (class, start.elapsed().as_nanos())
+        })
+        .collect();
+    welch_t(&times)
+}
+
+#[test]
+fn test_g_mul_ct_has_no_gross_timing_leak() {
+    let t = measure(samples(), |k| {
+        black_box(g_mul_ct(k));
+    });
+    assert!(t.abs() < THRESHOLD, "t = {t}");
+}
+
+#[test]
+fn test_harness_detects_double_and_add() {
+    // the leak is large and dense scalars are slow here, a tenth of the samples is plenty
+    let g = SECP256K1.generator();
+    let t = measure(samples().div_ceil(10).max(20), |k| {
+        black_box(k.clone() * &g);
+    });
+    assert!(t.abs() > THRESHOLD, "t = {t}");
+}